@@ -0,0 +1,53 @@
+//! Time per frame of the Viewer drawing a dataset of a real file, read through the slice model
+//! as in the TUI, with and without a block of cells selected.
+//!
+//! Run with `cargo bench --bench draw -- FILE DATASET`, by default `iinput/FsPEE` of
+//! `.data/database.hdf5`.
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use e2020_data_viewer::{
+    action::Action,
+    components::{app::App, Component},
+    config::Config,
+};
+use ratatui::{backend::TestBackend, Terminal};
+
+const WIDTH: u16 = 200;
+const HEIGHT: u16 = 60;
+const FRAMES: u32 = 50;
+
+/// Average time of drawing `app` over `FRAMES` frames, printed with `label`, after a first
+/// draw that lays out the columns that fit.
+fn time(label: &str, app: &mut App) -> Result<Duration> {
+    let mut terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT))?;
+    terminal.draw(|f| app.draw(f, f.size()))?;
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        terminal.draw(|f| app.draw(f, f.size()))?;
+    }
+    let elapsed = start.elapsed() / FRAMES;
+    println!("{label:<24} {elapsed:>12.2?} per frame");
+    Ok(elapsed)
+}
+
+fn main() -> Result<()> {
+    let mut args = std::env::args().skip(1).filter(|a| a != "--bench");
+    let file = args
+        .next()
+        .unwrap_or_else(|| "./.data/database.hdf5".to_string());
+    let dataset = args.next().unwrap_or_else(|| "iinput/FsPEE".to_string());
+    let mut app = App::new(file.clone(), Some(dataset.clone()), None, Config::new()?)?;
+    println!("{dataset} of {file} on a {WIDTH}x{HEIGHT} screen");
+
+    time("table", &mut app)?;
+
+    app.viewer.update(Action::ToggleBlockSelection)?;
+    for action in [Action::MoveSelectionNext, Action::MoveSelectionRight] {
+        for _ in 0..3 {
+            app.viewer.update(action.clone())?;
+        }
+    }
+    time("table with a block", &mut app)?;
+    Ok(())
+}
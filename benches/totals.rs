@@ -0,0 +1,60 @@
+//! Time per frame of the Viewer table of a large slice, with its totals computed on every draw
+//! and with them read from the cached slice model. Reading the slice from the file, which the
+//! cached model also saves on every draw, is left out; the `draw` bench times it on a real file.
+//!
+//! Run with `cargo bench --bench totals`.
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use e2020_data_viewer::components::viewer::{SliceModel, Viewer};
+use ndarray::Array2;
+use rand::Rng;
+
+const ROWS: usize = 2_000;
+const COLS: usize = 500;
+const FRAMES: u32 = 50;
+
+/// Average time of `frame` over `FRAMES` calls, printed with `label`.
+fn time(label: &str, mut frame: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        frame();
+    }
+    let elapsed = start.elapsed() / FRAMES;
+    println!("{label:<24} {elapsed:>12.2?} per frame");
+    elapsed
+}
+
+fn main() {
+    let viewer = Viewer::default();
+    let mut rng = rand::thread_rng();
+    let slice = Array2::from_shape_fn((COLS, ROWS), |_| rng.gen::<f64>());
+    println!("Slice of {ROWS} rows by {COLS} columns");
+
+    let recomputed = time("totals on every draw", || {
+        let table = viewer.with_totals(slice.clone(), None);
+        black_box(viewer.row_order(&table));
+    });
+
+    let model = SliceModel {
+        key: viewer.table_key(None),
+        table: viewer.with_totals(slice.clone(), None),
+        nrow: ROWS,
+        ncol: COLS,
+        kept_rows: None,
+        other_row: false,
+        row_keys: vec![],
+        missing: 0,
+    };
+    let cached = time("cached slice model", || {
+        assert!(model.key == viewer.table_key(None));
+        black_box(viewer.row_order(&model.table));
+    });
+
+    println!(
+        "{:.1}x faster with the model",
+        recomputed.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -1,6 +1,9 @@
 use crossterm::event::KeyEvent;
 
-use crate::components::app::Mode as HomeMode;
+use crate::{
+    components::{app::Mode as HomeMode, viewer::SliceFormat},
+    search::Match,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Action {
@@ -15,6 +18,8 @@ pub enum Action {
     MoveSelectionPageUp,
     MoveSelectionRight,
     MoveSelectionLeft,
+    MoveSelectionPageRight,
+    MoveSelectionPageLeft,
     MoveSelectionBottom,
     MoveSelectionTop,
     Pause(HomeMode),
@@ -25,15 +30,110 @@ pub enum Action {
     Render,
     Resume,
     EnterSubset,
+    NextSet,
+    PreviousSet,
     UnPause,
     Refresh,
     NextAxis(usize),
     PreviousAxis(usize),
+    SetIndex(usize, usize),
+    StepScrubbed(bool),
+    CycleAggregation(usize),
     IncrementAxis(usize),
     DecrementAxis(usize),
-    Resize { x: u16, y: u16 },
+    SwapAxes,
+    NavigateBack,
+    NavigateForward,
+    EnterPivot,
+    Pivot(usize, usize, Option<usize>),
+    ToggleSortByTotal,
+    ToggleRollups,
+    ToggleTimings,
+    ToggleLongLabels,
+    ToggleSummary,
+    ScrollSummary(i16),
+    EnterFrames,
+    FileError(String),
+    Error(String),
+    RetryOpen,
+    OpenCopy,
+    OpenFile(String),
+    ExportFrames(usize, bool),
+    ExportView(bool),
+    ExportSlice(SliceFormat, bool),
+    ExportChart(bool),
+    RunHook,
+    ColumnStats,
+    ShowProblems,
+    OpenMarked,
+    OpenCombination(String),
+    ToggleBlockSelection,
+    TogglePinRow,
+    ToggleGroupSmallRows,
+    EnterTopRows,
+    TopRows(String),
+    EnterColumnRange,
+    ColumnRange(String),
+    EnterGoToColumn,
+    GoToColumn(String),
+    EnterGoToRow,
+    GoToRow(String),
+    CopyBlock,
+    ExportBlock(bool),
+    EnterBookmark,
+    AddBookmark(String),
+    EnterBookmarks,
+    JumpToBookmark(usize),
+    DeleteBookmark,
+    EnterWeights,
+    EnterHeatmap,
+    EnterHistogram,
+    ChooseWeights(Vec<String>),
+    SetWeights(String),
+    EnterExpression,
+    OpenExpression(String),
+    EnterDiff,
+    DiffFiles(String),
+    EnterCompareFile,
+    CompareFile(String),
+    SaveEdits,
+    EnterTransform,
+    TransformCells(String),
+    ApplyTransform,
+    EnterSaveDataset,
+    SaveDataset(String),
+    EnterAttributes,
+    DiscardEdits,
+    ShowOutput(String, String, bool),
+    Confirm(String, Box<Action>),
+    Resize {
+        x: u16,
+        y: u16,
+    },
+    /// Write the screen to a file, with ANSI colours if true
+    ExportScreen(bool),
+    Notify(String),
+    IncreaseRates,
+    DecreaseRates,
     Suspend,
     SwitchModeToViewer(usize),
+    NextInGroup,
+    CycleSortColumn,
+    EnterSearch,
+    Search(String),
+    SearchResults(Vec<Match>),
+    ShowSearch,
+    OpenMatch(String, Vec<Option<usize>>),
+    ShowTasks,
+    CancelTask(usize),
+    ShowLog,
+    NextFile,
+    PreviousFile,
+    CycleLogLevel,
+    DumpLog,
+    CycleTotalPlacement,
+    ReverseSort,
+    PreviousInGroup,
     SwitchModeToPicker,
     SwitchModeToHelp,
     SwitchModeToPreviousMode,
@@ -41,7 +141,21 @@ pub enum Action {
     ToggleTree,
     ToggleSelection,
     ToggleAllSelection,
-    ToggleFormattedData,
+    EnterFormatting,
+    ToggleFormatting(usize),
+    CycleUnitScale,
+    ToggleCumulative,
+    IncreaseDecimals,
+    DecreaseDecimals,
+    ToggleReference(usize),
+    CycleComparison,
     ViewerSliceRefresh,
+    ReadLargeSlice,
     ReloadData,
+    CancelScan,
+    ExportMarked,
+    UnmarkAll,
+    RunPlugin,
+    ShowSparsity,
+    ExportedMarked(String),
 }
@@ -0,0 +1,156 @@
+use std::fmt::Write;
+
+/// Size of the whole figure and margins around the plot, in pixels.
+const WIDTH: f64 = 800.0;
+const HEIGHT: f64 = 450.0;
+const LEFT: f64 = 80.0;
+const RIGHT: f64 = 180.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 60.0;
+/// Most labels written along the horizontal axis.
+const MAX_X_LABELS: usize = 12;
+
+/// Okabe-Ito colours, distinguishable with common colour vision deficiencies.
+const COLOURS: [&str; 8] = [
+    "#0072b2", "#e69f00", "#009e73", "#cc79a7", "#56b4e9", "#d55e00", "#f0e442", "#000000",
+];
+
+/// Lines of `series` along `labels`, as an SVG figure with axes and a legend.
+///
+/// Missing values leave gaps in the lines.
+pub fn line_svg(
+    title: &str,
+    units: &str,
+    labels: &[String],
+    series: &[(String, Vec<f64>)],
+) -> String {
+    let values = series.iter().flat_map(|(_, v)| v).filter(|v| v.is_finite());
+    let (min, max) = values.fold((0.0_f64, 0.0_f64), |(lo, hi), &v| (lo.min(v), hi.max(v)));
+    let ticks = ticks(min, max);
+    let (lo, hi) = (ticks[0], ticks[ticks.len() - 1]);
+    let decimals = (-(ticks[1] - ticks[0]).log10().floor()).max(0.0) as usize;
+    let (plot_width, plot_height) = (WIDTH - LEFT - RIGHT, HEIGHT - TOP - BOTTOM);
+    let x = |i: usize| LEFT + plot_width * (i as f64 + 0.5) / labels.len().max(1) as f64;
+    let y = |v: f64| TOP + plot_height * (hi - v) / (hi - lo);
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{HEIGHT}\" \
+         font-family=\"sans-serif\" font-size=\"12\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+         <text x=\"{LEFT}\" y=\"24\" font-size=\"14\" font-weight=\"bold\">{}</text>\n",
+        escape(title)
+    );
+    for t in ticks.iter() {
+        writeln!(
+            svg,
+            "<line x1=\"{LEFT}\" x2=\"{}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#dddddd\"/>\n\
+             <text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{t:.decimals$}</text>",
+            LEFT + plot_width,
+            LEFT - 6.0,
+            y(*t) + 4.0,
+            y = y(*t),
+        )
+        .unwrap_or_default();
+    }
+    if !units.is_empty() {
+        writeln!(
+            svg,
+            "<text x=\"16\" y=\"{:.1}\" transform=\"rotate(-90 16 {0:.1})\" \
+             text-anchor=\"middle\">{}</text>",
+            TOP + plot_height / 2.0,
+            escape(units)
+        )
+        .unwrap_or_default();
+    }
+    let step = labels.len().div_ceil(MAX_X_LABELS).max(1);
+    for (i, label) in labels.iter().enumerate().step_by(step) {
+        writeln!(
+            svg,
+            "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{}</text>",
+            x(i),
+            TOP + plot_height + 20.0,
+            escape(label)
+        )
+        .unwrap_or_default();
+    }
+    for (k, (name, values)) in series.iter().enumerate() {
+        let colour = COLOURS[k % COLOURS.len()];
+        // One polyline per run of values, so missing ones leave a gap
+        for run in values
+            .iter()
+            .enumerate()
+            .collect::<Vec<_>>()
+            .split(|(_, v)| !v.is_finite())
+            .filter(|run| !run.is_empty())
+        {
+            let points: Vec<String> = run
+                .iter()
+                .map(|&(i, &v)| format!("{:.1},{:.1}", x(i), y(v)))
+                .collect();
+            writeln!(
+                svg,
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{colour}\" stroke-width=\"2\"/>",
+                points.join(" ")
+            )
+            .unwrap_or_default();
+            if let [(i, &v)] = run[..] {
+                writeln!(
+                    svg,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{colour}\"/>",
+                    x(i),
+                    y(v)
+                )
+                .unwrap_or_default();
+            }
+        }
+        let legend_y = TOP + 10.0 + 20.0 * k as f64;
+        writeln!(
+            svg,
+            "<line x1=\"{}\" x2=\"{}\" y1=\"{legend_y}\" y2=\"{legend_y}\" stroke=\"{colour}\" \
+             stroke-width=\"2\"/>\n<text x=\"{}\" y=\"{}\">{}</text>",
+            WIDTH - RIGHT + 16.0,
+            WIDTH - RIGHT + 36.0,
+            WIDTH - RIGHT + 42.0,
+            legend_y + 4.0,
+            escape(name)
+        )
+        .unwrap_or_default();
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Round values from at most `min` to at least `max`, about five of them.
+fn ticks(min: f64, max: f64) -> Vec<f64> {
+    if max <= min {
+        return vec![min - 1.0, min, min + 1.0];
+    }
+    let rough = (max - min) / 5.0;
+    let magnitude = 10_f64.powf(rough.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|s| *s >= rough)
+        .unwrap_or(10.0 * magnitude);
+    let first = (min / step).floor() as i64;
+    let last = (max / step).ceil() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks() {
+        assert_eq!(ticks(0.0, 9.0), vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(ticks(-30.0, 120.0), vec![-50.0, 0.0, 50.0, 100.0, 150.0]);
+        assert_eq!(ticks(0.0, 0.0), vec![-1.0, 0.0, 1.0]);
+    }
+}
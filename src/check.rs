@@ -0,0 +1,119 @@
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use hdf5::{
+    types::{TypeDescriptor, VarLenUnicode},
+    Dataset,
+};
+use ndarray::{IxDyn, SliceInfo, SliceInfoElem};
+
+use crate::data::{self, Data};
+
+/// Number of elements read from each dataset along its last dimension.
+const SAMPLE_SIZE: usize = 10;
+
+/// An object of the file that could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub path: String,
+    pub error: String,
+}
+
+/// Summary of an integrity check of a whole file.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub datasets: usize,
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    fn problem(&mut self, path: &str, error: impl ToString) {
+        self.problems.push(Problem {
+            path: path.into(),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Walks every group and dataset of `file`, opening each one and reading a small sample.
+///
+/// Only failing to open the file itself is an error; everything else is collected in the report.
+pub fn check(file: &Path) -> Result<Report> {
+    let f = data::open_file(file)?;
+    let mut report = Report::default();
+    let groups = match f.member_names() {
+        Ok(groups) => groups,
+        Err(e) => {
+            report.problem("/", e);
+            return Ok(report);
+        }
+    };
+    for group in groups {
+        let names = match f.group(&group).and_then(|g| g.member_names()) {
+            Ok(names) => names,
+            Err(e) => {
+                report.problem(&group, e);
+                continue;
+            }
+        };
+        for name in names {
+            let path = format!("{group}/{name}");
+            report.datasets += 1;
+            if let Err(e) = check_dataset(file, &f, &path) {
+                report.problem(&path, e);
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn check_dataset(file: &Path, f: &hdf5::File, path: &str) -> Result<()> {
+    let dataset = f.dataset(path)?;
+    // Variables carry their sets and metadata, which the Viewer needs as well
+    if dataset.attr("dims").is_ok() {
+        Data::new(file.into(), path.into())?;
+    }
+    sample(&dataset)
+}
+
+/// Reads the first few elements of `dataset`.
+fn sample(dataset: &Dataset) -> Result<()> {
+    let shape = dataset.shape();
+    if shape.iter().product::<usize>() == 0 {
+        return Ok(());
+    }
+    let slices: Vec<SliceInfoElem> = shape
+        .iter()
+        .enumerate()
+        .map(|(i, &n)| {
+            if i + 1 == shape.len() {
+                SliceInfoElem::Slice {
+                    start: 0,
+                    end: Some(n.min(SAMPLE_SIZE) as isize),
+                    step: 1,
+                }
+            } else {
+                SliceInfoElem::Index(0)
+            }
+        })
+        .collect();
+    let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
+    match dataset.dtype()?.to_descriptor()? {
+        TypeDescriptor::Integer(_) | TypeDescriptor::Unsigned(_) | TypeDescriptor::Float(_) => {
+            if shape.is_empty() {
+                dataset.read_scalar::<f64>()?;
+            } else {
+                dataset.read_slice_1d::<f64, _>(s)?;
+            }
+        }
+        TypeDescriptor::VarLenUnicode => {
+            if shape.is_empty() {
+                dataset.read_scalar::<VarLenUnicode>()?;
+            } else {
+                dataset.read_slice_1d::<VarLenUnicode, _>(s)?;
+            }
+        }
+        _ => (),
+    }
+    Ok(())
+}
@@ -12,11 +12,28 @@ use crate::{
 };
 
 pub mod app;
+pub mod attributes;
+pub mod bookmarks;
+pub mod browser;
+pub mod confirm;
+pub mod file_error;
+pub mod formatting;
+pub mod frames;
+pub mod heatmap;
 pub mod help;
+pub mod histogram;
+pub mod logs;
+pub mod notifications;
+pub mod output;
 pub mod picker;
+pub mod pivot;
+pub mod prompt;
+pub mod search;
 pub mod select;
 pub mod summary;
+pub mod tasks;
 pub mod viewer;
+pub mod weights;
 
 pub trait Component {
     fn init(&mut self) -> Result<()> {
@@ -41,5 +58,9 @@ pub trait Component {
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         Ok(None)
     }
+    /// Whether the screen changes without any action, e.g. while loading in the background.
+    fn needs_redraw(&self) -> bool {
+        false
+    }
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect);
 }
@@ -11,11 +11,38 @@ use ratatui::{
 
 use crate::{
     action::Action,
-    components::{help::Help, picker::Picker, viewer::Viewer, Component, Frame},
-    data::Data,
+    components::{
+        attributes::Attributes,
+        confirm::Confirm,
+        file_error::{self, FileError},
+        help::Help,
+        logs::LogView,
+        notifications::Notifications,
+        output::Output,
+        picker::{self, Picker},
+        prompt::Prompt,
+        search::Search,
+        tasks::TaskList,
+        viewer::{self, Viewer},
+        Component, Frame,
+    },
+    compute,
+    config::{Config, Session},
+    data::{self, Data},
+    diff, export,
+    i18n::tr,
+    journal::Journal,
+    keymap::{self, Context, Leader, Sequence},
+    palette,
+    search::{self, Query},
+    sparsity,
+    tasks::{self, Tasks},
     trace_dbg, tui,
     tui::{key_event_to_string, Event},
+    utils::run_command,
+    view::ViewDefinition,
 };
+use tokio::sync::mpsc::UnboundedSender;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
@@ -24,6 +51,14 @@ pub enum Mode {
     Viewer(String),
     Waiting,
     Help,
+    FileError,
+    Confirm,
+    Prompt,
+    Attributes,
+    Search,
+    Tasks,
+    Log,
+    Output,
 }
 
 #[derive(Default)]
@@ -31,29 +66,63 @@ pub struct App {
     pub mode: Mode,
     pub previous_mode: Mode,
     pub file: String,
+    /// Files given on the command line, switched between in order
+    pub files: Vec<String>,
+    pub config: Config,
     pub picker: Picker,
     pub viewer: Viewer,
     pub help: Help,
+    pub file_error: FileError,
+    pub confirm: Confirm,
+    pub output: Output,
+    pub prompt: Prompt,
+    pub attributes: Attributes,
+    pub search: Search,
+    /// Id in `tasks` of the last search
+    pub search_task: Option<usize>,
+    pub tasks: Tasks,
+    pub task_list: TaskList,
+    pub log_view: LogView,
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub notifications: Notifications,
+    pub leader: Leader,
     pub last_event: String,
 }
 
 impl App {
-    pub fn new(file: String, dataset: Option<String>) -> Result<Self> {
+    pub fn new(
+        file: String,
+        dataset: Option<String>,
+        view: Option<ViewDefinition>,
+        config: Config,
+    ) -> Result<Self> {
         if !PathBuf::from(file.clone()).exists() {
             return Err(color_eyre::eyre::eyre!("Unable to find {file:?}"));
         }
         let mut s = Self {
             file,
+            config,
             ..Default::default()
         };
+        s.viewer.pending_view = view;
+        s.picker.tasks = s.tasks.clone();
+        s.viewer.tasks = s.tasks.clone();
+        s.task_list.tasks = s.tasks.clone();
         if let Some(name) = dataset {
-            if hdf5::File::open(s.file.clone())
-                .expect("Unable to find file")
-                .dataset(&name)
-                .is_ok()
+            let f = match data::open_file(&s.file) {
+                Ok(f) => f,
+                Err(e) => {
+                    s.file_error.refresh(&s.file, e.to_string());
+                    s.mode = Mode::FileError;
+                    return Ok(s);
+                }
+            };
+            if f.dataset(name.trim()).is_ok()
+                || compute::is_combination(&name)
+                || compute::is_expression(&name)
             {
                 s.mode = Mode::Viewer(name);
-                s.init().unwrap();
+                s.init()?;
             } else {
                 return Err(color_eyre::eyre::eyre!(
                     "Unable to load {:?} from {:?}. Are you sure {:?} exists in the file?",
@@ -68,18 +137,277 @@ impl App {
 
     pub fn quit(&mut self) {
         self.picker.cancel();
+        match self.viewer.journal.save() {
+            Ok(Some(path)) => log::info!("Saved journal to {}", path.display()),
+            Ok(None) => (),
+            Err(e) => log::error!("Unable to save journal: {e}"),
+        }
     }
 
     pub fn tick(&mut self) -> Result<()> {
         Ok(())
     }
+
+    /// Key bindings that are active in the current mode.
+    pub fn context(&self) -> Context {
+        match self.mode {
+            Mode::Picker | Mode::Waiting => match self.picker.mode {
+                picker::Mode::Normal => Context::Picker,
+                picker::Mode::Editing => Context::PickerEditing,
+            },
+            Mode::Viewer(_) => match self.viewer.mode {
+                viewer::Mode::Selection => Context::ViewerSelection,
+                viewer::Mode::Pivot => Context::ViewerPivot,
+                viewer::Mode::Frames => Context::ViewerFrames,
+                viewer::Mode::Bookmarks => Context::ViewerBookmarks,
+                viewer::Mode::Weights => Context::ViewerWeights,
+                viewer::Mode::Formatting => Context::ViewerFormatting,
+                viewer::Mode::Heatmap => Context::ViewerHeatmap,
+                viewer::Mode::Histogram => Context::ViewerHistogram,
+                viewer::Mode::Editing => Context::ViewerEditing,
+                _ => Context::Viewer,
+            },
+            Mode::Help => Context::Help,
+            Mode::FileError => Context::FileError,
+            Mode::Confirm => Context::Confirm,
+            Mode::Prompt => Context::Prompt,
+            Mode::Attributes => Context::Attributes,
+            Mode::Search => Context::Search,
+            Mode::Tasks => Context::Tasks,
+            Mode::Log => Context::Log,
+            Mode::Output => Context::Output,
+        }
+    }
+
+    /// Compares the open file with `other` in the background, showing the report once done.
+    fn diff_files(&mut self, other: String) {
+        let (first, action_tx) = (PathBuf::from(&self.file), self.action_tx.clone());
+        let tolerance = diff::Tolerance::default();
+        let task = self.tasks.start(tr("Comparing files"), 0);
+        tokio::task::spawn_blocking(move || {
+            let title = format!("Differences with {other}");
+            let action = match diff::diff(&first, other.as_ref(), tolerance) {
+                Ok(report) => Action::ShowOutput(title, report.to_text(), true),
+                Err(e) => Action::ShowOutput(title, e.to_string(), false),
+            };
+            if task.is_cancelled() {
+                return;
+            }
+            if let Some(tx) = action_tx {
+                tx.send(action).unwrap_or_default();
+            }
+        });
+    }
+
+    /// Searches the marked datasets of the Picker, or all of them, in the background.
+    fn search_datasets(&mut self, query: Query) {
+        let datasets: Vec<Data> = {
+            let all = self.picker.datasets.lock().unwrap();
+            let mut marked: Vec<usize> = self.picker.marked().cloned().collect();
+            marked.sort();
+            if marked.is_empty() {
+                all.clone()
+            } else {
+                marked.iter().filter_map(|&i| all.get(i).cloned()).collect()
+            }
+        };
+        let action_tx = self.action_tx.clone();
+        let task = self.tasks.start(tr("Searching"), 0);
+        self.search_task = Some(task.id());
+        tokio::task::spawn_blocking(move || {
+            let matches = search::search(&datasets, &query);
+            if task.is_cancelled() {
+                return;
+            }
+            if let Some(tx) = action_tx {
+                tx.send(Action::SearchResults(matches)).unwrap_or_default();
+            }
+        });
+    }
+
+    /// Exports the marked datasets of the Picker to CSV files in a temporary directory, then runs
+    /// the configured plugin with their paths as arguments, in the background, and shows its
+    /// output once it exits. The files are removed afterwards.
+    fn run_plugin(&mut self) -> Result<()> {
+        let Some(command) = self.config.plugin.clone() else {
+            bail!("No plugin configured, set \"plugin\" in config.json");
+        };
+        let mut args = shlex::split(&command)
+            .filter(|args| !args.is_empty())
+            .ok_or_else(|| color_eyre::eyre::eyre!("Invalid plugin command {command:?}"))?;
+        let datasets: Vec<Data> = {
+            let all = self.picker.datasets.lock().unwrap();
+            let mut marked: Vec<usize> = self.picker.marked().cloned().collect();
+            marked.sort();
+            marked.iter().filter_map(|&i| all.get(i).cloned()).collect()
+        };
+        if datasets.is_empty() {
+            bail!("No datasets marked, press v to mark some");
+        }
+        let dir = std::env::temp_dir().join(format!(
+            "e2020-plugin-{}-{}",
+            std::process::id(),
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let env = [
+            ("E2020_FILE", self.file.clone()),
+            (
+                "E2020_DATASETS",
+                datasets
+                    .iter()
+                    .map(|d| d.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        ];
+        let action_tx = self.action_tx.clone();
+        let task = self.tasks.start(
+            tr("Running plugin"),
+            datasets
+                .iter()
+                .map(|d| d.shape.iter().product::<usize>())
+                .sum(),
+        );
+        tokio::task::spawn_blocking(move || {
+            let mut run = || -> Result<(String, String, bool)> {
+                for d in &datasets {
+                    let path = export::csv_path(&dir, d);
+                    if !export::write_csv(d, &path, &task)? {
+                        bail!("Cancelled");
+                    }
+                    args.push(path.display().to_string());
+                }
+                let output = run_command(&args, &env, String::default())?;
+                Ok((
+                    format!("{command}: {}", output.status),
+                    String::from_utf8_lossy(&output.stdout).to_string()
+                        + &String::from_utf8_lossy(&output.stderr),
+                    output.status.success(),
+                ))
+            };
+            let (title, text, success) = run()
+                .unwrap_or_else(|e| (format!("{command}: failed to run"), e.to_string(), false));
+            std::fs::remove_dir_all(&dir).unwrap_or_default();
+            if task.is_cancelled() {
+                return;
+            }
+            if let Some(tx) = action_tx {
+                tx.send(Action::ShowOutput(title, text, success))
+                    .unwrap_or_default();
+            }
+        });
+        Ok(())
+    }
+
+    /// Reports the zero and missing cells of the dataset highlighted in the Picker, and the
+    /// members of its sets with no data, in the background.
+    fn sparsity_report(&mut self) {
+        let Some(d) = self.picker.state.selected().and_then(|i| {
+            let k = *self.picker.filtered_indices.get(i)?;
+            self.picker.datasets.lock().unwrap().get(k).cloned()
+        }) else {
+            return;
+        };
+        let action_tx = self.action_tx.clone();
+        let task = self.tasks.start(tr("Measuring sparsity"), 0);
+        tokio::task::spawn_blocking(move || {
+            let title = format!("Sparsity of {}", d.name);
+            let action = match sparsity::sparsity(&d) {
+                Ok(report) => Action::ShowOutput(title, report.to_text(), true),
+                Err(e) => Action::ShowOutput(title, e.to_string(), false),
+            };
+            if task.is_cancelled() {
+                return;
+            }
+            if let Some(tx) = action_tx {
+                tx.send(action).unwrap_or_default();
+            }
+        });
+    }
+
+    /// Writes the units and documentation typed in the attribute editor to the file, and shows
+    /// them in the Picker and Viewer.
+    fn save_attributes(&mut self) -> Action {
+        let (name, (units, doc)) = (self.attributes.name.clone(), self.attributes.values());
+        let written = data::open_file_rw(&self.file)
+            .and_then(|f| data::write_attributes(&f, &name, &units, &doc));
+        if let Err(e) = written {
+            return Action::Error(format!("Unable to edit the attributes of {name}: {e}"));
+        }
+        let mut datasets = self.picker.datasets.lock().unwrap();
+        for d in datasets
+            .iter_mut()
+            .chain(self.viewer.data.as_mut())
+            .filter(|d| d.name == name)
+        {
+            d.units.clone_from(&units);
+            d.doc.clone_from(&doc);
+        }
+        Action::Notify(format!("Updated the units and doc of {name}"))
+    }
+
+    /// Group of the dataset in the Viewer, or of the one selected in the Picker, and its number
+    /// of datasets.
+    fn current_group(&self) -> Option<(String, usize)> {
+        let name = match self.mode {
+            Mode::Viewer(ref name) => name.clone(),
+            Mode::Picker => {
+                let i = *self
+                    .picker
+                    .filtered_indices
+                    .get(self.picker.state.selected()?)?;
+                self.picker.datasets.lock().unwrap().get(i)?.name.clone()
+            }
+            _ => return None,
+        };
+        self.picker.group_of(&name)
+    }
+
+    /// Asks before quitting while datasets are still being scanned or exported, or edited
+    /// cells are not saved.
+    fn confirm_quit(&self, action: Option<Action>) -> Option<Action> {
+        if action != Some(Action::Quit) {
+            return action;
+        }
+        if !self.viewer.edits.is_empty() {
+            return Some(Action::Confirm(
+                format!(
+                    "{} edited cells are not saved. Quit anyway?",
+                    self.viewer.edits.len()
+                ),
+                Box::new(Action::Quit),
+            ));
+        }
+        if self.tasks.busy() {
+            return Some(Action::Confirm(
+                "Tasks are still running in the background. Quit anyway?".into(),
+                Box::new(Action::Quit),
+            ));
+        }
+        action
+    }
 }
 
 impl Component for App {
+    fn needs_redraw(&self) -> bool {
+        self.picker.needs_redraw()
+            || self.viewer.needs_redraw()
+            || !self.notifications.items.is_empty()
+            // The footer animates a spinner
+            || self.tasks.busy()
+            // The footer shows the session time
+            || self.viewer.journal.enabled
+    }
+
     fn init(&mut self) -> Result<()> {
         log::debug!("********** Inside App.init() **************");
         self.picker.file.clone_from(&self.file);
         self.viewer.file.clone_from(&self.file);
+        self.viewer.config.clone_from(&self.config);
+        if self.config.journal && !self.viewer.journal.enabled {
+            self.viewer.journal = Journal::new(&self.file);
+        }
         match self.mode {
             Mode::Picker => self.picker.init(),
             Mode::Viewer(ref s) => {
@@ -98,6 +426,7 @@ impl Component for App {
         &mut self,
         tx: tokio::sync::mpsc::UnboundedSender<Action>,
     ) -> Result<()> {
+        self.action_tx = Some(tx.clone());
         self.picker.register_action_handler(tx.clone())?;
         self.viewer.register_action_handler(tx)?;
         Ok(())
@@ -115,15 +444,99 @@ impl Component for App {
         if let Event::Key(key_event) = event.clone() {
             self.last_event = key_event_to_string(&key_event);
         }
-        match self.mode {
+        if let Event::Key(key_event) = event {
+            if let Sequence::Consumed(action) =
+                self.leader.handle_key_event(self.context(), key_event)
+            {
+                return self.confirm_quit(action);
+            }
+        }
+        let action = match self.mode {
             Mode::Picker => self.picker.handle_events(event),
             Mode::Viewer(_) => self.viewer.handle_events(event),
             Mode::Help => self.help.handle_events(event),
+            Mode::FileError => self.file_error.handle_events(event),
+            Mode::Confirm => self.confirm.handle_events(event),
+            Mode::Prompt => self.prompt.handle_events(event),
+            Mode::Attributes => self.attributes.handle_events(event),
+            Mode::Search => self.search.handle_events(event),
+            Mode::Tasks => self.task_list.handle_events(event),
+            Mode::Log => self.log_view.handle_events(event),
+            Mode::Output => self.output.handle_events(event),
             Mode::Waiting => None,
-        }
+        };
+        self.confirm_quit(action)
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if self.mode == Mode::Confirm {
+            match action {
+                Action::SubmitSelection => {
+                    self.mode = self.confirm.previous_mode.clone();
+                    return Ok(self.confirm.action.take());
+                }
+                Action::EnterNormal => {
+                    self.mode = self.confirm.previous_mode.clone();
+                    self.confirm.action = None;
+                    return Ok(None);
+                }
+                _ => (),
+            }
+        }
+        if self.mode == Mode::Attributes {
+            match action {
+                Action::EnterNormal => {
+                    self.mode = self.attributes.previous_mode.clone();
+                    return Ok(None);
+                }
+                Action::SubmitSelection => {
+                    // Text that cannot be written stays in the popup to be fixed
+                    let saved = self.save_attributes();
+                    if !matches!(saved, Action::Error(_)) {
+                        self.mode = self.attributes.previous_mode.clone();
+                    }
+                    return Ok(Some(saved));
+                }
+                _ => (),
+            }
+        }
+        if self.mode == Mode::Output && action == Action::EnterNormal {
+            self.mode = self.output.previous_mode.clone();
+            return Ok(None);
+        }
+        if self.mode == Mode::Search && action == Action::EnterNormal {
+            self.mode = Mode::Picker;
+            return Ok(None);
+        }
+        if self.mode == Mode::Tasks && action == Action::EnterNormal {
+            self.mode = self.task_list.previous_mode.clone();
+            return Ok(None);
+        }
+        if self.mode == Mode::Log && action == Action::EnterNormal {
+            self.mode = self.log_view.previous_mode.clone();
+            return Ok(None);
+        }
+        if self.mode == Mode::Prompt
+            && matches!(
+                action,
+                Action::EnterNormal
+                    | Action::DiffFiles(_)
+                    | Action::CompareFile(_)
+                    | Action::TransformCells(_)
+                    | Action::SaveDataset(_)
+                    | Action::AddBookmark(_)
+                    | Action::TopRows(_)
+                    | Action::ColumnRange(_)
+                    | Action::GoToColumn(_)
+                    | Action::GoToRow(_)
+                    | Action::Search(_)
+            )
+        {
+            self.mode = self.prompt.previous_mode.clone();
+            if action == Action::EnterNormal {
+                return Ok(None);
+            }
+        }
         match action {
             Action::Init => self.init()?,
             Action::Quit => self.quit(),
@@ -134,14 +547,350 @@ impl Component for App {
             }
             Action::UnPause => self.mode = self.previous_mode.clone(),
             Action::SwitchModeToViewer(i) => {
+                // From another dataset of the same group
+                if matches!(self.mode, Mode::Viewer(_)) {
+                    self.viewer.reset();
+                }
                 let d = self.picker.datasets.lock().unwrap()[i].clone();
+                if let Err(e) = Session::new(&self.file, &d.name).save() {
+                    log::warn!("Unable to save session: {e}");
+                }
                 self.previous_mode = self.mode.clone();
                 self.mode = Mode::Viewer(d.name.clone());
             }
+            Action::NextInGroup | Action::PreviousInGroup => {
+                let forward = action == Action::NextInGroup;
+                let Some(i) = self.picker.neighbour_in_group(&self.viewer.name, forward) else {
+                    return Ok(Some(Action::Notify(
+                        "No other dataset in this group".into(),
+                    )));
+                };
+                if !self.viewer.edits.is_empty() {
+                    return Ok(Some(Action::Confirm(
+                        format!(
+                            "{} edited cells are not saved. Open another dataset anyway?",
+                            self.viewer.edits.len()
+                        ),
+                        Box::new(Action::SwitchModeToViewer(i)),
+                    )));
+                }
+                return Ok(Some(Action::SwitchModeToViewer(i)));
+            }
             Action::SwitchModeToPicker => {
                 self.previous_mode = self.mode.clone();
                 self.mode = Mode::Picker;
             }
+            Action::FileError(ref message) => {
+                self.file_error.refresh(&self.file, message.clone());
+                self.previous_mode = self.mode.clone();
+                self.mode = Mode::FileError;
+            }
+            Action::Confirm(ref message, ref confirmed) => {
+                self.confirm
+                    .refresh(message.clone(), *confirmed.clone(), self.mode.clone());
+                self.mode = Mode::Confirm;
+            }
+            Action::ShowOutput(ref title, ref text, success) => {
+                let previous_mode = match self.mode {
+                    Mode::Output => self.output.previous_mode.clone(),
+                    ref mode => mode.clone(),
+                };
+                self.output
+                    .refresh(title.clone(), text.clone(), success, previous_mode);
+                self.mode = Mode::Output;
+            }
+            Action::EnterDiff => {
+                self.prompt.refresh(
+                    tr("Compare with file"),
+                    &self.file,
+                    Action::DiffFiles,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::DiffFiles(ref other) => self.diff_files(other.clone()),
+            Action::EnterCompareFile => {
+                let other = self.viewer.other_file.as_ref().unwrap_or(&self.file);
+                self.prompt.refresh(
+                    tr("Show the dataset side by side with file, empty to stop"),
+                    other,
+                    Action::CompareFile,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterTransform | Action::EnterSaveDataset | Action::EnterAttributes
+                if self.config.read_only_reason(&self.file).is_some() =>
+            {
+                let reason = self.config.read_only_reason(&self.file).unwrap_or_default();
+                return Ok(Some(Action::Notify(tr(reason).into())));
+            }
+            Action::EnterTransform => {
+                let title = if self.viewer.anchor.is_some() {
+                    "Change the selected cells: *factor, =value or 0"
+                } else {
+                    "Change every cell of the slice: *factor, =value or 0"
+                };
+                self.prompt
+                    .refresh(tr(title), "", Action::TransformCells, self.mode.clone());
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterAttributes => {
+                let d = match self.mode {
+                    Mode::Viewer(_) => self.viewer.data.clone(),
+                    _ => self.picker.state.selected().and_then(|i| {
+                        let k = *self.picker.filtered_indices.get(i)?;
+                        self.picker.datasets.lock().unwrap().get(k).cloned()
+                    }),
+                };
+                let Some(d) = d else {
+                    return Ok(None);
+                };
+                if d.expression.is_some() || d.combination.is_some() {
+                    return Ok(Some(Action::Notify(
+                        "Computed datasets have no attributes to edit".into(),
+                    )));
+                }
+                self.attributes
+                    .refresh(&d.name, &d.units, &d.doc, self.mode.clone());
+                self.mode = Mode::Attributes;
+            }
+            Action::EnterSaveDataset => {
+                // In the group of the dataset, or of the first operand of a computed one
+                let group = self
+                    .viewer
+                    .data
+                    .as_ref()
+                    .and_then(|d| {
+                        let name = d.dataset.name();
+                        Some(name.trim_matches('/').split_once('/')?.0.to_string())
+                    })
+                    .unwrap_or_default();
+                self.prompt.refresh(
+                    tr("Save as the new dataset"),
+                    &format!("{group}/"),
+                    Action::SaveDataset,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterTopRows => {
+                let n = match self.viewer.row_filter {
+                    viewer::RowFilter::Top(n) => n,
+                    _ => self.viewer.config.top_rows,
+                };
+                self.prompt.refresh(
+                    tr("Number of rows to show, 0 for all"),
+                    &n.to_string(),
+                    Action::TopRows,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterColumnRange => {
+                let columns = match (self.viewer.anchor, self.viewer.block()) {
+                    (Some(_), Some((_, cols))) => Some(cols),
+                    _ => self.viewer.column_range.clone(),
+                };
+                let labels = self.viewer.column_labels();
+                let value = match columns {
+                    Some(cols) if cols.len() > 1 => labels
+                        .get(cols.start)
+                        .zip(labels.get(cols.end - 1))
+                        .map(|(first, last)| format!("{first}-{last}"))
+                        .unwrap_or_default(),
+                    Some(cols) => labels.get(cols.start).cloned().unwrap_or_default(),
+                    None => String::default(),
+                };
+                self.prompt.refresh(
+                    tr("Columns to export, e.g. 2025-2035, empty for all"),
+                    &value,
+                    Action::ColumnRange,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterGoToColumn => {
+                self.prompt.refresh(
+                    tr("Go to column"),
+                    "",
+                    Action::GoToColumn,
+                    self.mode.clone(),
+                );
+                self.prompt.completions = self.viewer.column_labels();
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterGoToRow => {
+                self.prompt
+                    .refresh(tr("Go to row"), "", Action::GoToRow, self.mode.clone());
+                self.prompt.completions = self.viewer.row_names();
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterExpression => {
+                let value = match self.mode {
+                    Mode::Viewer(ref name) => name.clone(),
+                    _ => String::default(),
+                };
+                self.prompt.refresh(
+                    tr("Expression, e.g. ooutput/Emissions / ooutput/Demand"),
+                    &value,
+                    |text| {
+                        if text.is_empty() {
+                            Action::EnterNormal
+                        } else {
+                            Action::OpenExpression(text)
+                        }
+                    },
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::OpenCombination(ref text) => {
+                self.previous_mode = self.mode.clone();
+                self.mode = Mode::Viewer(text.clone());
+            }
+            Action::OpenExpression(ref text) => {
+                if matches!(self.prompt.previous_mode, Mode::Viewer(_)) {
+                    self.viewer.reset();
+                }
+                self.previous_mode = self.prompt.previous_mode.clone();
+                self.mode = Mode::Viewer(text.clone());
+            }
+            Action::EnterBookmark => {
+                self.prompt.refresh(
+                    tr("Bookmark name"),
+                    &self.viewer.view_description(),
+                    Action::AddBookmark,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::EnterWeights => {
+                let Some(ref d) = self.viewer.data else {
+                    return Ok(None);
+                };
+                // The datasets of the Picker are never rolled up
+                let labels = d.member_labels();
+                let names = self
+                    .picker
+                    .datasets
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|w| w.name != d.name)
+                    .filter(|w| w.set_names == d.set_names && w.set_data == labels)
+                    .map(|w| w.name.clone())
+                    .collect();
+                return Ok(Some(Action::ChooseWeights(names)));
+            }
+            Action::EnterSearch => {
+                self.prompt.refresh(
+                    tr("Search for a label, >value or <value, in the marked datasets if any"),
+                    &self.search.query,
+                    Action::Search,
+                    self.mode.clone(),
+                );
+                self.mode = Mode::Prompt;
+            }
+            Action::Search(ref text) => {
+                let query = match text.parse::<Query>() {
+                    Ok(query) => query,
+                    Err(e) => return Ok(Some(Action::Notify(e.to_string()))),
+                };
+                self.search_datasets(query);
+                self.search.start(text);
+                self.mode = Mode::Search;
+            }
+            Action::SearchResults(ref matches) => self.search.finish(matches.clone()),
+            Action::ShowSearch => {
+                if self.search.query.is_empty() {
+                    return Ok(Some(Action::Notify("Nothing searched yet".into())));
+                }
+                self.mode = Mode::Search;
+            }
+            Action::OpenMatch(ref name, ref index) => {
+                let Some(i) = self
+                    .picker
+                    .datasets
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .position(|d| &d.name == name)
+                else {
+                    return Ok(None);
+                };
+                self.viewer.pending_cell = Some(index.clone());
+                self.mode = Mode::Picker;
+                return Ok(Some(Action::SwitchModeToViewer(i)));
+            }
+            Action::RunPlugin => {
+                if let Err(e) = self.run_plugin() {
+                    return Ok(Some(Action::Error(e.to_string())));
+                }
+            }
+            Action::ShowSparsity => self.sparsity_report(),
+            Action::ShowTasks => {
+                if !self.tasks.busy() {
+                    return Ok(Some(Action::Notify("No task running".into())));
+                }
+                self.task_list.refresh(self.mode.clone());
+                self.mode = Mode::Tasks;
+            }
+            Action::ShowLog => {
+                self.log_view.refresh(self.mode.clone());
+                self.mode = Mode::Log;
+            }
+            Action::CancelTask(id) => {
+                if self.picker.scan_task == Some(id) {
+                    // Also settles the count and selection of what was read so far
+                    self.picker.cancel();
+                } else if self.tasks.cancel(id) && self.search_task == Some(id) {
+                    self.search.finish(vec![]);
+                }
+            }
+            Action::RetryOpen => return Ok(Some(Action::OpenFile(self.file.clone()))),
+            Action::OpenCopy => {
+                // Large files take a while to copy, which would freeze the interface
+                let (file, action_tx) = (self.file.clone(), self.action_tx.clone());
+                tokio::task::spawn_blocking(move || {
+                    let action = match file_error::copy_to_temp(&file) {
+                        Ok(copy) => Action::OpenFile(copy.to_string_lossy().into()),
+                        Err(e) => Action::FileError(e.to_string()),
+                    };
+                    if let Some(tx) = action_tx {
+                        tx.send(action).unwrap_or_default();
+                    }
+                });
+                return Ok(Some(Action::Notify(format!(
+                    "Copying {} to the temporary directory…",
+                    self.file
+                ))));
+            }
+            Action::NextFile | Action::PreviousFile => {
+                let n = self.files.len();
+                if n < 2 {
+                    return Ok(Some(Action::Notify("Only one file was given".into())));
+                }
+                let i = self
+                    .files
+                    .iter()
+                    .position(|f| *f == self.file)
+                    .unwrap_or_default();
+                let i = if action == Action::NextFile {
+                    (i + 1) % n
+                } else {
+                    (i + n - 1) % n
+                };
+                return Ok(Some(Action::OpenFile(self.files[i].clone())));
+            }
+            Action::OpenFile(ref file) => {
+                log::info!("Opening {file}");
+                self.picker.cancel();
+                self.file.clone_from(file);
+                self.mode = Mode::Picker;
+                self.init()?;
+                return Ok(None);
+            }
             Action::SwitchModeToHelp => {
                 self.previous_mode = self.mode.clone();
                 log::debug!("Previous mode = {:?}", self.previous_mode);
@@ -174,7 +923,8 @@ impl Component for App {
             _ => (),
         };
 
-        match self.mode {
+        self.notifications.update(action.clone())?;
+        let result = match self.mode {
             Mode::Picker => self.picker.update(action),
             Mode::Viewer(ref name) => {
                 self.viewer.name.clone_from(name);
@@ -182,8 +932,22 @@ impl Component for App {
                 self.viewer.update(action)
             }
             Mode::Help => self.help.update(action),
+            Mode::FileError => self.file_error.update(action),
+            Mode::Output => self.output.update(action),
+            Mode::Attributes => self.attributes.update(action),
+            Mode::Search => self.search.update(action),
+            Mode::Tasks => self.task_list.update(action),
+            Mode::Log => self.log_view.update(action),
             _ => Ok(None),
-        }
+        };
+        // Report failures instead of exiting, and leave a Viewer that has nothing to show
+        result.or_else(|e| {
+            if matches!(self.mode, Mode::Viewer(_)) && self.viewer.data.is_none() {
+                self.mode = Mode::Picker;
+                self.picker.focus = true;
+            }
+            Ok(Some(Action::Error(format!("{e}"))))
+        })
     }
 
     fn draw(&mut self, f: &mut Frame, rect: Rect) {
@@ -203,6 +967,99 @@ impl Component for App {
                 self.viewer.draw(f, chunks[0]);
             }
             Mode::Waiting => {}
+            Mode::Confirm => {
+                match self.confirm.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                let area = chunks[0].inner(&Margin {
+                    vertical: chunks[0].height.saturating_sub(7) / 2,
+                    horizontal: chunks[0].width.saturating_sub(60) / 2,
+                });
+                self.confirm.draw(f, area);
+            }
+            Mode::Prompt => {
+                match self.prompt.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                let area = chunks[0].inner(&Margin {
+                    vertical: chunks[0].height.saturating_sub(3) / 2,
+                    horizontal: chunks[0].width.saturating_sub(80) / 2,
+                });
+                self.prompt.draw(f, area);
+            }
+            Mode::Attributes => {
+                match self.attributes.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                let area = chunks[0].inner(&Margin {
+                    vertical: chunks[0].height.saturating_sub(8) / 2,
+                    horizontal: chunks[0].width.saturating_sub(80) / 2,
+                });
+                self.attributes.draw(f, area);
+            }
+            Mode::Output => {
+                match self.output.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                self.output.draw(
+                    f,
+                    chunks[0].inner(&Margin {
+                        vertical: 5,
+                        horizontal: 5,
+                    }),
+                );
+            }
+            Mode::Search => {
+                self.picker.draw(f, chunks[0]);
+                self.search.draw(
+                    f,
+                    chunks[0].inner(&Margin {
+                        vertical: 5,
+                        horizontal: 5,
+                    }),
+                );
+            }
+            Mode::Tasks => {
+                match self.task_list.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                let area = chunks[0].inner(&Margin {
+                    vertical: chunks[0].height.saturating_sub(12) / 2,
+                    horizontal: chunks[0].width.saturating_sub(70) / 2,
+                });
+                self.task_list.draw(f, area);
+            }
+            Mode::Log => {
+                match self.log_view.previous_mode {
+                    Mode::Picker => self.picker.draw(f, chunks[0]),
+                    Mode::Viewer(_) => self.viewer.draw(f, chunks[0]),
+                    _ => {}
+                };
+                self.log_view.draw(
+                    f,
+                    chunks[0].inner(&Margin {
+                        vertical: 3,
+                        horizontal: 3,
+                    }),
+                );
+            }
+            Mode::FileError => {
+                let area = chunks[0].inner(&Margin {
+                    vertical: chunks[0].height.saturating_sub(12) / 2,
+                    horizontal: chunks[0].width.saturating_sub(80) / 2,
+                });
+                self.file_error.draw(f, area);
+            }
             Mode::Help => {
                 match self.previous_mode {
                     Mode::Picker => {
@@ -222,53 +1079,87 @@ impl Component for App {
                 )
             }
         };
-        let help_message = vec![
-            Span::styled("Press ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "q",
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Gray),
-            ),
-            Span::styled(" to exit, ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "?",
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Gray),
-            ),
-            Span::styled(" to view help, ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                "◄ ▲ ▼ ►",
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Gray),
-            ),
-            Span::styled(" to navigate.", Style::default().fg(Color::DarkGray)),
-        ];
+        let hints: Vec<(String, &str)> = if self.leader.pending {
+            keymap::leader_bindings(self.context())
+                .iter()
+                .map(|b| (format!("Space {}", b.key), tr(b.description)))
+                .collect()
+        } else {
+            keymap::hints(self.context())
+                .iter()
+                .map(|b| (b.keys(), tr(b.description)))
+                .collect()
+        };
+        let mut help_message = vec![];
+        for (i, (keys, description)) in hints.into_iter().enumerate() {
+            if i > 0 {
+                help_message.push(Span::styled(", ", palette::fg(Color::DarkGray)));
+            }
+            help_message.push(Span::styled(
+                keys,
+                palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
+            ));
+            help_message.push(Span::styled(
+                format!(" {}", description.to_lowercase()),
+                palette::fg(Color::DarkGray),
+            ));
+        }
         let text = Text::from(Line::from(help_message));
         let help_message = Paragraph::new(text);
         f.render_widget(help_message, chunks[1]);
 
-        let about_message = vec![
+        let mut about_message = vec![];
+        let progress = self.tasks.progress();
+        if let Some(first) = progress.first() {
+            let labels = progress
+                .iter()
+                .map(|p| format!("{} {}", p.label, p.text()).trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            about_message.push(Span::styled(
+                format!("{} {labels} · ", tasks::spinner(first.elapsed)),
+                palette::fg(Color::Yellow),
+            ));
+        }
+        if let Some(i) = self
+            .files
+            .iter()
+            .position(|f| *f == self.file)
+            .filter(|_| self.files.len() > 1)
+        {
+            about_message.push(Span::styled(
+                format!("{} {}/{} · ", tr("file"), i + 1, self.files.len()),
+                palette::fg(Color::DarkGray),
+            ));
+        }
+        if let Some((group, n)) = self.current_group() {
+            about_message.push(Span::styled(
+                format!("{group} ({n} {}) · ", tr("datasets")),
+                palette::fg(Color::DarkGray),
+            ));
+        }
+        if self.viewer.journal.enabled {
+            about_message.push(Span::styled(
+                format!("journal {} · ", self.viewer.journal.elapsed()),
+                palette::fg(Color::DarkGray),
+            ));
+        }
+        about_message.extend([
             Span::styled(
                 "https://github.com/ECCC-RPE-EPR/e2020-data-viewer",
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Gray),
+                palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
             ),
             "#v".into(),
             Span::styled(
                 env!("CARGO_PKG_VERSION"),
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::Gray),
+                palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
             ),
-        ];
+        ]);
         let text = Text::from(Line::from(about_message));
         let about_message = Paragraph::new(text).alignment(Alignment::Right);
         f.render_widget(about_message, chunks[1]);
-        if !self.last_event.is_empty() {
+        self.notifications.draw(f, chunks[0]);
+        if !self.last_event.is_empty() && !self.config.reduced_motion {
             f.render_widget(
                 Block::default()
                     .title(
@@ -0,0 +1,120 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::{app::Mode, Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Editor of the `units` and `doc` attributes of a dataset, in write mode.
+#[derive(Default, Debug)]
+pub struct Attributes {
+    /// Full path of the dataset, e.g. `/routput/Dmd`
+    pub name: String,
+    pub units: Input,
+    pub doc: Input,
+    /// Whether the documentation is being edited rather than the units
+    pub editing_doc: bool,
+    /// Mode to return to once saved or cancelled
+    pub previous_mode: Mode,
+}
+
+impl Attributes {
+    pub fn refresh(&mut self, name: &str, units: &str, doc: &str, previous_mode: Mode) {
+        self.name = name.into();
+        self.units = Input::new(units.trim().into());
+        self.doc = Input::new(doc.trim().into());
+        self.editing_doc = false;
+        self.previous_mode = previous_mode;
+    }
+
+    /// The units and documentation typed in.
+    pub fn values(&self) -> (String, String) {
+        (
+            self.units.value().trim().into(),
+            self.doc.value().trim().into(),
+        )
+    }
+}
+
+impl Component for Attributes {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if let Some(action) = keymap::action(Context::Attributes, key) {
+            return Some(action);
+        }
+        let input = if self.editing_doc {
+            &mut self.doc
+        } else {
+            &mut self.units
+        };
+        input.handle_event(&Event::Key(key));
+        None
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::MoveSelectionNext {
+            self.editing_doc = !self.editing_doc;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .title(Line::from(vec![Span::styled(
+                format!(
+                    "{} {} ({})",
+                    tr("Attributes of"),
+                    self.name,
+                    tr("Enter to save, ESC to cancel")
+                ),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(Color::Yellow));
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        let areas: [Rect; 2] =
+            Layout::vertical([Constraint::Length(3), Constraint::Length(3)]).areas(inner);
+        for (i, (title, input)) in [(tr("Units"), &self.units), (tr("Documentation"), &self.doc)]
+            .into_iter()
+            .enumerate()
+        {
+            let area = areas[i];
+            let focused = self.editing_doc == (i == 1);
+            let width = area.width.max(3) - 3; // keep 2 for borders and 1 for cursor
+            let scroll = input.visual_scroll(width as usize);
+            f.render_widget(
+                Paragraph::new(input.value())
+                    .scroll((0, scroll as u16))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(title)
+                            .border_style(if focused {
+                                palette::fg(Color::Yellow)
+                            } else {
+                                Style::default()
+                            }),
+                    ),
+                area,
+            );
+            if focused {
+                f.set_cursor(
+                    (area.x + 1 + (input.cursor() - scroll) as u16).min(area.x + area.width - 2),
+                    area.y + 1,
+                );
+            }
+        }
+    }
+}
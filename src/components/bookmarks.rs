@@ -0,0 +1,115 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Popup to jump back to a bookmarked slice of the current dataset.
+#[derive(Default, Debug)]
+pub struct Bookmarks {
+    /// Bookmarks of the dataset, as (index in the Viewer's bookmarks, name)
+    pub items: Vec<(usize, String)>,
+    pub state: ListState,
+}
+
+impl Bookmarks {
+    pub fn refresh(&mut self, items: Vec<(usize, String)>) {
+        self.items = items;
+        self.state
+            .select(if self.items.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn next(&mut self) {
+        let n = self.items.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + 1) % n)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let n = self.items.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + n - 1) % n)));
+        }
+    }
+
+    /// Index of the chosen bookmark in the Viewer's bookmarks.
+    pub fn choice(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|i| self.items.get(i))
+            .map(|(index, _)| *index)
+    }
+}
+
+impl Component for Bookmarks {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::ViewerBookmarks, key)? {
+            Action::SubmitSelection => Some(Action::JumpToBookmark(self.choice()?)),
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.next(),
+            Action::MoveSelectionPrevious => self.previous(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Bookmarks"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let hint = if self.items.is_empty() {
+            Span::styled(
+                "No bookmarks for this dataset, press Space b to add one.",
+                palette::fg(Color::Red),
+            )
+        } else {
+            Span::styled(
+                "Press Enter to jump, d to delete, ESC to cancel.",
+                palette::fg(Color::DarkGray),
+            )
+        };
+        let items: Vec<ListItem> = self
+            .items
+            .iter()
+            .map(|(_, name)| ListItem::new(Line::from(name.clone())))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Whether `path` is named like an HDF5 file, e.g. `database.hdf5` or `scenario.h5`.
+pub fn is_database(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("hdf5") || e.eq_ignore_ascii_case("h5"))
+}
+
+/// An entry of the directory shown, a subdirectory or an HDF5 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl Entry {
+    fn label(&self, parent: Option<&Path>) -> String {
+        if Some(self.path.as_path()) == parent {
+            return "../".into();
+        }
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if self.is_dir {
+            format!("{name}/")
+        } else {
+            name
+        }
+    }
+}
+
+/// The subdirectories of `dir` then its HDF5 files, each sorted by name, without hidden ones.
+pub fn entries(dir: &Path) -> Result<Vec<Entry>> {
+    let mut entries: Vec<Entry> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| Entry {
+            is_dir: e.path().is_dir(),
+            path: e.path(),
+        })
+        .filter(|e| e.is_dir || is_database(&e.path))
+        .collect();
+    entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then(a.path.cmp(&b.path)));
+    Ok(entries)
+}
+
+/// Screen to find the file to open, shown on launch when no `--file` is given.
+#[derive(Default, Debug)]
+pub struct FileBrowser {
+    pub dir: PathBuf,
+    /// The parent directory, if any, then the entries of `dir`
+    pub entries: Vec<Entry>,
+    pub state: ListState,
+    /// Why the last directory could not be read
+    pub message: Option<String>,
+}
+
+impl FileBrowser {
+    pub fn new(dir: PathBuf) -> Self {
+        let mut browser = Self::default();
+        browser.open(dir);
+        browser
+    }
+
+    /// Lists `dir`, staying in the current directory if it cannot be read.
+    pub fn open(&mut self, dir: PathBuf) {
+        let dir = dir.canonicalize().unwrap_or(dir);
+        match entries(&dir) {
+            Ok(entries) => {
+                let previous = std::mem::replace(&mut self.dir, dir);
+                self.entries = self
+                    .dir
+                    .parent()
+                    .map(|p| Entry {
+                        path: p.to_path_buf(),
+                        is_dir: true,
+                    })
+                    .into_iter()
+                    .chain(entries)
+                    .collect();
+                // Coming back up, the directory left stays selected
+                let selected = self.entries.iter().skip(1).position(|e| e.path == previous);
+                self.state.select(match selected {
+                    Some(i) => Some(i + 1),
+                    None if self.entries.is_empty() => None,
+                    None => Some(self.entries.len().min(2) - 1),
+                });
+                self.message = None;
+            }
+            Err(e) => self.message = Some(format!("Unable to read {}: {e}", dir.display())),
+        }
+    }
+
+    fn parent(&self) -> Option<&Path> {
+        self.dir.parent()
+    }
+
+    fn choice(&self) -> Option<&Entry> {
+        self.entries.get(self.state.selected()?)
+    }
+
+    fn select(&mut self, forward: bool) {
+        let n = self.entries.len();
+        if n > 0 {
+            let i = self.state.selected().unwrap_or_default();
+            self.state.select(Some(if forward {
+                (i + 1) % n
+            } else {
+                (i + n - 1) % n
+            }));
+        }
+    }
+}
+
+impl Component for FileBrowser {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::Browser, key)? {
+            Action::SubmitSelection => match self.choice()? {
+                e if e.is_dir => Some(Action::SubmitSelection),
+                e => Some(Action::OpenFile(e.path.display().to_string())),
+            },
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.select(true),
+            Action::MoveSelectionPrevious => self.select(false),
+            Action::SubmitSelection => {
+                if let Some(e) = self.choice().filter(|e| e.is_dir) {
+                    self.open(e.path.clone());
+                }
+            }
+            Action::MoveSelectionLeft => {
+                if let Some(p) = self.parent() {
+                    self.open(p.to_path_buf());
+                }
+            }
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(rect);
+        let block = Block::default()
+            .title(Line::from(vec![
+                Span::styled(
+                    format!("{} ", tr("Choose a file")),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(self.dir.display().to_string(), palette::fg(Color::DarkGray)),
+            ]))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(Color::Yellow));
+        let parent = self.parent().map(Path::to_path_buf);
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let style = if e.is_dir {
+                    palette::fg(Color::Cyan)
+                } else {
+                    Style::default()
+                };
+                ListItem::new(Line::styled(e.label(parent.as_deref()), style))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        let hint = match self.message {
+            Some(ref message) => Span::styled(message.clone(), palette::fg(Color::Red)),
+            None if self.entries.len() <= 1 => Span::styled(
+                tr("No directory or HDF5 file (.hdf5 or .h5) here."),
+                palette::fg(Color::DarkGray),
+            ),
+            None => Span::styled(
+                keymap::hints(Context::Browser)
+                    .iter()
+                    .map(|b| format!("{} {}", b.keys(), tr(b.description).to_lowercase()))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                palette::fg(Color::DarkGray),
+            ),
+        };
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_database() {
+        assert!(is_database(Path::new("out/database.hdf5")));
+        assert!(is_database(Path::new("Scenario.H5")));
+        assert!(!is_database(Path::new("database.hdf5.bak")));
+        assert!(!is_database(Path::new("h5")));
+    }
+}
@@ -0,0 +1,76 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+
+use super::{app::Mode, Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Dialog asking the user to confirm an action before it is carried out.
+#[derive(Default, Debug)]
+pub struct Confirm {
+    pub message: String,
+    /// Action carried out once confirmed
+    pub action: Option<Action>,
+    /// Mode to return to once answered
+    pub previous_mode: Mode,
+}
+
+impl Confirm {
+    pub fn refresh(&mut self, message: String, action: Action, previous_mode: Mode) {
+        self.message = message;
+        self.action = Some(action);
+        self.previous_mode = previous_mode;
+    }
+}
+
+impl Component for Confirm {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        keymap::action(Context::Confirm, key)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Are you sure?"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [message_area, choices_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        f.render_widget(
+            Paragraph::new(self.message.clone()).wrap(Wrap { trim: false }),
+            message_area,
+        );
+        let choices: Vec<Span> = keymap::bindings(Context::Confirm)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, b)| {
+                [
+                    Span::raw(if i > 0 { "  " } else { "" }),
+                    Span::styled(b.keys(), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {}", tr(b.description))),
+                ]
+            })
+            .collect();
+        f.render_widget(Paragraph::new(Line::from(choices)), choices_area);
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Dialog shown when the file cannot be opened, offering ways to carry on instead of exiting.
+#[derive(Default, Debug)]
+pub struct FileError {
+    pub path: String,
+    pub message: String,
+    /// Path of another file to open
+    pub input: Input,
+    pub editing: bool,
+}
+
+impl FileError {
+    pub fn refresh(&mut self, path: &str, message: String) {
+        self.path = path.into();
+        self.message = message;
+        self.input = Input::new(path.into());
+        self.editing = false;
+    }
+}
+
+/// Copies `path` to the temporary directory, so a file locked or being written elsewhere can be
+/// read from the copy. The copy is named after the process, like [`crate::files::stdin_path`], so
+/// viewers running side by side do not overwrite each other's.
+pub fn copy_to_temp(path: &str) -> Result<PathBuf> {
+    let name = Path::new(path)
+        .file_name()
+        .ok_or_else(|| color_eyre::eyre::eyre!("{path} is not a file"))?;
+    let copy = std::env::temp_dir().join(format!(
+        "e2020-copy-{}-{}",
+        std::process::id(),
+        name.to_string_lossy()
+    ));
+    if std::fs::canonicalize(path).ok() == std::fs::canonicalize(&copy).ok() {
+        return Ok(copy);
+    }
+    std::fs::copy(path, &copy)
+        .map_err(|e| color_eyre::eyre::eyre!("Unable to copy {path} to {}: {e}", copy.display()))?;
+    Ok(copy)
+}
+
+impl Component for FileError {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        if !self.editing {
+            return keymap::action(Context::FileError, key);
+        }
+        match key.code {
+            KeyCode::Esc => {
+                self.editing = false;
+                None
+            }
+            KeyCode::Enter => Some(Action::OpenFile(self.input.value().trim().into())),
+            _ => {
+                self.input.handle_event(&Event::Key(key));
+                None
+            }
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        if action == Action::EnterInsert {
+            self.editing = true;
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Unable to open file"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Red)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [message_area, choices_area, input_area] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(2),
+            Constraint::Length(3),
+        ])
+        .areas(inner);
+        f.render_widget(
+            Paragraph::new(self.message.clone()).wrap(Wrap { trim: false }),
+            message_area,
+        );
+        let choices: Vec<Span> = keymap::bindings(Context::FileError)
+            .iter()
+            .enumerate()
+            .flat_map(|(i, b)| {
+                [
+                    Span::raw(if i > 0 { "  " } else { "" }),
+                    Span::styled(b.keys(), Style::default().add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(" {}", tr(b.description))),
+                ]
+            })
+            .collect();
+        f.render_widget(Paragraph::new(Line::from(choices)), choices_area);
+        if self.editing {
+            let width = input_area.width.max(3) - 3; // keep 2 for borders and 1 for cursor
+            let scroll = self.input.visual_scroll(width as usize);
+            f.render_widget(
+                Paragraph::new(self.input.value())
+                    .scroll((0, scroll as u16))
+                    .block(
+                        Block::default()
+                            .borders(Borders::ALL)
+                            .title(tr("Open file (Enter to open, ESC to cancel)"))
+                            .border_style(palette::fg(Color::Yellow)),
+                    ),
+                input_area,
+            );
+            f.set_cursor(
+                (input_area.x + 1 + self.input.cursor() as u16)
+                    .min(input_area.x + input_area.width - 2),
+                input_area.y + 1,
+            );
+        }
+    }
+}
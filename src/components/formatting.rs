@@ -0,0 +1,103 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    config::Formatting,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Popup to switch the formatting options of the Viewer on and off, one at a time.
+#[derive(Default, Debug)]
+pub struct FormattingMenu {
+    /// The options of the Viewer, as last changed
+    pub formatting: Formatting,
+    pub state: ListState,
+}
+
+impl FormattingMenu {
+    pub fn refresh(&mut self, formatting: Formatting) {
+        self.formatting = formatting;
+        if self.state.selected().is_none() {
+            self.state.select(Some(0));
+        }
+    }
+
+    fn select(&mut self, forward: bool) {
+        let n = Formatting::OPTIONS.len();
+        let i = self.state.selected().unwrap_or_default();
+        self.state.select(Some(if forward {
+            (i + 1) % n
+        } else {
+            (i + n - 1) % n
+        }));
+    }
+}
+
+impl Component for FormattingMenu {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::ViewerFormatting, key)? {
+            Action::SubmitSelection => Some(Action::ToggleFormatting(self.state.selected()?)),
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.select(true),
+            Action::MoveSelectionPrevious => self.select(false),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Formatting"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let items: Vec<ListItem> = Formatting::OPTIONS
+            .iter()
+            .enumerate()
+            .map(|(i, option)| {
+                let check = if self.formatting.get(i) { "[x]" } else { "[ ]" };
+                ListItem::new(Line::from(format!("{check} {}", tr(option))))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        let hint = keymap::hints(Context::ViewerFormatting)
+            .iter()
+            .map(|b| format!("{} {}", b.keys(), tr(b.description).to_lowercase()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        f.render_widget(
+            Paragraph::new(Line::styled(hint, palette::fg(Color::DarkGray))),
+            hint_area,
+        );
+    }
+}
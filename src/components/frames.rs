@@ -0,0 +1,115 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Popup to choose the dimension along which every slice is exported as a frame.
+#[derive(Default, Debug)]
+pub struct Frames {
+    /// Fixed dimensions, as (dimension, set name, number of elements)
+    pub dims: Vec<(usize, String, usize)>,
+    pub state: ListState,
+}
+
+impl Frames {
+    pub fn refresh(&mut self, dims: Vec<(usize, String, usize)>) {
+        self.dims = dims;
+        self.state
+            .select(if self.dims.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn next(&mut self) {
+        let n = self.dims.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + 1) % n)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let n = self.dims.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + n - 1) % n)));
+        }
+    }
+
+    /// The chosen dimension.
+    pub fn choice(&self) -> Option<usize> {
+        self.state
+            .selected()
+            .and_then(|i| self.dims.get(i))
+            .map(|(dim, _, _)| *dim)
+    }
+}
+
+impl Component for Frames {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::ViewerFrames, key)? {
+            Action::SubmitSelection => Some(Action::ExportFrames(self.choice()?, false)),
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.next(),
+            Action::MoveSelectionPrevious => self.previous(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Export frames along"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let hint = if self.dims.is_empty() {
+            Span::styled(
+                "Every dimension is displayed, there is nothing to step through.",
+                palette::fg(Color::Red),
+            )
+        } else {
+            Span::styled(
+                "Press Enter to export one text file per element, ESC to cancel.",
+                palette::fg(Color::DarkGray),
+            )
+        };
+        let items: Vec<ListItem> = self
+            .dims
+            .iter()
+            .map(|(_, name, len)| ListItem::new(Line::from(format!("{name} ({len} frames)"))))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
@@ -0,0 +1,162 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ndarray::{s, Array2};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        canvas::{Canvas, Points},
+        Block, Borders, Clear, Paragraph,
+    },
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    keymap::{self, Context},
+    palette::{self, Palette},
+    stats::nanmean,
+};
+
+/// Number of colours the values are shaded with.
+const LEVELS: usize = 16;
+
+/// The whole slice as an image, one half-block per bin of cells, shaded along the palette's
+/// gradient, to see the structure of a slice too large for the table.
+#[derive(Default, Debug)]
+pub struct Heatmap {
+    /// Values of the slice without totals, indexed as `[[row, col]]`
+    pub values: Array2<f64>,
+    pub title: String,
+    pub palette: Palette,
+}
+
+impl Heatmap {
+    pub fn refresh(&mut self, values: Array2<f64>, title: String, palette: Palette) {
+        self.values = values;
+        self.title = title;
+        self.palette = palette;
+    }
+}
+
+/// Means of `values` over `rows` x `cols` bins of about the same size, leaving out missing
+/// values. When `values` is smaller than that, its cells are repeated instead.
+pub fn bin(values: &Array2<f64>, rows: usize, cols: usize) -> Array2<f64> {
+    let (nrow, ncol) = values.dim();
+    if nrow == 0 || ncol == 0 {
+        return Array2::from_elem((rows, cols), f64::NAN);
+    }
+    let edges = |n: usize, bins: usize, i: usize| {
+        let start = i * n / bins;
+        start..((i + 1) * n / bins).max(start + 1)
+    };
+    Array2::from_shape_fn((rows, cols), |(r, c)| {
+        let (rr, cc) = (edges(nrow, rows, r), edges(ncol, cols, c));
+        nanmean(values.slice(s![rr, cc]).iter())
+    })
+}
+
+impl Component for Heatmap {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        keymap::action(Context::ViewerHeatmap, key)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .title(Line::from(vec![Span::styled(
+                self.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(Color::Yellow));
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        let [image_area, legend_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        // A half-block is a pixel wide and two pixels high
+        let pixels = bin(
+            &self.values,
+            2 * image_area.height as usize,
+            image_area.width as usize,
+        );
+        let (rows, cols) = pixels.dim();
+        let (min, max) = pixels
+            .iter()
+            .filter(|v| !v.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let level = |v: f64| {
+            let t = if max > min {
+                (v - min) / (max - min)
+            } else {
+                0.5
+            };
+            ((t * LEVELS as f64) as usize).min(LEVELS - 1)
+        };
+        let mut points = vec![vec![]; LEVELS];
+        for ((r, c), &v) in pixels.indexed_iter() {
+            // Without colours, only the upper half of the values shows
+            if !v.is_nan() && (self.palette != Palette::Monochrome || level(v) >= LEVELS / 2) {
+                points[level(v)].push((c as f64 + 0.5, (rows - r) as f64 - 0.5));
+            }
+        }
+        let palette = self.palette;
+        let canvas = Canvas::default()
+            .marker(Marker::HalfBlock)
+            .x_bounds([0.0, cols as f64])
+            .y_bounds([0.0, rows as f64])
+            .paint(|ctx| {
+                for (i, coords) in points.iter().enumerate() {
+                    ctx.draw(&Points {
+                        coords,
+                        color: palette.gradient((i as f64 + 0.5) / LEVELS as f64),
+                    });
+                }
+            });
+        f.render_widget(canvas, image_area);
+        let (nrow, ncol) = self.values.dim();
+        let legend = if min > max {
+            "Every value is missing.".to_string()
+        } else {
+            let per_pixel = if nrow * ncol > rows * cols {
+                "means of cells"
+            } else {
+                "cells"
+            };
+            format!("{nrow} × {ncol} cells, shown as {per_pixel} from {min:.4} to {max:.4}.")
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                legend,
+                palette::fg(Color::DarkGray),
+            ))),
+            legend_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_bin() {
+        let values = array![[1.0, 3.0, 5.0], [f64::NAN, 5.0, 7.0]];
+        assert_eq!(
+            bin(&values, 1, 1),
+            array![[(1.0 + 3.0 + 5.0 + 5.0 + 7.0) / 5.0]]
+        );
+        assert_eq!(bin(&values, 2, 1), array![[3.0], [6.0]]);
+        // Smaller than the image
+        assert_eq!(
+            bin(&values, 4, 3).column(2).to_vec(),
+            vec![5.0, 5.0, 7.0, 7.0]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Clear, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    keymap::{self, Context},
+    palette,
+    stats::histogram,
+};
+
+/// Bounds on the number of bins.
+const MIN_BINS: usize = 2;
+const MAX_BINS: usize = 100;
+
+/// Distribution of the values of the slice, to spot outliers and check magnitudes.
+#[derive(Default, Debug)]
+pub struct Histogram {
+    /// Values of the slice without totals, missing ones included
+    pub values: Vec<f64>,
+    pub bins: usize,
+    pub title: String,
+}
+
+impl Histogram {
+    pub fn refresh(&mut self, values: Vec<f64>, bins: usize, title: String) {
+        self.values = values;
+        self.bins = bins.clamp(MIN_BINS, MAX_BINS);
+        self.title = title;
+    }
+}
+
+/// `v` in at most 4 significant digits, in scientific notation if that is shorter.
+fn short(v: f64) -> String {
+    let plain = format!("{v:.4}");
+    let plain = plain.trim_end_matches('0').trim_end_matches('.');
+    let scientific = format!("{v:.2e}");
+    if plain.len() <= scientific.len() {
+        plain.to_string()
+    } else {
+        scientific
+    }
+}
+
+impl Component for Histogram {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        keymap::action(Context::ViewerHistogram, key)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionRight => self.bins = (self.bins * 2).min(MAX_BINS),
+            Action::MoveSelectionLeft => self.bins = (self.bins / 2).max(MIN_BINS),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .title(Line::from(vec![Span::styled(
+                self.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(Color::Yellow));
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        let [chart_area, legend_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let counts = histogram(&self.values, self.bins);
+        // Bars as wide as the area allows, with a gap of one
+        let bar_width = (chart_area.width as usize / counts.len().max(1)).max(2) as u16 - 1;
+        let bars: Vec<Bar> = counts
+            .iter()
+            .map(|&(start, _, count)| {
+                Bar::default()
+                    .value(count as u64)
+                    .label(Line::from(short(start)))
+                    .style(palette::fg(Color::Cyan))
+            })
+            .collect();
+        f.render_widget(
+            BarChart::default()
+                .data(BarGroup::default().bars(&bars))
+                .bar_width(bar_width)
+                .bar_gap(1),
+            chart_area,
+        );
+        let missing = self.values.iter().filter(|v| v.is_nan()).count();
+        let legend = match (counts.first(), counts.last()) {
+            (Some(&(min, _, _)), Some(&(_, max, _))) => format!(
+                "{} values from {} to {} in {} bins of {}{}. Press + or - for more or fewer bins.",
+                self.values.len() - missing,
+                short(min),
+                short(max),
+                counts.len(),
+                short((max - min) / counts.len() as f64),
+                if missing > 0 {
+                    format!(", {missing} missing")
+                } else {
+                    String::default()
+                },
+            ),
+            _ => "Every value is missing.".to_string(),
+        };
+        f.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                legend,
+                palette::fg(Color::DarkGray),
+            ))),
+            legend_area,
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::Rect,
+    prelude::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, Clear, Paragraph},
+};
+use tracing::Level;
+
+use super::{app::Mode, Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    logs::{self, LOGS},
+    palette,
+};
+
+/// Popup showing the last log lines of the session, at a level or more severe.
+#[derive(Debug)]
+pub struct LogView {
+    pub level: Level,
+    /// Lines scrolled up from the most recent one
+    pub scroll: usize,
+    /// Mode to return to once closed
+    pub previous_mode: Mode,
+}
+
+impl Default for LogView {
+    fn default() -> Self {
+        Self {
+            level: Level::INFO,
+            scroll: 0,
+            previous_mode: Mode::default(),
+        }
+    }
+}
+
+impl LogView {
+    pub fn refresh(&mut self, previous_mode: Mode) {
+        self.scroll = 0;
+        self.previous_mode = previous_mode;
+    }
+
+    /// Writes every line kept, whatever its level, to a new file in the current directory.
+    fn dump(&self) -> Action {
+        let path = std::path::PathBuf::from(format!(
+            "log-{}.txt",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        match LOGS.dump(&path) {
+            Ok(()) => Action::Notify(format!("Saved the log to {}", path.display())),
+            Err(e) => Action::Error(format!("Unable to save the log: {e}")),
+        }
+    }
+}
+
+impl Component for LogView {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        keymap::action(Context::Log, key)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.scroll = self.scroll.saturating_sub(1),
+            Action::MoveSelectionPrevious => {
+                let lines = LOGS.lines(self.level).len();
+                self.scroll = (self.scroll + 1).min(lines.saturating_sub(1));
+            }
+            Action::CycleLogLevel => {
+                self.level = logs::next_level(self.level);
+                self.scroll = 0;
+            }
+            Action::DumpLog => return Ok(Some(self.dump())),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .title(Line::from(vec![Span::styled(
+                format!("{} ({} {})", tr("Log"), self.level, tr("and above")),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]))
+            .title(Title::from(tr("Press ESC to close.")).alignment(Alignment::Right))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(Color::Yellow));
+        let height = rect.height.saturating_sub(2) as usize;
+        let lines = LOGS.lines(self.level);
+        let end = lines.len().saturating_sub(self.scroll);
+        let text: Vec<Line> = lines[end.saturating_sub(height)..end]
+            .iter()
+            .map(|l| {
+                let style = match l.level {
+                    Level::ERROR => palette::fg(Color::Red),
+                    Level::WARN => palette::fg(Color::Yellow),
+                    Level::INFO => Style::default(),
+                    _ => palette::fg(Color::DarkGray),
+                };
+                Line::styled(l.text.clone(), style)
+            })
+            .collect();
+        let text = if text.is_empty() {
+            vec![Line::styled(
+                tr("(no log lines)"),
+                palette::fg(Color::DarkGray),
+            )]
+        } else {
+            text
+        };
+        f.render_widget(Paragraph::new(text).block(block), rect);
+    }
+}
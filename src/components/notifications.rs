@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+};
+use unicode_width::UnicodeWidthStr;
+
+use super::{Component, Frame};
+use crate::{action::Action, palette};
+
+/// How long a notification stays on screen.
+const NOTIFICATION_DURATION: Duration = Duration::from_secs(5);
+/// Most notifications shown at once; older ones are dropped.
+const MAX_NOTIFICATIONS: usize = 3;
+const NOTIFICATION_WIDTH: u16 = 60;
+
+/// Error and other messages shown in the top right corner until they expire.
+#[derive(Default, Debug)]
+pub struct Notifications {
+    /// Messages, when each was shown and whether it is an error
+    pub items: Vec<(String, Instant, bool)>,
+}
+
+impl Notifications {
+    /// Shows `message`, restarting its timer if it is already on screen.
+    pub fn push(&mut self, message: String, error: bool) {
+        self.items.retain(|(m, _, _)| *m != message);
+        self.items.push((message, Instant::now(), error));
+        if self.items.len() > MAX_NOTIFICATIONS {
+            self.items.remove(0);
+        }
+    }
+
+    pub fn expire(&mut self) {
+        self.items
+            .retain(|(_, shown, _)| shown.elapsed() < NOTIFICATION_DURATION);
+    }
+}
+
+impl Component for Notifications {
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::Error(message) => {
+                log::error!("{message}");
+                self.push(message, true);
+            }
+            Action::Notify(message) => {
+                log::info!("{message}");
+                self.push(message, false);
+            }
+            Action::Tick => self.expire(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        let width = NOTIFICATION_WIDTH.min(rect.width);
+        let mut y = rect.y + 1;
+        for (message, _, error) in self.items.iter().rev() {
+            let inner_width = width.saturating_sub(2).max(1) as usize;
+            let lines = message.width().div_ceil(inner_width).clamp(1, 3) as u16;
+            let height = lines + 2;
+            if y + height > rect.y + rect.height {
+                break;
+            }
+            let area = Rect {
+                x: rect.x + rect.width - width,
+                y,
+                width,
+                height,
+            };
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(message.clone())
+                    .wrap(Wrap { trim: true })
+                    .block(
+                        Block::default()
+                            .title(Line::from(vec![Span::styled(
+                                if *error { "Error" } else { "Info" },
+                                Style::default().add_modifier(Modifier::BOLD),
+                            )]))
+                            .borders(Borders::ALL)
+                            .border_style(palette::fg(if *error {
+                                Color::Red
+                            } else {
+                                Color::Green
+                            })),
+                    ),
+                area,
+            );
+            y += height;
+        }
+    }
+}
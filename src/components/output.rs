@@ -0,0 +1,82 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::Rect,
+    prelude::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{block::Title, Block, Borders, Clear, Paragraph},
+};
+
+use super::{app::Mode, Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Popup showing the output of an external command.
+#[derive(Default, Debug)]
+pub struct Output {
+    pub title: String,
+    pub text: String,
+    /// Whether the command succeeded, for the border colour
+    pub success: bool,
+    pub scroll: u16,
+    /// Mode to return to once closed
+    pub previous_mode: Mode,
+}
+
+impl Output {
+    pub fn refresh(&mut self, title: String, text: String, success: bool, previous_mode: Mode) {
+        self.title = title;
+        self.text = text;
+        self.success = success;
+        self.scroll = 0;
+        self.previous_mode = previous_mode;
+    }
+}
+
+impl Component for Output {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        keymap::action(Context::Output, key)
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => {
+                let lines = self.text.lines().count() as u16;
+                self.scroll = (self.scroll + 1).min(lines.saturating_sub(1));
+            }
+            Action::MoveSelectionPrevious => self.scroll = self.scroll.saturating_sub(1),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let block = Block::default()
+            .title(Line::from(vec![Span::styled(
+                self.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )]))
+            .title(Title::from(tr("Press ESC to close.")).alignment(Alignment::Right))
+            .borders(Borders::ALL)
+            .border_style(palette::fg(if self.success {
+                Color::Yellow
+            } else {
+                Color::Red
+            }));
+        let text = if self.text.trim().is_empty() {
+            "(no output)".to_string()
+        } else {
+            self.text.clone()
+        };
+        f.render_widget(
+            Paragraph::new(text).scroll((self.scroll, 0)).block(block),
+            rect,
+        );
+    }
+}
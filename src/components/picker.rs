@@ -1,11 +1,12 @@
 use std::{
+    cmp::Ordering as CmpOrdering,
     collections::HashSet,
     io::Stderr,
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicUsize, Ordering},
         Arc, Mutex,
     },
-    time::Duration,
 };
 
 use color_eyre::eyre::{anyhow, eyre, Result};
@@ -15,11 +16,65 @@ use itertools::Itertools;
 use ratatui::{prelude::*, widgets::*};
 use ratatui_macros::line;
 use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
-use tokio_util::sync::CancellationToken;
 use tui_input::{backend::crossterm::EventHandler, Input};
 
 use super::{Component, Frame};
-use crate::{action::Action, data::Data, runner::Runner};
+use crate::{
+    action::Action,
+    config::PickerState,
+    data::{self, Data, LabelCache},
+    export,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+    runner::Runner,
+    tasks::Tasks,
+    utils,
+};
+
+/// Spans of `text` in `style`, with the parts matching any of `words` highlighted.
+fn highlighted(text: &str, words: &[&str], style: Style) -> Vec<Span<'static>> {
+    let matched = style
+        .fg(palette::color(Color::Cyan))
+        .add_modifier(Modifier::UNDERLINED);
+    let mut spans = Vec::new();
+    let mut start = 0;
+    for range in utils::match_ranges(text, words) {
+        spans.push(Span::styled(text[start..range.start].to_string(), style));
+        spans.push(Span::styled(text[range.clone()].to_string(), matched));
+        start = range.end;
+    }
+    spans.push(Span::styled(text[start..].to_string(), style));
+    spans
+}
+
+/// Top-level group of the dataset `name`, e.g. `ooutput` for `/ooutput/Dmd`.
+fn top_group(name: &str) -> &str {
+    name.trim_start_matches('/')
+        .split('/')
+        .next()
+        .unwrap_or_default()
+}
+
+/// Order of `a` and `b` by the Picker column `column`, sets by their names and shapes by their
+/// number of cells.
+fn compare(a: &Data, b: &Data, column: usize) -> CmpOrdering {
+    match column {
+        0 => a.name.cmp(&b.name),
+        1 => a.set_names.cmp(&b.set_names),
+        2 => a
+            .shape
+            .iter()
+            .product::<usize>()
+            .cmp(&b.shape.iter().product()),
+        3 => a.ndims.cmp(&b.ndims),
+        4 => a.units.cmp(&b.units),
+        _ => a.doc.cmp(&b.doc),
+    }
+}
+
+/// Widest the scan progress bar gets.
+const PROGRESS_WIDTH: u16 = 60;
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub enum Mode {
@@ -38,18 +93,38 @@ pub struct Picker {
     pub bold_first_row_col: bool,
     pub bold_first_row: bool,
     pub marked: HashSet<usize>,
+    /// Top-level groups of the file with at least one dataset, e.g. `ooutput`, one tab each
     pub groups: Vec<String>,
+    /// Group whose datasets are shown, or `None` for every group
+    pub group: Option<String>,
+    /// Column the datasets are sorted by, in scan order if `None`
+    pub sort_column: Option<usize>,
+    pub descending: bool,
     pub datasets: Arc<Mutex<Vec<Data>>>,
-    pub loading_status: Arc<AtomicBool>,
+    /// Datasets and groups the last scan could not read, with the error
+    pub problems: Arc<Mutex<Vec<(String, String)>>>,
     pub ndatasets: Arc<AtomicUsize>,
     pub loading: usize,
     pub input: Input,
     pub mode: Mode,
     pub task: Option<JoinHandle<()>>,
-    pub cancellation_token: Option<CancellationToken>,
     pub action_tx: Option<UnboundedSender<Action>>,
     pub filtered_items: Vec<Vec<String>>,
+    /// Index in `datasets` of each filtered item
+    pub filtered_indices: Vec<usize>,
+    /// Operations running in the background, shared with the App
+    pub tasks: Tasks,
+    /// Id in `tasks` of the last scan of the file
+    pub scan_task: Option<usize>,
+    /// Id in `tasks` of the last export of marked datasets
+    pub export_task: Option<usize>,
+    /// Outcome of the last export, shown until the next key press
+    pub message: Option<String>,
     pub page_height: Option<usize>,
+    /// Whether the filtered items changed since the last draw
+    pub redraw: bool,
+    /// Number of the dataset being typed, whose row is selected
+    pub number: String,
 }
 
 impl Picker {
@@ -57,6 +132,9 @@ impl Picker {
         log::debug!("Inside dataset picker init");
         self.focus = true;
         self.bold_first_row = true;
+        let state = PickerState::load(&self.file);
+        (self.sort_column, self.descending) = (state.sort_column, state.descending);
+        self.input = Input::new(state.filter);
         log::debug!("Before read self.get_datasets()");
         self.get_datasets();
         log::debug!("After read self.get_datasets()");
@@ -65,29 +143,131 @@ impl Picker {
     }
 
     pub fn tick(&mut self) {
+        let indices = self.filtered_indices.clone();
         let filter = self.input.value().to_lowercase();
         let filter_words = filter.split_whitespace().collect::<Vec<_>>();
-        self.filtered_items = self
-            .datasets
-            .lock()
-            .unwrap()
+        let datasets = self.datasets.lock().unwrap();
+        let groups: Vec<String> = datasets
+            .iter()
+            .map(|d| top_group(&d.name).to_string())
+            .unique()
+            .sorted()
+            .collect();
+        let mut filtered: Vec<(usize, &Data)> = datasets
             .iter()
-            .filter(|d| {
+            .enumerate()
+            .filter(|(_, d)| self.group.as_ref().is_none_or(|g| top_group(&d.name) == g))
+            .filter(|(_, d)| {
                 filter_words
                     .iter()
                     .all(|word| d.name.to_lowercase().contains(word))
             })
-            .map(|d| {
-                vec![
+            .collect();
+        if let Some(column) = self.sort_column {
+            filtered.sort_by(|(_, a), (_, b)| compare(a, b, column));
+        }
+        if self.descending {
+            filtered.reverse();
+        }
+        (self.filtered_indices, self.filtered_items) = filtered
+            .into_iter()
+            .map(|(i, d)| {
+                let item = vec![
                     format!("'{}'", d.name.clone()),
                     format!("{}", d.set_names.join(", ")),
                     format!("{}", d.shape.iter().map(|i| i.to_string()).join(", ")),
                     format!("{}", d.ndims),
                     d.units.clone(),
                     d.doc.clone(),
-                ]
+                ];
+                (i, item)
             })
+            .unzip();
+        drop(datasets);
+        self.redraw |= self.filtered_indices != indices || self.groups != groups;
+        self.groups = groups;
+    }
+
+    /// Shows the datasets of the next group, or of the previous one if `forward` is false,
+    /// going through every group at once between the last and the first.
+    pub fn cycle_group(&mut self, forward: bool) {
+        // Position among the tabs, where 0 is every group
+        let n = self.groups.len() + 1;
+        let tab = self
+            .group
+            .as_ref()
+            .and_then(|g| self.groups.iter().position(|x| x == g))
+            .map_or(0, |i| i + 1);
+        let tab = if forward {
+            (tab + 1) % n
+        } else {
+            (tab + n - 1) % n
+        };
+        self.group = tab.checked_sub(1).map(|i| self.groups[i].clone());
+        self.state
+            .select((!self.datasets.lock().unwrap().is_empty()).then_some(0));
+        self.tick();
+    }
+
+    /// Sorts the datasets by the next column, going back to scan order after the last one.
+    pub fn cycle_sort_column(&mut self) {
+        self.sort_column = match self.sort_column {
+            None => Some(0),
+            Some(c) if c + 1 < self.columns.len() => Some(c + 1),
+            Some(_) => None,
+        };
+        self.tick();
+        self.save_state();
+    }
+
+    pub fn reverse_sort(&mut self) {
+        self.descending = !self.descending;
+        self.tick();
+        self.save_state();
+    }
+
+    /// Keeps the sort and filter for the next time the file is opened.
+    fn save_state(&self) {
+        let state = PickerState {
+            sort_column: self.sort_column,
+            descending: self.descending,
+            filter: self.input.value().into(),
+        };
+        if let Err(e) = state.save(&self.file) {
+            log::warn!("Unable to save the state of the Picker: {e}");
+        }
+    }
+
+    /// Top-level group of the dataset `name` and its number of datasets, if it has any.
+    pub fn group_of(&self, name: &str) -> Option<(String, usize)> {
+        let group = top_group(name);
+        let n = self
+            .datasets
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|d| top_group(&d.name) == group)
+            .count();
+        (n > 0).then(|| (group.to_string(), n))
+    }
+
+    /// Index in `datasets` of the dataset after `name` in its group, or before it if `forward`
+    /// is false, wrapping around. `None` if it is alone in its group or not in the file.
+    pub fn neighbour_in_group(&self, name: &str, forward: bool) -> Option<usize> {
+        let datasets = self.datasets.lock().unwrap();
+        let group = top_group(name);
+        let indices: Vec<usize> = (0..datasets.len())
+            .filter(|&i| top_group(&datasets[i].name) == group)
             .collect();
+        let k = indices.iter().position(|&i| datasets[i].name == name)?;
+        let n = indices.len();
+        (n > 1).then(|| {
+            indices[if forward {
+                (k + 1) % n
+            } else {
+                (k + n - 1) % n
+            }]
+        })
     }
 
     pub fn reset(&mut self) {
@@ -208,56 +388,255 @@ impl Picker {
     pub fn get_datasets(&mut self) {
         log::debug!("-------- Reading from {}", self.file);
         let datasets = self.datasets.clone();
+        let problems = self.problems.clone();
         let file = self.file.clone();
-        let loading_status = self.loading_status.clone();
         let ndatasets = self.ndatasets.clone();
-        self.cancellation_token = Some(CancellationToken::new());
-        let _cancellation_token = self.cancellation_token.clone().unwrap();
+        let scan = self.tasks.start(tr("Scanning"), 0);
+        self.scan_task = Some(scan.id());
         let _action_tx = self.action_tx.clone();
         self.task = Some(tokio::spawn(async move {
             datasets.lock().unwrap().drain(0..);
-            loading_status.store(true, Ordering::SeqCst);
+            problems.lock().unwrap().clear();
             let mut names = vec![];
-            let f = hdf5::File::open(&file).unwrap();
-            for group in f.member_names().unwrap() {
-                for dataset in f.group(&group).unwrap().member_names().unwrap() {
-                    names.push(format!("{group}/{dataset}"));
+            let opened = data::open_file(&file).and_then(|f| {
+                let groups = f.member_names()?;
+                Ok((f, groups))
+            });
+            let (f, groups) = match opened {
+                Ok(opened) => opened,
+                Err(e) => {
+                    log::error!("{e}");
+                    if let Some(ref action_tx) = _action_tx {
+                        action_tx
+                            .send(Action::FileError(e.to_string()))
+                            .unwrap_or_default();
+                    }
+                    return;
+                }
+            };
+            for group in groups {
+                match f.group(&group).and_then(|g| g.member_names()) {
+                    Ok(members) => {
+                        names.extend(members.iter().map(|dataset| format!("{group}/{dataset}")))
+                    }
+                    Err(e) => {
+                        log::error!("Skipping unreadable group {group}: {e}");
+                        problems.lock().unwrap().push((group, e.to_string()));
+                    }
                 }
             }
             ndatasets.store(names.len(), Ordering::SeqCst);
+            scan.set_total(names.len());
             let mut count = 0;
+            // Datasets of a group mostly share the same sets
+            let labels = LabelCache::of(Path::new(&file));
             for name in names {
-                if let Ok(d) = Data::new(file.clone().into(), name) {
-                    datasets.lock().unwrap().push(d);
-                    count += 1;
-                }
-                if _cancellation_token.is_cancelled() {
+                // One at a time from the file opened above: the HDF5 library runs one call at
+                // a time, so reading several datasets at once would not be faster
+                let (f, labels, read) = (f.clone(), labels.clone(), name.clone());
+                let result =
+                    tokio::task::spawn_blocking(move || Data::open(&f, &read, &labels)).await;
+                if scan.is_cancelled() {
                     break;
                 }
+                scan.advance();
+                match result {
+                    Ok(Ok(d)) => {
+                        // Checked under the lock so a cancelled scan adds nothing once a new one
+                        // started
+                        let mut datasets = datasets.lock().unwrap();
+                        if scan.is_cancelled() {
+                            break;
+                        }
+                        datasets.push(d);
+                        count += 1;
+                    }
+                    Ok(Err(e)) => problems.lock().unwrap().push((name, e.to_string())),
+                    Err(e) => problems.lock().unwrap().push((name, e.to_string())),
+                }
+            }
+            if scan.is_cancelled() {
+                log::debug!("Cancelled reading from {}", file);
+                return;
             }
             ndatasets.store(count, Ordering::SeqCst);
             if let Some(action_tx) = _action_tx {
+                let failed = problems.lock().unwrap().len();
+                if failed > 0 {
+                    action_tx
+                        .send(Action::Notify(format!(
+                            "{failed} datasets or groups could not be read. Press Space p to see why."
+                        )))
+                        .unwrap_or_default();
+                }
                 action_tx.send(Action::Tick).unwrap_or_default();
                 action_tx
                     .send(Action::MoveSelectionNext)
                     .unwrap_or_default();
             }
-            loading_status.store(false, Ordering::SeqCst);
+            drop(scan);
             log::debug!("Finished reading from {}", file);
         }));
     }
 
+    /// Stops the scan without waiting for it to finish, keeping the datasets read so far.
     pub fn cancel(&mut self) {
-        if let Some(ref t) = self.cancellation_token {
-            t.cancel();
-            if let Some(ref t) = self.task {
-                while !t.is_finished() {
-                    std::thread::sleep(Duration::from_millis(50))
-                }
+        if self
+            .scan_task
+            .take()
+            .is_some_and(|id| self.tasks.cancel(id))
+        {
+            let count = self.datasets.lock().unwrap().len();
+            self.ndatasets.store(count, Ordering::SeqCst);
+            if self.state.selected().is_none() && count > 0 {
+                self.state.select(Some(0));
             }
         }
     }
 
+    /// Names of the marked datasets in file order, joined to open them side by side, if there
+    /// are at least two and they have the same sets.
+    pub fn combination(&self) -> Result<String> {
+        let datasets = self.datasets.lock().unwrap();
+        let marked: Vec<&Data> = self
+            .marked
+            .iter()
+            .sorted()
+            .filter_map(|&i| datasets.get(i))
+            .collect();
+        let [first, rest @ ..] = &marked[..] else {
+            return Err(eyre!("Mark datasets with v first"));
+        };
+        if rest.is_empty() {
+            return Err(eyre!(
+                "Mark at least two datasets to open them side by side"
+            ));
+        }
+        if let Some(d) = rest
+            .iter()
+            .find(|d| d.set_names != first.set_names || d.set_data != first.set_data)
+        {
+            return Err(eyre!(
+                "{} does not have the dimensions of {}",
+                d.name,
+                first.name
+            ));
+        }
+        Ok(marked.iter().map(|d| d.name.as_str()).join(", "))
+    }
+
+    /// Number, combined size on disk and sets of the marked datasets, along with what can be done
+    /// with them, if any are marked.
+    pub fn marked_summary(&self) -> Option<String> {
+        if self.marked.is_empty() {
+            return None;
+        }
+        let datasets = self.datasets.lock().unwrap();
+        let marked: Vec<&Data> = self
+            .marked
+            .iter()
+            .sorted()
+            .filter_map(|&i| datasets.get(i))
+            .collect();
+        let size: u64 = marked.iter().map(|d| d.dataset.storage_size()).sum();
+        let sets = marked
+            .iter()
+            .flat_map(|d| d.set_names.iter())
+            .unique()
+            .join(", ");
+        Some(format!(
+            "{} {}, {} {}, {sets}: {}",
+            marked.len(),
+            tr("marked"),
+            utils::human_size(size),
+            tr("on disk"),
+            tr("e export, o side by side, Space f search, Space P plugin, V unmark all"),
+        ))
+    }
+
+    /// The datasets and groups the last scan could not read, one per line with the error.
+    pub fn problems_text(&self) -> String {
+        let problems = self.problems.lock().unwrap();
+        if problems.is_empty() {
+            return "Every dataset of the file was read.".into();
+        }
+        problems
+            .iter()
+            .map(|(name, e)| format!("{name}\n    {e}"))
+            .join("\n")
+    }
+
+    /// Whether datasets are being scanned or exported in the background.
+    pub fn busy(&self) -> bool {
+        self.scanning() || self.export_task.is_some_and(|id| self.tasks.is_running(id))
+    }
+
+    pub fn scanning(&self) -> bool {
+        self.scan_task.is_some_and(|id| self.tasks.is_running(id))
+    }
+
+    /// Writes every marked dataset to its own CSV file in a new directory, in the background.
+    pub fn export_marked(&mut self) -> Result<()> {
+        if self.export_task.is_some_and(|id| self.tasks.is_running(id)) {
+            return Err(eyre!("An export is already running"));
+        }
+        let mut marked: Vec<usize> = self.marked().cloned().collect();
+        marked.sort();
+        let datasets: Vec<Data> = {
+            let all = self.datasets.lock().unwrap();
+            marked.iter().filter_map(|&i| all.get(i).cloned()).collect()
+        };
+        if datasets.is_empty() {
+            return Err(eyre!("No datasets marked, press v to mark some"));
+        }
+        let dir = PathBuf::from(format!(
+            "export-{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let export = self.tasks.start(tr("Exporting"), 0);
+        self.export_task = Some(export.id());
+        export::spawn(datasets, dir, export, self.action_tx.clone());
+        Ok(())
+    }
+
+    /// Progress of the scan or export, drawn over the right of the top border.
+    fn draw_progress(&self, f: &mut Frame, rect: Rect) {
+        let scanning = self.scanning();
+        let id = if scanning {
+            self.scan_task
+        } else {
+            self.export_task
+        };
+        let Some(p) = self.tasks.progress().into_iter().find(|p| Some(p.id) == id) else {
+            return;
+        };
+        let (done, total) = (p.done, p.total);
+        let ratio = if total > 0 {
+            (done as f64 / total as f64).min(1.0)
+        } else {
+            0.0
+        };
+        let width = (rect.width / 2).min(PROGRESS_WIDTH);
+        let area = Rect {
+            x: rect.x + rect.width.saturating_sub(width + 1),
+            y: rect.y,
+            width,
+            height: 1,
+        };
+        let gauge = LineGauge::default()
+            .label(if scanning {
+                format!("{} {} ({}) ", p.label, p.text(), tr("ESC to cancel"))
+            } else {
+                format!("{} {} ", p.label, p.text())
+            })
+            .ratio(ratio)
+            .line_set(symbols::line::THICK)
+            .gauge_style(palette::fg(Color::Yellow));
+        f.render_widget(Clear, area);
+        f.render_widget(gauge, area);
+    }
+
     pub fn filtered_items(&self) -> Vec<Vec<String>> {
         self.filtered_items.clone()
     }
@@ -292,6 +671,24 @@ impl Picker {
         }
     }
 
+    /// Adds `digit` to the number typed and selects the dataset of that number, starting a new
+    /// number from `digit` if there is no such dataset.
+    pub fn type_number(&mut self, digit: char) {
+        let n = self.filtered_indices.len();
+        let valid = |number: &str| number.parse::<usize>().is_ok_and(|k| k >= 1 && k <= n);
+        let mut number = format!("{}{digit}", self.number);
+        if !valid(&number) {
+            number = digit.to_string();
+        }
+        if valid(&number) {
+            self.state
+                .select(number.parse::<usize>().ok().map(|k| k - 1));
+            self.number = number;
+        } else {
+            self.number.clear();
+        }
+    }
+
     pub fn select(&mut self, selection: usize) -> usize {
         let items = self.filtered_items();
         let name = items[selection][0]
@@ -313,6 +710,10 @@ impl Picker {
 }
 
 impl Component for Picker {
+    fn needs_redraw(&self) -> bool {
+        self.redraw || self.busy()
+    }
+
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.action_tx = Some(tx);
         Ok(())
@@ -320,31 +721,21 @@ impl Component for Picker {
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
         log::debug!("key: {key:?}");
+        self.message = None;
         let cmd = match self.mode {
             Mode::Normal => match key.code {
-                KeyCode::Char('q') => Action::Quit,
-                KeyCode::Char('/') => Action::EnterInsert,
-                KeyCode::Char('?') => Action::SwitchModeToHelp,
-                KeyCode::Char('j') | KeyCode::Down => Action::MoveSelectionNext,
-                KeyCode::Char('k') | KeyCode::Up => Action::MoveSelectionPrevious,
-                KeyCode::Char('h') | KeyCode::Left => Action::MoveSelectionLeft,
-                KeyCode::Char('l') | KeyCode::Right => Action::MoveSelectionRight,
-                KeyCode::Char('g') => Action::MoveSelectionTop,
-                KeyCode::Char('G') => Action::MoveSelectionBottom,
-                KeyCode::PageUp => Action::MoveSelectionPageUp,
-                KeyCode::PageDown => Action::MoveSelectionPageDown,
-                KeyCode::Char('r') => Action::ReloadData,
-                KeyCode::Char('v') => Action::ToggleSelection,
-                KeyCode::Home => Action::MoveSelectionHome,
-                KeyCode::End => Action::MoveSelectionEnd,
-                KeyCode::Enter => Action::SubmitSelection,
-                KeyCode::Esc => Action::Close,
-                _ => return None,
-            },
-            Mode::Editing => match key.code {
-                KeyCode::Esc => Action::EnterNormal,
-                KeyCode::Enter => Action::EnterNormal,
+                KeyCode::Char(c) if c.is_ascii_digit() && key.modifiers.is_empty() => {
+                    self.type_number(c);
+                    return None;
+                }
                 _ => {
+                    self.number.clear();
+                    keymap::action(Context::Picker, key)?
+                }
+            },
+            Mode::Editing => match keymap::action(Context::PickerEditing, key) {
+                Some(action) => action,
+                None => {
                     self.input.handle_event(&Event::Key(key));
                     Action::Refresh
                 }
@@ -370,12 +761,14 @@ impl Component for Picker {
                 self.cancel();
                 self.get_datasets();
             }
+            Action::CancelScan => self.cancel(),
             Action::EnterInsert => {
                 self.mode = Mode::Editing;
                 return Ok(Some(Action::Refresh));
             }
             Action::EnterNormal => {
                 self.mode = Mode::Normal;
+                self.save_state();
                 return Ok(Some(Action::Refresh));
             }
             Action::SubmitSelection => {
@@ -389,17 +782,70 @@ impl Component for Picker {
                 // self.input.set_value("");
                 return Ok(Some(Action::Refresh));
             }
-            Action::ToggleSelection => self.mark(self.state.selected()),
+            Action::ToggleSelection => {
+                let index = self
+                    .state
+                    .selected()
+                    .and_then(|i| self.filtered_indices.get(i).copied());
+                self.toggle(index);
+            }
+            Action::ExportMarked => self.export_marked()?,
+            Action::UnmarkAll => self.clear(),
+            Action::OpenMarked => match self.combination() {
+                Ok(text) => return Ok(Some(Action::OpenCombination(text))),
+                Err(e) => self.message = Some(e.to_string()),
+            },
+            Action::ExportedMarked(message) => self.message = Some(message),
             Action::Tick => self.tick(),
+            Action::ShowProblems => {
+                let n = self.problems.lock().unwrap().len();
+                return Ok(Some(Action::ShowOutput(
+                    format!("Problems ({n})"),
+                    self.problems_text(),
+                    n == 0,
+                )));
+            }
+            Action::MoveSelectionRight => self.cycle_group(true),
+            Action::MoveSelectionLeft => self.cycle_group(false),
+            Action::CycleSortColumn => self.cycle_sort_column(),
+            Action::ReverseSort => self.reverse_sort(),
             _ => (),
         }
         Ok(None)
     }
 
     fn draw(&mut self, f: &mut Frame, rect: Rect) {
-        let [table_area, input_area] =
-            Layout::vertical([Constraint::Percentage(100), Constraint::Min(3)]).areas(rect);
+        self.redraw = false;
+        let [tabs_area, table_area, input_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Percentage(100),
+            Constraint::Min(3),
+        ])
+        .areas(rect);
+        let tab = self
+            .group
+            .as_ref()
+            .and_then(|g| self.groups.iter().position(|x| x == g))
+            .map_or(0, |i| i + 1);
+        let tabs = Tabs::new(
+            std::iter::once("All")
+                .chain(self.groups.iter().map(String::as_str))
+                .collect::<Vec<_>>(),
+        )
+        .select(tab)
+        .style(palette::dim())
+        .highlight_style(
+            palette::fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD)
+                .remove_modifier(Modifier::DIM),
+        );
+        f.render_widget(tabs, tabs_area);
         let header_cells = self.columns.iter().enumerate().map(|(i, h)| {
+            let h = &match self.sort_column {
+                Some(c) if c == i && self.descending => format!("{h} ▼"),
+                Some(c) if c == i => format!("{h} ▲"),
+                _ => h.clone(),
+            };
             if i == 0 {
                 if self.bold_first_row_col || self.bold_first_row {
                     Cell::from(h.clone()).style(Style::default().add_modifier(Modifier::BOLD))
@@ -414,28 +860,42 @@ impl Component for Picker {
         });
         let header = Row::new(header_cells).height(1).bottom_margin(1);
         let items: Vec<Vec<String>> = self.filtered_items();
+        // Each dataset is numbered, to select it by typing its number
+        let number_width = items.len().to_string().len();
+        // Show why each dataset matched the filter, in its name and doc
+        let filter = self.input.value().to_string();
+        let filter_words = filter.split_whitespace().collect::<Vec<_>>();
         let rows = items.iter().enumerate().map(|(i, item)| {
             let height = 1;
-            let style = if self.contains(i) {
-                Style::default()
-                    .fg(Color::LightYellow)
-                    .add_modifier(Modifier::BOLD)
+            let marked = self
+                .filtered_indices
+                .get(i)
+                .is_some_and(|&index| self.contains(index));
+            let style = if marked {
+                palette::fg(Color::LightYellow).add_modifier(Modifier::BOLD)
             } else {
                 Style::default()
             };
-            let cells = item
-                .iter()
-                .enumerate()
-                .map(|(j, c)| Cell::from(c.clone()).style(style));
+            let cells = item.iter().enumerate().map(|(j, c)| {
+                if j == 0 {
+                    let mut spans = vec![Span::styled(
+                        format!("{:>number_width$} ", i + 1),
+                        palette::fg(Color::DarkGray),
+                    )];
+                    spans.extend(highlighted(c, &filter_words, style));
+                    Cell::from(Line::from(spans))
+                } else if j == 5 {
+                    Cell::from(Line::from(highlighted(c, &filter_words, style)))
+                } else {
+                    Cell::from(c.clone()).style(style)
+                }
+            });
             Row::new(cells).height(height as u16)
         });
         let highlight_symbol = if self.focus { " \u{2022} " } else { "" };
-        let loading_status = if self.loading_status.load(Ordering::SeqCst) {
-            format!(
-                "Scanning {}/{}",
-                self.datasets.lock().unwrap().len(),
-                self.ndatasets.load(Ordering::SeqCst)
-            )
+        let busy = self.busy();
+        let loading_status = if busy {
+            String::default()
         } else {
             format!(
                 "{}/{}",
@@ -443,25 +903,61 @@ impl Component for Picker {
                 self.ndatasets.load(Ordering::SeqCst)
             )
         };
+        let problems = self.problems.lock().unwrap().len();
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(tr("Picker"))
+            .title(block::Title::from(loading_status).alignment(Alignment::Right))
+            .border_style(if self.focus {
+                palette::fg(Color::Yellow)
+            } else {
+                palette::dim()
+            });
+        if problems > 0 && !busy {
+            block = block.title(
+                block::Title::from(Span::styled(
+                    format!("{problems} unreadable, Space p to see why"),
+                    palette::fg(Color::Red),
+                ))
+                .alignment(Alignment::Center),
+            );
+        }
+        if !self.number.is_empty() {
+            block = block.title(
+                block::Title::from(Span::styled(
+                    format!("#{} ({})", self.number, tr("Enter to open")),
+                    palette::fg(Color::Yellow),
+                ))
+                .position(block::Position::Bottom)
+                .alignment(Alignment::Left),
+            );
+        }
+        if let Some(summary) = self.marked_summary().filter(|_| self.number.is_empty()) {
+            block = block.title(
+                block::Title::from(Span::styled(summary, palette::fg(Color::LightYellow)))
+                    .position(block::Position::Bottom)
+                    .alignment(Alignment::Left),
+            );
+        }
+        if let Some(ref message) = self.message {
+            block = block.title(
+                block::Title::from(Span::styled(message.clone(), palette::fg(Color::Yellow)))
+                    .position(block::Position::Bottom)
+                    .alignment(Alignment::Right),
+            );
+        }
         let table = Table::new(rows, &self.constraints)
             .header(header)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("Picker")
-                    .title(block::Title::from(loading_status).alignment(Alignment::Right))
-                    .border_style(if self.focus {
-                        Style::default().fg(Color::Yellow)
-                    } else {
-                        Style::default().add_modifier(Modifier::DIM)
-                    }),
-            )
+            .block(block)
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
             .highlight_symbol(highlight_symbol)
             .highlight_spacing(HighlightSpacing::Always);
 
         self.page_height = Some(table_area.height.saturating_sub(4) as usize);
         f.render_stateful_widget(table, table_area, &mut self.state);
+        if busy {
+            self.draw_progress(f, table_area);
+        }
 
         if let Some(i) = self.state.selected() {
             let mut state = ScrollbarState::default()
@@ -481,15 +977,15 @@ impl Component for Picker {
                 Block::default()
                     .borders(Borders::ALL)
                     .title(line![
-                        "Fuzzy Find (Press ",
+                        tr("Fuzzy Find (Press "),
                         "/".bold(),
-                        " to start, ",
+                        tr(" to start, "),
                         "ESC".bold(),
-                        " to finish)",
+                        tr(" to finish)"),
                     ])
                     .border_style(match self.mode {
-                        Mode::Editing => Style::default().fg(Color::Yellow),
-                        _ => Style::default().add_modifier(Modifier::DIM),
+                        Mode::Editing => palette::fg(Color::Yellow),
+                        _ => palette::dim(),
                     }),
             );
         f.render_widget(input, input_area);
@@ -0,0 +1,185 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Lists of the pivot popup, in the order Tab goes through them.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PivotList {
+    #[default]
+    Rows,
+    Columns,
+    Groups,
+}
+
+/// Popup to choose which dimensions are displayed as rows and as columns, and optionally which
+/// dimension the rows are grouped by.
+#[derive(Default, Debug)]
+pub struct Pivot {
+    pub set_names: Vec<String>,
+    pub rows: ListState,
+    pub columns: ListState,
+    /// Dimension the rows are grouped by, after a first entry for none
+    pub groups: ListState,
+    pub focused: PivotList,
+}
+
+impl Pivot {
+    pub fn refresh(
+        &mut self,
+        set_names: Vec<String>,
+        axis0: usize,
+        axis1: usize,
+        groups: Option<usize>,
+    ) {
+        self.set_names = set_names;
+        self.rows.select(Some(axis1));
+        self.columns.select(Some(axis0));
+        self.groups.select(Some(groups.map_or(0, |g| g + 1)));
+        self.focused = PivotList::Rows;
+    }
+
+    fn focused(&mut self) -> (&mut ListState, usize) {
+        let n = self.set_names.len();
+        match self.focused {
+            PivotList::Rows => (&mut self.rows, n),
+            PivotList::Columns => (&mut self.columns, n),
+            PivotList::Groups => (&mut self.groups, n + 1),
+        }
+    }
+
+    pub fn next(&mut self) {
+        let (state, n) = self.focused();
+        if n > 0 {
+            state.select(Some(state.selected().map_or(0, |i| (i + 1) % n)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let (state, n) = self.focused();
+        if n > 0 {
+            state.select(Some(state.selected().map_or(0, |i| (i + n - 1) % n)));
+        }
+    }
+
+    /// Moves the focus to the next list, or the previous one if `forward` is false.
+    pub fn cycle_focus(&mut self, forward: bool) {
+        self.focused = match (self.focused, forward) {
+            (PivotList::Rows, true) | (PivotList::Groups, false) => PivotList::Columns,
+            (PivotList::Columns, true) | (PivotList::Rows, false) => PivotList::Groups,
+            (PivotList::Groups, true) | (PivotList::Columns, false) => PivotList::Rows,
+        };
+    }
+
+    /// Chosen (rows, columns, groups) dimensions, if they all differ.
+    pub fn choice(&self) -> Option<(usize, usize, Option<usize>)> {
+        let groups = self.groups.selected().unwrap_or_default().checked_sub(1);
+        match (self.rows.selected(), self.columns.selected()) {
+            (Some(r), Some(c)) if r != c && groups != Some(r) && groups != Some(c) => {
+                Some((r, c, groups))
+            }
+            _ => None,
+        }
+    }
+
+    fn list(&self, title: &'static str, focused: bool, none: bool) -> List<'static> {
+        let items: Vec<ListItem> = none
+            .then(|| "None".to_string())
+            .iter()
+            .chain(self.set_names.iter())
+            .map(|s| ListItem::new(Line::from(s.clone())))
+            .collect();
+        List::new(items)
+            .block(
+                Block::default()
+                    .title(tr(title))
+                    .borders(Borders::ALL)
+                    .border_style(if focused {
+                        palette::fg(Color::Yellow)
+                    } else {
+                        palette::dim()
+                    }),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("→ ")
+    }
+}
+
+impl Component for Pivot {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::ViewerPivot, key)? {
+            Action::SubmitSelection => {
+                let (rows, columns, groups) = self.choice()?;
+                Some(Action::Pivot(rows, columns, groups))
+            }
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.next(),
+            Action::MoveSelectionPrevious => self.previous(),
+            Action::MoveSelectionLeft => self.cycle_focus(false),
+            Action::MoveSelectionRight => self.cycle_focus(true),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        let hint = if self.choice().is_some() {
+            Span::styled(
+                "Press Tab to switch list, Enter to apply, ESC to cancel.",
+                palette::fg(Color::DarkGray),
+            )
+        } else {
+            Span::styled(
+                "Rows, columns and groups must be different dimensions.",
+                palette::fg(Color::Red),
+            )
+        };
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Pivot"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [lists_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let [rows_area, columns_area, groups_area] = Layout::horizontal([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .areas(lists_area);
+        let rows = self.list("Rows", self.focused == PivotList::Rows, false);
+        f.render_stateful_widget(rows, rows_area, &mut self.rows);
+        let columns = self.list("Columns", self.focused == PivotList::Columns, false);
+        f.render_stateful_widget(columns, columns_area, &mut self.columns);
+        let groups = self.list("Rows grouped by", self.focused == PivotList::Groups, true);
+        f.render_stateful_widget(groups, groups_area, &mut self.groups);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
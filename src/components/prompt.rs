@@ -0,0 +1,149 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    widgets::{block, Block, Borders, Clear, Paragraph},
+};
+use tui_input::{backend::crossterm::EventHandler, Input};
+
+use super::{app::Mode, Component, Frame};
+use crate::{action::Action, i18n::tr, palette, utils::fuzzy_matches};
+
+/// Most completions listed under the input.
+const MAX_SHOWN_COMPLETIONS: usize = 8;
+
+/// Single line text input, e.g. for a path, turned into an action when submitted.
+#[derive(Debug)]
+pub struct Prompt {
+    pub title: String,
+    pub input: Input,
+    /// Action carrying the submitted text
+    pub on_submit: fn(String) -> Action,
+    /// Mode to return to once answered
+    pub previous_mode: Mode,
+    /// Values offered as completions of the text typed, if any
+    pub completions: Vec<String>,
+    /// Text typed before Tab was first pressed, and the rank of the completion shown
+    completing: Option<(String, usize)>,
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Self {
+            title: String::default(),
+            input: Input::default(),
+            on_submit: |_| Action::EnterNormal,
+            previous_mode: Mode::default(),
+            completions: vec![],
+            completing: None,
+        }
+    }
+}
+
+impl Prompt {
+    pub fn refresh(
+        &mut self,
+        title: &str,
+        value: &str,
+        on_submit: fn(String) -> Action,
+        previous_mode: Mode,
+    ) {
+        self.title = title.into();
+        self.input = Input::new(value.into());
+        self.on_submit = on_submit;
+        self.previous_mode = previous_mode;
+        self.completions = vec![];
+        self.completing = None;
+    }
+
+    /// The completions matching the text typed, best first.
+    fn matches(&self) -> Vec<&String> {
+        let typed = match self.completing {
+            Some((ref typed, _)) => typed.as_str(),
+            None => self.input.value(),
+        };
+        fuzzy_matches(typed.trim(), &self.completions)
+            .into_iter()
+            .map(|i| &self.completions[i])
+            .collect()
+    }
+
+    /// Replaces the text typed with its next completion, back to the text once they are all
+    /// shown.
+    fn complete(&mut self) {
+        let (typed, rank) = match self.completing.take() {
+            Some((typed, rank)) => (typed, rank + 1),
+            None => (self.input.value().to_string(), 0),
+        };
+        self.completing = Some((typed.clone(), rank));
+        let value = self.matches().get(rank).map(|m| m.to_string());
+        if value.is_none() {
+            self.completing = None;
+        }
+        self.input = Input::new(value.unwrap_or(typed));
+    }
+}
+
+impl Component for Prompt {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Esc => Some(Action::EnterNormal),
+            KeyCode::Enter => Some((self.on_submit)(self.input.value().trim().into())),
+            KeyCode::Tab if !self.completions.is_empty() => {
+                self.complete();
+                None
+            }
+            _ => {
+                self.completing = None;
+                self.input.handle_event(&Event::Key(key));
+                None
+            }
+        }
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        let width = rect.width.max(3) - 3; // keep 2 for borders and 1 for cursor
+        let scroll = self.input.visual_scroll(width as usize);
+        f.render_widget(Clear, rect);
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(
+                "{} ({})",
+                self.title,
+                tr("Enter to confirm, ESC to cancel")
+            ))
+            .border_style(palette::fg(Color::Yellow));
+        if !self.completions.is_empty() && !self.input.value().trim().is_empty() {
+            let matches = self.matches();
+            let mut shown: Vec<&str> = matches
+                .iter()
+                .take(MAX_SHOWN_COMPLETIONS)
+                .map(|m| m.as_str())
+                .collect();
+            if matches.len() > MAX_SHOWN_COMPLETIONS {
+                shown.push("…");
+            }
+            let hint = if matches.is_empty() {
+                tr("no match").to_string()
+            } else {
+                format!("{}: {}", tr("Tab to complete"), shown.join(", "))
+            };
+            block = block.title(
+                block::Title::from(hint)
+                    .position(block::Position::Bottom)
+                    .alignment(ratatui::layout::Alignment::Left),
+            );
+        }
+        f.render_widget(
+            Paragraph::new(self.input.value())
+                .scroll((0, scroll as u16))
+                .block(block),
+            rect,
+        );
+        f.set_cursor(
+            (rect.x + 1 + self.input.cursor() as u16).min(rect.x + rect.width - 2),
+            rect.y + 1,
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+    search::{Match, MAX_MATCHES},
+};
+
+/// Popup listing the matches of a search across the datasets of the file.
+#[derive(Default, Debug)]
+pub struct Search {
+    pub query: String,
+    pub matches: Vec<Match>,
+    /// Whether the datasets are still being searched
+    pub running: bool,
+    pub state: ListState,
+}
+
+impl Search {
+    pub fn start(&mut self, query: &str) {
+        self.query = query.into();
+        self.matches.clear();
+        self.running = true;
+        self.state.select(None);
+    }
+
+    pub fn finish(&mut self, matches: Vec<Match>) {
+        self.matches = matches;
+        self.running = false;
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    pub fn next(&mut self) {
+        let n = self.matches.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + 1) % n)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let n = self.matches.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + n - 1) % n)));
+        }
+    }
+
+    pub fn choice(&self) -> Option<&Match> {
+        self.matches.get(self.state.selected()?)
+    }
+}
+
+impl Component for Search {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::Search, key)? {
+            Action::SubmitSelection => {
+                let m = self.choice()?;
+                Some(Action::OpenMatch(m.dataset.clone(), m.index.clone()))
+            }
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.next(),
+            Action::MoveSelectionPrevious => self.previous(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    format!("{} {:?}", tr("Search for"), self.query),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let hint = match self.matches.len() {
+            _ if self.running => {
+                Span::styled(tr("Searching every dataset…"), palette::fg(Color::Yellow))
+            }
+            0 => Span::styled(tr("No match."), palette::fg(Color::Red)),
+            n => Span::styled(
+                format!(
+                    "{n}{} {}",
+                    if n >= MAX_MATCHES { "+" } else { "" },
+                    tr("matches, press Enter to open one in the Viewer, ESC to close.")
+                ),
+                palette::fg(Color::DarkGray),
+            ),
+        };
+        let items: Vec<ListItem> = self
+            .matches
+            .iter()
+            .map(|m| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        m.dataset.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(format!("  {}", m.description)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
@@ -1,19 +1,28 @@
 use std::collections::HashSet;
 
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::KeyEvent;
 use itertools::Itertools;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     symbols,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Row, Table, Tabs},
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Paragraph, Row, Table,
+        Tabs,
+    },
 };
 use tracing::debug;
+use unicode_width::UnicodeWidthStr;
 
 use super::{app::Mode, Component};
-use crate::action::Action;
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct MultipleSelectionListState {
@@ -70,8 +79,10 @@ impl StatefulList {
         }
     }
 
+    /// Indices of the marked items, in order, marking the first one if none is and there are
+    /// any.
     pub fn selected(&mut self) -> Vec<usize> {
-        if self.multiple_selection_state.marked.is_empty() {
+        if self.multiple_selection_state.marked.is_empty() && !self.items.is_empty() {
             self.multiple_selection_state.mark(Some(0));
         };
         let mut s = self
@@ -106,6 +117,10 @@ impl StatefulList {
     }
 
     pub fn next(&mut self) {
+        if self.items.is_empty() {
+            self.list_state.select(None);
+            return;
+        }
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -120,6 +135,10 @@ impl StatefulList {
     }
 
     pub fn previous(&mut self) {
+        if self.items.is_empty() {
+            self.list_state.select(None);
+            return;
+        }
         let i = match self.list_state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -132,6 +151,40 @@ impl StatefulList {
         };
         self.list_state.select(Some(i));
     }
+
+    /// Number of columns needed to lay out the items with `height` items per column.
+    pub fn ncolumns(&self, height: usize) -> usize {
+        self.items.len().div_ceil(height.max(1))
+    }
+
+    /// Moves one column to the left when laid out with `height` items per column.
+    pub fn column_left(&mut self, height: usize) {
+        if let Some(i) = self.list_state.selected() {
+            if i >= height {
+                self.list_state.select(Some(i - height));
+            }
+        }
+    }
+
+    /// Moves one column to the right when laid out with `height` items per column.
+    pub fn column_right(&mut self, height: usize) {
+        if let Some(i) = self.list_state.selected() {
+            let height = height.max(1);
+            if i / height < self.items.len().saturating_sub(1) / height {
+                self.list_state
+                    .select(Some((i + height).min(self.items.len() - 1)));
+            }
+        }
+    }
+
+    fn list_item(&self, i: usize, s: &str) -> ListItem<'static> {
+        let c = if self.multiple_selection_state.contains(i) {
+            "\u{2714} ".to_string()
+        } else {
+            "  ".to_string()
+        };
+        ListItem::new(vec![Line::from(c + s)]).style(Style::default())
+    }
 }
 
 #[derive(Default, Debug)]
@@ -139,12 +192,17 @@ pub struct Select {
     pub active_sets_state: Vec<StatefulList>,
     pub set_names: Vec<String>,
     current_set: usize,
+    /// Number of items per column in the last draw
+    pub page_height: Option<usize>,
+    /// First column shown when the items are laid out in more columns than fit
+    column_offset: usize,
 }
 
 impl Select {
     pub fn init(&mut self) -> Result<()> {
         for i in 0..self.active_sets_state.len() {
-            self.active_sets_state[i].list_state.select(Some(0));
+            let first = (!self.active_sets_state[i].items.is_empty()).then_some(0);
+            self.active_sets_state[i].list_state.select(first);
             self.active_sets_state[i].mark_all();
             debug!(
                 "active_sets[{i}] = {:?}",
@@ -154,15 +212,55 @@ impl Select {
         Ok(())
     }
 
+    /// The list of the set shown, if the dataset has any set.
+    fn current(&mut self) -> Option<&mut StatefulList> {
+        self.active_sets_state.get_mut(self.current_set)
+    }
+
     pub fn next_element(&mut self) {
-        self.active_sets_state[self.current_set].next()
+        if let Some(list) = self.current() {
+            list.next()
+        }
     }
 
     pub fn previous_element(&mut self) {
-        self.active_sets_state[self.current_set].previous()
+        if let Some(list) = self.current() {
+            list.previous()
+        }
+    }
+
+    /// Moves across columns when the current set spans several, otherwise switches set.
+    pub fn move_left(&mut self) {
+        match (self.page_height, self.current_set) {
+            (Some(h), i)
+                if self
+                    .active_sets_state
+                    .get(i)
+                    .is_some_and(|l| l.ncolumns(h) > 1) =>
+            {
+                self.active_sets_state[i].column_left(h)
+            }
+            _ => self.previous_set(),
+        }
+    }
+
+    /// Moves across columns when the current set spans several, otherwise switches set.
+    pub fn move_right(&mut self) {
+        match (self.page_height, self.current_set) {
+            (Some(h), i)
+                if self
+                    .active_sets_state
+                    .get(i)
+                    .is_some_and(|l| l.ncolumns(h) > 1) =>
+            {
+                self.active_sets_state[i].column_right(h)
+            }
+            _ => self.next_set(),
+        }
     }
 
     pub fn next_set(&mut self) {
+        self.column_offset = 0;
         self.current_set += 1;
         if self.current_set >= self.set_names.len() {
             self.current_set = 0
@@ -170,19 +268,24 @@ impl Select {
     }
 
     pub fn previous_set(&mut self) {
+        self.column_offset = 0;
         if self.current_set == 0 {
-            self.current_set = self.set_names.len() - 1
+            self.current_set = self.set_names.len().saturating_sub(1)
         } else {
             self.current_set = self.current_set.saturating_sub(1);
         }
     }
 
     pub fn toggle(&mut self) {
-        self.active_sets_state[self.current_set].toggle()
+        if let Some(list) = self.current() {
+            list.toggle()
+        }
     }
 
     pub fn toggle_all(&mut self) {
-        self.active_sets_state[self.current_set].toggle_all()
+        if let Some(list) = self.current() {
+            list.toggle_all()
+        }
     }
 
     pub fn refresh(&mut self, set_data: Vec<Vec<String>>, set_names: Vec<String>) {
@@ -192,30 +295,27 @@ impl Select {
             .map(StatefulList::with_items)
             .collect();
         self.set_names.clone_from(&set_names);
+        // The dataset may have fewer sets than the previous one
+        if self.current_set >= self.set_names.len() {
+            self.current_set = 0;
+            self.column_offset = 0;
+        }
     }
 }
 
 impl Component for Select {
     fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
-        let action = match key.code {
-            KeyCode::Esc => Action::EnterNormal,
-            KeyCode::Char('j') | KeyCode::Down => Action::MoveSelectionNext,
-            KeyCode::Char('k') | KeyCode::Up => Action::MoveSelectionPrevious,
-            KeyCode::Char('h') | KeyCode::Left => Action::MoveSelectionLeft,
-            KeyCode::Char('l') | KeyCode::Right => Action::MoveSelectionRight,
-            KeyCode::Char('V') => Action::ToggleAllSelection,
-            KeyCode::Char('v') => Action::ToggleSelection,
-            _ => return None,
-        };
-        Some(action)
+        keymap::action(Context::ViewerSelection, key)
     }
 
     fn update(&mut self, command: Action) -> Result<Option<Action>> {
         match command {
             Action::MoveSelectionNext => self.next_element(),
             Action::MoveSelectionPrevious => self.previous_element(),
-            Action::MoveSelectionLeft => self.previous_set(),
-            Action::MoveSelectionRight => self.next_set(),
+            Action::MoveSelectionLeft => self.move_left(),
+            Action::MoveSelectionRight => self.move_right(),
+            Action::NextSet => self.next_set(),
+            Action::PreviousSet => self.previous_set(),
             Action::ToggleSelection => self.toggle(),
             Action::ToggleAllSelection => self.toggle_all(),
             _ => (),
@@ -230,67 +330,103 @@ impl Component for Select {
             .block(
                 Block::default()
                     .title(Line::from(vec![
-                        Span::styled("Press ", Style::default().fg(Color::DarkGray)),
+                        Span::styled(tr("Press "), palette::fg(Color::DarkGray)),
+                        Span::styled("Tab", palette::fg(Color::Gray).add_modifier(Modifier::BOLD)),
+                        Span::styled(tr(" to switch axis, "), palette::fg(Color::DarkGray)),
                         Span::styled(
                             "◄ or ►",
-                            Style::default()
-                                .add_modifier(Modifier::BOLD)
-                                .fg(Color::Gray),
-                        ),
-                        Span::styled(" to switch axis, ", Style::default().fg(Color::DarkGray)),
-                        Span::styled(
-                            "v",
-                            Style::default()
-                                .add_modifier(Modifier::BOLD)
-                                .fg(Color::Gray),
+                            palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
                         ),
-                        Span::styled(" to toggle values, ", Style::default().fg(Color::DarkGray)),
                         Span::styled(
-                            "ESC",
-                            Style::default()
-                                .add_modifier(Modifier::BOLD)
-                                .fg(Color::Gray),
+                            tr(" to move across columns, "),
+                            palette::fg(Color::DarkGray),
                         ),
-                        Span::styled(" to close.", Style::default().fg(Color::DarkGray)),
+                        Span::styled("v", palette::fg(Color::Gray).add_modifier(Modifier::BOLD)),
+                        Span::styled(tr(" to toggle values, "), palette::fg(Color::DarkGray)),
+                        Span::styled("ESC", palette::fg(Color::Gray).add_modifier(Modifier::BOLD)),
+                        Span::styled(tr(" to close."), palette::fg(Color::DarkGray)),
                     ]))
                     .borders(Borders::ALL),
             )
-            .style(Style::default().fg(Color::White))
+            .style(palette::fg(Color::White))
             .select(self.current_set)
-            .highlight_style(Style::default().fg(Color::Yellow))
+            .highlight_style(palette::fg(Color::Yellow))
             .divider(symbols::DOT);
         f.render_widget(t, rect);
 
-        let items: Vec<ListItem> = self.active_sets_state[self.current_set]
+        let area = rect.inner(&Margin {
+            vertical: 3,
+            horizontal: 5,
+        });
+        let height = area.height.max(1) as usize;
+        self.page_height = Some(height);
+        let empty = match self.active_sets_state.get(self.current_set) {
+            None => Some(tr("This dataset has no sets to select from.")),
+            Some(list) if list.items.is_empty() => {
+                Some(tr("This set is empty: the dataset has no values along it."))
+            }
+            Some(_) => None,
+        };
+        if let Some(message) = empty {
+            f.render_widget(
+                Paragraph::new(Line::styled(message, palette::fg(Color::DarkGray))),
+                area,
+            );
+            return;
+        }
+        let list = &mut self.active_sets_state[self.current_set];
+        let ncolumns = list.ncolumns(height);
+        if ncolumns <= 1 {
+            let items: Vec<ListItem> = list
+                .items
+                .iter()
+                .enumerate()
+                .map(|(i, s)| list.list_item(i, s))
+                .collect();
+            let items = List::new(items)
+                .block(Block::default())
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_symbol("→ ");
+            f.render_stateful_widget(items, area, &mut list.list_state);
+            return;
+        }
+
+        // Lay long lists out in columns, scrolling horizontally to keep the selection visible
+        let column_width = list
             .items
             .iter()
-            .cloned()
-            .enumerate()
-            .map(|(i, s)| {
-                let c = if self.active_sets_state[self.current_set]
-                    .multiple_selection_state
-                    .contains(i)
-                {
-                    "\u{2714} ".to_string()
-                } else {
-                    "  ".to_string()
-                };
-                let lines = vec![Line::from(c + &s)];
-                ListItem::new(lines).style(Style::default())
-            })
-            .collect();
-        let items = List::new(items)
-            .block(Block::default())
-            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
-            .highlight_symbol("→ ");
-
-        f.render_stateful_widget(
-            items,
-            rect.inner(&Margin {
-                vertical: 3,
-                horizontal: 5,
-            }),
-            &mut self.active_sets_state[self.current_set].list_state,
-        );
+            .map(|s| s.width())
+            .max()
+            .unwrap_or_default()
+            + 6;
+        let visible = (area.width as usize / column_width).max(1);
+        let selected = list.list_state.selected().unwrap_or_default();
+        let selected_column = selected / height;
+        if selected_column < self.column_offset {
+            self.column_offset = selected_column;
+        } else if selected_column >= self.column_offset + visible {
+            self.column_offset = selected_column + 1 - visible;
+        }
+        for (n, j) in (self.column_offset..ncolumns.min(self.column_offset + visible)).enumerate() {
+            let start = j * height;
+            let end = (start + height).min(list.items.len());
+            let items: Vec<ListItem> = (start..end)
+                .map(|i| list.list_item(i, &list.items[i]))
+                .collect();
+            let mut state = ListState::default();
+            if selected_column == j {
+                state.select(Some(selected - start));
+            }
+            let items = List::new(items)
+                .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+                .highlight_symbol("→ ")
+                .highlight_spacing(HighlightSpacing::Always);
+            let column_area = Rect {
+                x: area.x + (n * column_width) as u16,
+                width: (column_width as u16).min(area.width),
+                ..area
+            };
+            f.render_stateful_widget(items, column_area, &mut state);
+        }
     }
 }
@@ -9,13 +9,23 @@ use tui_input::Input;
 use crate::{
     action::Action,
     components::{Component, Frame},
-    data::Data,
+    data::{Aggregation, Data},
+    i18n::tr,
+    palette,
     runner::Runner,
 };
 
+/// Columns of the scrubbers of the fixed dimensions, with their position.
+const SCRUBBER_WIDTH: u16 = 24;
+
 #[derive(Default, Debug)]
 pub struct Summary {
+    /// First line shown of the dimensions that are not displayed
     pub scroll: u16,
+    /// Lines of dimensions that fit in the last draw
+    pub page_height: u16,
+    /// Whether the summary is a single line, to leave the table more room
+    pub collapsed: bool,
     pub name: String,
     pub doc: String,
     pub kvs: Vec<(String, String)>,
@@ -23,6 +33,29 @@ pub struct Summary {
     pub total_indices: Vec<usize>,
     pub axis0: usize,
     pub axis1: usize,
+    /// How each dimension that is not displayed is reduced, by set
+    pub aggregations: Vec<Aggregation>,
+    /// Dimension pinned as the comparison reference, with a description of the comparison
+    pub reference: Option<(usize, String)>,
+    /// Number of missing values in the displayed slice
+    pub missing: usize,
+    /// Dimension last stepped through, whose scrubber `Alt+←/→` steps
+    pub current: Option<usize>,
+    /// Where the bar of each scrubber shown was last drawn, by dimension, for mouse clicks
+    scrubbers: Vec<(usize, Rect)>,
+}
+
+/// Bar of `width` columns with a knob at `index` of `total`, as the parts before, at and after it.
+fn scrubber(index: usize, total: usize, width: usize) -> (String, &'static str, String) {
+    if width == 0 {
+        return Default::default();
+    }
+    let knob = if total > 1 {
+        index * (width - 1) / (total - 1)
+    } else {
+        0
+    };
+    ("━".repeat(knob), "●", "─".repeat(width - 1 - knob))
 }
 
 impl Summary {
@@ -49,35 +82,167 @@ impl Summary {
         }
         Ok(())
     }
+
+    /// Height the summary takes, for `ndims` dimensions and at most `max_height` lines.
+    pub fn height(&self, ndims: usize, max_height: u16) -> u16 {
+        if self.collapsed {
+            1
+        } else {
+            // The name, doc and dimensions, each other dimension, and the borders
+            (ndims as u16 + 5).min(max_height.max(7))
+        }
+    }
+
+    /// Lines of the dimensions that are not displayed, one each.
+    fn lines(&self) -> u16 {
+        (0..self.kvs.len())
+            .filter(|&i| i != self.axis0 && i != self.axis1)
+            .count() as u16
+    }
+
+    fn max_scroll(&self) -> u16 {
+        self.lines().saturating_sub(self.page_height.max(1))
+    }
+
+    /// Scrolls the dimensions by `delta` lines, down if positive.
+    pub fn scroll_by(&mut self, delta: i16) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(self.max_scroll());
+    }
+
+    /// The dimension `Alt+←/→` steps: the last stepped through if still fixed, or else the first
+    /// fixed one.
+    pub fn scrubbed(&self) -> Option<usize> {
+        let fixed = |&i: &usize| {
+            i < self.kvs.len()
+                && i != self.axis0
+                && i != self.axis1
+                && self.aggregations.get(i).copied().unwrap_or_default() == Aggregation::Fix
+        };
+        self.current
+            .filter(fixed)
+            .or_else(|| (0..self.kvs.len()).find(fixed))
+    }
+
+    /// Dimension and index of the scrubber at `column`, `row` of the screen, if any.
+    pub fn index_at(&self, column: u16, row: u16) -> Option<(usize, usize)> {
+        let &(dim, rect) = self
+            .scrubbers
+            .iter()
+            .find(|(_, r)| r.y == row && (r.x..r.x + r.width).contains(&column))?;
+        let total = self.total_indices[dim];
+        let steps = rect.width.saturating_sub(1) as usize;
+        if steps == 0 {
+            return Some((dim, self.kis[dim]));
+        }
+        // The nearest index to the column clicked
+        let offset = (column - rect.x) as usize;
+        Some((dim, (offset * (total - 1) + steps / 2) / steps))
+    }
+
+    /// Scrolls the dimensions just enough for the line of dimension `dim` to be shown.
+    pub fn follow(&mut self, dim: usize) {
+        self.current = Some(dim);
+        let line = (0..dim)
+            .filter(|&i| i != self.axis0 && i != self.axis1)
+            .count() as u16;
+        let height = self.page_height.max(1);
+        if line < self.scroll {
+            self.scroll = line;
+        } else if line >= self.scroll + height {
+            self.scroll = line + 1 - height;
+        }
+    }
+
+    /// The dataset and the element of every other dimension on one line.
+    fn draw_collapsed(&self, f: &mut Frame<'_>, rect: Rect) {
+        let mut spans = vec![Span::styled(
+            format!(" {} ", self.name),
+            Style::default().add_modifier(Modifier::BOLD),
+        )];
+        for (i, (k, v)) in self.kvs.iter().enumerate() {
+            if i == self.axis0 || i == self.axis1 {
+                continue;
+            }
+            let value = match self.aggregations.get(i).copied().unwrap_or_default() {
+                Aggregation::Fix => format!("{v} ({}/{})", self.kis[i] + 1, self.total_indices[i]),
+                Aggregation::Sum => "Σ all".into(),
+                Aggregation::Mean => "mean of all".into(),
+                Aggregation::Weighted => "weighted mean of all".into(),
+            };
+            spans.push(Span::styled("· ", palette::fg(Color::DarkGray)));
+            spans.push(Span::styled(format!("{k}="), palette::fg(Color::Yellow)));
+            spans.push(Span::raw(format!("{value} ")));
+        }
+        if self.missing > 0 {
+            spans.push(Span::styled(
+                format!("· {} missing", self.missing),
+                palette::fg(Color::Yellow),
+            ));
+        }
+        f.render_widget(Paragraph::new(Line::from(spans)), rect);
+    }
 }
 
 impl Component for Summary {
     fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
-        f.render_widget(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Dataset")
-                .border_style(Style::default().add_modifier(Modifier::DIM)),
-            rect,
-        );
+        self.scrubbers.clear();
+        if self.collapsed {
+            self.draw_collapsed(f, rect);
+            return;
+        }
         let [top_rect, bottom_rect] =
             Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).areas(rect);
+        // The bottom border takes the last line
+        self.page_height = bottom_rect.height.saturating_sub(1);
+        let bottom_rect = Rect {
+            height: self.page_height,
+            ..bottom_rect
+        };
+        self.scroll = self.scroll.min(self.max_scroll());
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .title(tr("Dataset"))
+            .border_style(palette::dim());
+        if self.lines() > self.page_height {
+            block = block.title(
+                block::Title::from(format!(
+                    " {}-{} / {}, Ctrl+↑/↓ ",
+                    self.scroll + 1,
+                    (self.scroll + self.page_height).min(self.lines()),
+                    self.lines()
+                ))
+                .position(block::Position::Bottom)
+                .alignment(Alignment::Right),
+            );
+        }
+        f.render_widget(block, rect);
 
         let text = text![
             "",
             self.name.clone(),
-            Span::styled(
-                &self.doc,
-                Style::default()
-                    .add_modifier(Modifier::BOLD)
-                    .fg(Color::DarkGray),
-            ),
+            Line::from(vec![
+                Span::styled(
+                    &self.doc,
+                    palette::fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+                ),
+                match self.missing {
+                    0 => Span::raw(""),
+                    1 => Span::styled("  (1 missing value)", palette::fg(Color::Yellow)),
+                    n => Span::styled(
+                        format!("  ({n} missing values)"),
+                        palette::fg(Color::Yellow),
+                    ),
+                },
+            ]),
             self.kvs
                 .iter()
                 .enumerate()
                 .map(|(i, (k, v))| {
                     if i == self.axis0 || i == self.axis1 {
-                        Span::styled(format!(" {} ", k), Style::default().fg(Color::Yellow))
+                        Span::styled(format!(" {} ", k), palette::fg(Color::Yellow))
                     } else {
                         Span::raw(format!(" {} ", k))
                     }
@@ -89,10 +254,12 @@ impl Component for Summary {
         let [left_rect, middle_left_rect, middle_right_rect, right_rect] = Layout::horizontal([
             Constraint::Percentage(25),
             Constraint::Percentage(25),
-            Constraint::Length(10),
+            Constraint::Length(SCRUBBER_WIDTH),
             Constraint::Percentage(50),
         ])
         .areas(bottom_rect);
+        let scrubbed = self.scrubbed();
+        let mut line = 0;
         let mut text_left = vec![];
         let mut text_middle_left = vec![];
         let mut text_middle_right = vec![];
@@ -105,49 +272,107 @@ impl Component for Summary {
             }
             let i = i + 1;
             text_left.push(Line::from(vec![
-                Span::styled(format!(" {k}"), Style::default().fg(Color::Yellow)),
+                Span::styled(format!(" {k}"), palette::fg(Color::Yellow)),
                 Span::raw(": "),
             ]));
-            text_middle_left.push(Line::from(vec![Span::styled(
-                v,
-                Style::default().add_modifier(Modifier::BOLD),
-            )]));
-            text_middle_right.push(Line::from(vec![Span::styled(
-                format!(" ({index} / {total_index})"),
-                Style::default().fg(Color::DarkGray),
-            )]));
+            let aggregation = self.aggregations.get(i - 1).copied().unwrap_or_default();
+            let mut spans = vec![match aggregation {
+                Aggregation::Fix => Span::styled(v, Style::default().add_modifier(Modifier::BOLD)),
+                Aggregation::Sum => Span::styled(
+                    "Σ all",
+                    palette::fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                ),
+                Aggregation::Mean => Span::styled(
+                    "mean of all",
+                    palette::fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                ),
+                Aggregation::Weighted => Span::styled(
+                    "weighted mean of all",
+                    palette::fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                ),
+            }];
+            if let Some((_, ref label)) = self.reference.as_ref().filter(|(d, _)| *d + 1 == i) {
+                spans.push(Span::styled(
+                    format!(" ({label})"),
+                    palette::fg(Color::DarkGray),
+                ));
+            }
+            text_middle_left.push(Line::from(spans));
+            let label = format!(" {index}/{total_index}");
+            let width = (middle_right_rect.width as usize).saturating_sub(label.len() + 1);
+            if aggregation == Aggregation::Fix {
+                let (before, knob, after) = scrubber(index - 1, total_index, width);
+                let color = if scrubbed == Some(i - 1) {
+                    Color::Yellow
+                } else {
+                    Color::Gray
+                };
+                text_middle_right.push(Line::from(vec![
+                    Span::raw(" "),
+                    Span::styled(before, palette::fg(color)),
+                    Span::styled(knob, palette::fg(color).add_modifier(Modifier::BOLD)),
+                    Span::styled(after, palette::fg(Color::DarkGray)),
+                    Span::styled(label, palette::fg(Color::DarkGray)),
+                ]));
+                if (self.scroll..self.scroll + self.page_height).contains(&line) {
+                    self.scrubbers.push((
+                        i - 1,
+                        Rect {
+                            x: middle_right_rect.x + 1,
+                            y: middle_right_rect.y + line - self.scroll,
+                            width: width as u16,
+                            height: 1,
+                        },
+                    ));
+                }
+            } else {
+                text_middle_right.push(Line::from(Span::styled(
+                    format!(" {}{label}", "─".repeat(width)),
+                    palette::fg(Color::DarkGray),
+                )));
+            }
+            line += 1;
             text_right.push(Line::from(vec![
-                Span::styled(" ↓ ", Style::default().fg(Color::DarkGray)),
+                Span::styled(" ↓ ", palette::fg(Color::DarkGray)),
                 Span::styled(
                     format!("F{i}"),
-                    Style::default()
-                        .add_modifier(Modifier::BOLD)
-                        .fg(Color::Gray),
+                    palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
                 ),
-                Span::styled(" ↑ ", Style::default().fg(Color::DarkGray)),
+                Span::styled(" ↑ ", palette::fg(Color::DarkGray)),
                 Span::styled(
                     format!("Shift + F{i}"),
-                    Style::default()
-                        .add_modifier(Modifier::BOLD)
-                        .fg(Color::Gray),
+                    palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(" Σ ", palette::fg(Color::DarkGray)),
+                Span::styled(
+                    format!("Alt + F{i}"),
+                    palette::fg(Color::Gray).add_modifier(Modifier::BOLD),
                 ),
             ]));
         }
 
         f.render_widget(
-            Paragraph::new(text_left).alignment(Alignment::Right),
+            Paragraph::new(text_left)
+                .alignment(Alignment::Right)
+                .scroll((self.scroll, 0)),
             left_rect,
         );
         f.render_widget(
-            Paragraph::new(text_middle_left).alignment(Alignment::Left),
+            Paragraph::new(text_middle_left)
+                .alignment(Alignment::Left)
+                .scroll((self.scroll, 0)),
             middle_left_rect,
         );
         f.render_widget(
-            Paragraph::new(text_middle_right).alignment(Alignment::Right),
+            Paragraph::new(text_middle_right)
+                .alignment(Alignment::Right)
+                .scroll((self.scroll, 0)),
             middle_right_rect,
         );
         f.render_widget(
-            Paragraph::new(text_right).alignment(Alignment::Left),
+            Paragraph::new(text_right)
+                .alignment(Alignment::Left)
+                .scroll((self.scroll, 0)),
             right_rect,
         );
     }
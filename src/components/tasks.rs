@@ -0,0 +1,125 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{app::Mode, Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+    tasks::{self, Progress, Tasks},
+};
+
+/// Popup listing the operations running in the background, to cancel them one by one.
+#[derive(Default, Debug)]
+pub struct TaskList {
+    pub tasks: Tasks,
+    pub state: ListState,
+    /// Mode to return to once closed
+    pub previous_mode: Mode,
+}
+
+impl TaskList {
+    pub fn refresh(&mut self, previous_mode: Mode) {
+        self.state.select(Some(0));
+        self.previous_mode = previous_mode;
+    }
+
+    fn choice(&self) -> Option<Progress> {
+        self.tasks
+            .progress()
+            .into_iter()
+            .nth(self.state.selected()?)
+    }
+
+    fn select(&mut self, forward: bool) {
+        let n = self.tasks.progress().len();
+        if n > 0 {
+            let i = self.state.selected().unwrap_or_default().min(n - 1);
+            self.state.select(Some(if forward {
+                (i + 1) % n
+            } else {
+                (i + n - 1) % n
+            }));
+        }
+    }
+}
+
+impl Component for TaskList {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::Tasks, key)? {
+            Action::SubmitSelection => Some(Action::CancelTask(self.choice()?.id)),
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.select(true),
+            Action::MoveSelectionPrevious => self.select(false),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Background tasks"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let progress = self.tasks.progress();
+        if let Some(i) = self.state.selected() {
+            self.state
+                .select(Some(i.min(progress.len().saturating_sub(1))));
+        }
+        let items: Vec<ListItem> = progress
+            .iter()
+            .map(|p| {
+                let (status, style) = if p.cancelled {
+                    (tr("cancelling…"), palette::fg(Color::DarkGray))
+                } else {
+                    (tasks::spinner(p.elapsed), Style::default())
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{status} {} {}", p.label, p.text()), style),
+                    Span::styled(
+                        format!("  {}s", p.elapsed.as_secs()),
+                        palette::fg(Color::DarkGray),
+                    ),
+                ]))
+            })
+            .collect();
+        let hint = if items.is_empty() {
+            Span::styled(tr("No task running."), palette::fg(Color::DarkGray))
+        } else {
+            Span::styled(
+                tr("Press c to cancel the selected task, ESC to close."),
+                palette::fg(Color::DarkGray),
+            )
+        };
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
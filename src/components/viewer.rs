@@ -1,176 +1,3050 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
 use approx::{abs_diff_eq, AbsDiffEq};
-use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use ndarray::{prelude::*, s, Dimension, IxDyn, Slice, SliceInfo, SliceInfoElem};
+use color_eyre::eyre::{eyre, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ndarray::{prelude::*, s, Dimension, IxDyn, Slice, SliceInfo, SliceInfoElem, Zip};
 use ratatui::{prelude::*, widgets::*};
 use ratatui_macros::line;
+use tokio::sync::mpsc::UnboundedSender;
 use tui_input::{backend::crossterm::EventHandler, Input};
+use unicode_width::UnicodeWidthStr;
+
+use super::{
+    bookmarks::Bookmarks, formatting::FormattingMenu, frames::Frames, heatmap::Heatmap,
+    histogram::Histogram, pivot::Pivot, select::Select, summary::Summary, weights::Weights,
+    Component,
+};
+use crate::{
+    action::Action,
+    chart, compute,
+    config::{Config, Formatting, TotalColumn, TotalRow},
+    data::{self, open_file_rw, Aggregation, Data},
+    i18n::tr,
+    journal::Journal,
+    keymap::{self, Context},
+    labels::Dictionary,
+    palette,
+    rollup::read_mapping,
+    stats::{nanmean, nansum, Stats},
+    tasks::Tasks,
+    trace_dbg,
+    units::{self, Conversion},
+    utils::{
+        copy_to_clipboard, csv_field, ellipsize, fuzzy_matches, get_config_dir, run_command_async,
+    },
+    view::ViewDefinition,
+};
+
+/// Bounds on the width of the row label column.
+const MIN_LABEL_WIDTH: usize = 8;
+const MAX_LABEL_WIDTH: usize = 30;
+/// Narrowest width of a value column.
+const MIN_COLUMN_WIDTH: usize = 5;
+
+/// Number of bins along the rows of an overview.
+const OVERVIEW_ROW_BINS: usize = 100;
+/// Number of bins along the columns of an overview.
+const OVERVIEW_COL_BINS: usize = 50;
+
+/// Number of earlier views kept to go back to.
+const MAX_HISTORY: usize = 100;
+
+/// Most decimals values are shown with.
+const MAX_DECIMALS: usize = 12;
+
+/// Shown in place of missing values.
+pub const MISSING: &str = "·";
+
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Editing,
+    Selection,
+    Pivot,
+    Frames,
+    Bookmarks,
+    Weights,
+    Heatmap,
+    Histogram,
+    Formatting,
+}
+
+/// Row labels, column labels and values of a block of cells.
+pub type BlockValues = (Vec<String>, Vec<String>, Vec<Vec<f64>>);
+
+/// File format the current slice is exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SliceFormat {
+    Markdown,
+    /// Records of `{row_label, col_label, value}` with the dataset, units and fixed indices
+    Json,
+    /// Row labels followed by the values, without totals
+    Csv,
+    /// One cell per line with its row and column labels, for screen readers
+    Text,
+}
+
+impl SliceFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SliceFormat::Markdown => "md",
+            SliceFormat::Json => "json",
+            SliceFormat::Csv => "csv",
+            SliceFormat::Text => "txt",
+        }
+    }
+}
+
+/// Identifies a slice by (axis0, axis1, active_index).
+pub type SliceKey = (usize, usize, Vec<usize>);
+
+/// Identifies the row totals of a slice by (dataset, axis0, axis1, active_index, aggregations).
+pub type TotalsKey = (String, usize, usize, Vec<usize>, Vec<Aggregation>);
+
+/// Everything the table of a slice with totals depends on besides the values in the file, to
+/// tell whether a [`SliceModel`] is still current.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableKey {
+    pub name: String,
+    pub other_file: Option<String>,
+    pub axis0: usize,
+    pub axis1: usize,
+    pub index: Vec<usize>,
+    pub aggregations: Vec<Aggregation>,
+    pub weights: Option<String>,
+    pub window: Option<Range<usize>>,
+    pub bins: Option<OverviewKey>,
+    pub nesting: Option<Nesting>,
+    pub reference: Option<Reference>,
+    pub row_filter: RowFilter,
+    pub other_share: f64,
+    pub top_rows_other: bool,
+    pub unit_scale: Option<Conversion>,
+    pub cumulative: bool,
+    pub edits: HashMap<Vec<usize>, f64>,
+}
+
+/// Identifies a block of cells by (rows, columns).
+pub type BlockKey = (Range<usize>, Range<usize>);
+
+/// Identifies the bins of an overview by (row bins, column bins, drilled-in window).
+pub type OverviewKey = (
+    Vec<Range<usize>>,
+    Vec<Range<usize>>,
+    Option<(Range<usize>, Range<usize>)>,
+);
+
+/// The table of the current slice with its totals, computed once per change of the slice and
+/// reused by every draw, statistic and export until the next one.
+#[derive(Debug, Clone)]
+pub struct SliceModel {
+    pub key: TableKey,
+    /// Values with a totals column and a totals row, indexed as `[[col, row]]`
+    pub table: Array2<f64>,
+    /// Number of rows and value columns of the table
+    pub nrow: usize,
+    pub ncol: usize,
+    pub kept_rows: Option<Vec<usize>>,
+    pub other_row: bool,
+    pub row_keys: Vec<RowKey>,
+    /// Number of missing values
+    pub missing: usize,
+}
+
+/// Aggregated view of a slice that is too large to display cell by cell.
+///
+/// Rows and columns are grouped into contiguous bins and each cell shows the sum over its bin.
+/// Drilling into a cell opens the exact values of that bin as a window.
+#[derive(Debug, Default, Clone)]
+pub struct Overview {
+    pub row_bins: Vec<Range<usize>>,
+    pub col_bins: Vec<Range<usize>>,
+    /// Window (rows, columns) opened with the drill-in action
+    pub window: Option<(Range<usize>, Range<usize>)>,
+    /// Cached bin sums of the most recent slices
+    pub sums: Vec<(SliceKey, Array2<f64>)>,
+}
+
+impl Overview {
+    pub fn new(nrow: usize, ncol: usize) -> Self {
+        Self {
+            row_bins: Self::bins(nrow, OVERVIEW_ROW_BINS),
+            col_bins: Self::bins(ncol, OVERVIEW_COL_BINS),
+            window: None,
+            sums: Vec::new(),
+        }
+    }
+
+    fn bins(len: usize, nbins: usize) -> Vec<Range<usize>> {
+        let size = len.div_ceil(nbins).max(1);
+        (0..len)
+            .step_by(size)
+            .map(|start| start..(start + size).min(len))
+            .collect()
+    }
+
+    /// Full extent of the slice this overview was built for, as (rows, columns)
+    pub fn extent(&self) -> (usize, usize) {
+        (
+            self.row_bins.last().map(|r| r.end).unwrap_or_default(),
+            self.col_bins.last().map(|r| r.end).unwrap_or_default(),
+        )
+    }
+
+    /// Number of (rows, columns) currently displayed
+    pub fn shape(&self) -> (usize, usize) {
+        match self.window {
+            Some((ref rows, ref cols)) => (rows.len(), cols.len()),
+            None => (self.row_bins.len(), self.col_bins.len()),
+        }
+    }
+
+    pub fn label(labels: &[String], bin: &Range<usize>) -> String {
+        if bin.len() == 1 {
+            labels[bin.start].clone()
+        } else {
+            format!("{}…{}", labels[bin.start], labels[bin.end - 1])
+        }
+    }
+}
+
+/// How the current slice is compared against the reference slice.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Comparison {
+    #[default]
+    Delta,
+    Percent,
+}
+
+impl Comparison {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Delta => "Δ",
+            Comparison::Percent => "%",
+        }
+    }
+}
+
+/// A fixed dimension pinned at an index, whose slice the current one is compared against.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub dim: usize,
+    pub index: usize,
+    pub comparison: Comparison,
+}
+
+/// Lays out a table as plain text: row labels left-aligned, values right-aligned.
+pub fn text_table(columns: &[String], rows: &[String], cells: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.width()).collect();
+    for (label, row) in rows.iter().zip(cells) {
+        widths[0] = widths[0].max(label.width());
+        for (j, c) in row.iter().enumerate() {
+            if let Some(w) = widths.get_mut(j + 1) {
+                *w = (*w).max(c.width());
+            }
+        }
+    }
+    let line = |label: &str, values: &[String]| {
+        let mut line = format!(
+            "{label}{}",
+            " ".repeat(widths[0].saturating_sub(label.width()))
+        );
+        for (v, w) in values.iter().zip(&widths[1..]) {
+            line.push_str(&format!("  {}{v}", " ".repeat(w.saturating_sub(v.width()))));
+        }
+        line.trim_end().to_string()
+    };
+    std::iter::once(line(&columns[0], &columns[1..]))
+        .chain(rows.iter().zip(cells).map(|(r, c)| line(r, c)))
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+/// Lays out a table as Markdown, with the totals and values right-aligned.
+pub fn markdown_table(columns: &[String], rows: &[String], cells: &[Vec<String>]) -> String {
+    let escape = |s: &str| s.replace('|', "\\|");
+    let line = |label: &str, values: &[String]| {
+        let values: Vec<String> = values.iter().map(|v| escape(v)).collect();
+        format!("| {} | {} |\n", escape(label), values.join(" | "))
+    };
+    let mut table = line(&columns[0], &columns[1..]);
+    table.push_str(&format!("|:---|{}\n", "---:|".repeat(columns.len() - 1)));
+    for (label, row) in rows.iter().zip(cells) {
+        table.push_str(&line(label, row));
+    }
+    table
+}
+
+/// Display positions of the rows shown in a viewport of `height` rows out of `len`, scrolled
+/// to `offset` unless the `selected` position is out of view, in which case it is scrolled just
+/// enough to show it.
+pub fn row_window(
+    offset: usize,
+    selected: Option<usize>,
+    height: usize,
+    len: usize,
+) -> Range<usize> {
+    let height = height.max(1);
+    let offset = match selected {
+        Some(s) if s < offset => s,
+        Some(s) if s >= offset + height => s + 1 - height,
+        _ => offset,
+    }
+    .min(len.saturating_sub(height));
+    offset..(offset + height).min(len)
+}
+
+/// Order to lay out a table like that of [`Viewer::values`], whose total column comes first and
+/// total row last, with the totals where configured: positions of its `columns` value columns,
+/// the total after the first `fitted` when last, and of its `rows` rows.
+pub fn totals_layout(
+    columns: usize,
+    rows: usize,
+    fitted: usize,
+    total_column: TotalColumn,
+    total_row: TotalRow,
+) -> (Vec<usize>, Vec<usize>) {
+    let column_order = match total_column {
+        _ if columns == 0 => vec![],
+        TotalColumn::First => (0..columns).collect(),
+        TotalColumn::Last => {
+            let fitted = fitted.clamp(1, columns);
+            (1..fitted)
+                .chain(std::iter::once(0))
+                .chain(fitted..columns)
+                .collect()
+        }
+    };
+    let row_order = match total_row {
+        TotalRow::Bottom => (0..rows).collect(),
+        TotalRow::Top => rows
+            .checked_sub(1)
+            .into_iter()
+            .chain(0..rows.saturating_sub(1))
+            .collect(),
+    };
+    (column_order, row_order)
+}
+
+/// Layout of a dataset when the Viewer was closed, restored when it is opened again.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SavedView {
+    /// Labels of every set, so a view is only restored onto the same dataset layout
+    pub set_data: Vec<Vec<String>>,
+    pub axis0: usize,
+    pub axis1: usize,
+    pub active_index: Vec<usize>,
+    pub aggregations: Vec<Aggregation>,
+    pub selected: Option<usize>,
+    pub col: usize,
+    pub selected_col: usize,
+    pub formatting: Formatting,
+    pub unit_scale: Option<Conversion>,
+    pub reference: Option<Reference>,
+    pub sort_by_total: Option<bool>,
+    pub cumulative: bool,
+}
+
+/// A named view of a dataset to jump back to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub dataset: String,
+    pub view: SavedView,
+}
+
+/// Which rows of the slice are shown on their own.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RowFilter {
+    #[default]
+    All,
+    /// Rows under `config.other_share` percent of the total are grouped into "Other"
+    GroupSmall,
+    /// Only the given number of rows with the largest totals
+    Top(usize),
+}
+
+/// Change applied to every cell of a block at once, in write mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transform {
+    /// Multiplied by a factor; missing values stay missing
+    Scale(f64),
+    /// Set to a value
+    Set(f64),
+}
+
+impl Transform {
+    /// Parses `*1.05` as a factor, `=2.5` as a value and `0` as zeroing out.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let number = |t: &str| {
+            t.trim()
+                .parse::<f64>()
+                .map_err(|_| eyre!("{} is not a number", t.trim()))
+        };
+        if let Some(factor) = text.strip_prefix('*') {
+            Ok(Transform::Scale(number(factor)?))
+        } else if let Some(value) = text.strip_prefix('=') {
+            Ok(Transform::Set(number(value)?))
+        } else if number(text).is_ok_and(|v| v == 0.0) {
+            Ok(Transform::Set(0.0))
+        } else {
+            Err(eyre!("Expected *factor, =value or 0, not {text}"))
+        }
+    }
+
+    pub fn apply(self, value: f64) -> f64 {
+        match self {
+            Transform::Scale(factor) => value * factor,
+            Transform::Set(v) => v,
+        }
+    }
+}
+
+impl std::fmt::Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Transform::Scale(factor) => write!(f, "Multiply by {factor}"),
+            Transform::Set(v) if *v == 0.0 => write!(f, "Zero out"),
+            Transform::Set(v) => write!(f, "Set to {v}"),
+        }
+    }
+}
+
+/// How long the Viewer took to open its dataset and to read, format and draw the slice, shown
+/// with `Space d` to report slow files with numbers.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    pub open: Duration,
+    /// Reading the values in view, with their aggregations and totals, or zero if the last draw
+    /// showed values already read
+    pub read: Duration,
+    /// Labelling and formatting the rows in view
+    pub format: Duration,
+    /// The whole previous draw, including reading and formatting
+    pub draw: Duration,
+}
+
+impl Timings {
+    pub fn lines(&self) -> Vec<String> {
+        let ms = |d: Duration| format!("{:>8.1} ms", d.as_secs_f64() * 1000.0);
+        vec![
+            format!("{:<7}{}", tr("open"), ms(self.open)),
+            format!("{:<7}{}", tr("read"), ms(self.read)),
+            format!("{:<7}{}", tr("format"), ms(self.format)),
+            format!("{:<7}{}", tr("draw"), ms(self.draw)),
+        ]
+    }
+}
+
+/// A second row dimension the rows are grouped by, each group shown as a row of subtotals above
+/// its members.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Nesting {
+    pub dim: usize,
+    /// Groups whose members are hidden
+    pub collapsed: HashSet<usize>,
+}
+
+/// What a row of a grouped table holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowKey {
+    /// Subtotals of the group at this index of the grouping dimension
+    Group(usize),
+    /// Row at the second index within the group at the first
+    Member(usize, usize),
+}
+
+/// `table`, with totals, keeping rows `keep` and, if `other`, summing the others into one row
+/// before the totals. The totals are those of every row.
+pub fn group_rows(table: &Array2<f64>, keep: &[usize], other: bool) -> Array2<f64> {
+    let (cols, rows) = table.dim();
+    let n = keep.len() + other as usize;
+    let mut grouped = Array2::zeros((cols, n + 1));
+    for (i, &r) in keep.iter().enumerate() {
+        grouped.column_mut(i).assign(&table.column(r));
+    }
+    if other {
+        for r in (0..rows - 1).filter(|r| !keep.contains(r)) {
+            grouped
+                .column_mut(keep.len())
+                .zip_mut_with(&table.column(r), |o, &v| {
+                    if !v.is_nan() {
+                        *o += v;
+                    }
+                });
+        }
+    }
+    grouped.column_mut(n).assign(&table.column(rows - 1));
+    grouped
+}
+
+/// Tables `a` and `b`, with totals, side by side: each column of `a` is followed by that of
+/// `b` and their difference. The totals are the differences of those of `a` and `b`.
+pub fn interleave(a: &Array2<f64>, b: &Array2<f64>) -> Array2<f64> {
+    let (cols, rows) = a.dim();
+    let mut table = Array2::zeros((3 * (cols - 1) + 1, rows));
+    for c in 0..cols {
+        let (x, y) = (a.row(c), b.row(c));
+        if c == cols - 1 {
+            table.row_mut(3 * c).assign(&(&x - &y));
+        } else {
+            table.row_mut(3 * c).assign(&x);
+            table.row_mut(3 * c + 1).assign(&y);
+            table.row_mut(3 * c + 2).assign(&(&x - &y));
+        }
+    }
+    table
+}
+
+/// Relative change of `current` versus `reference` in percent.
+///
+/// Divides by the magnitude of the reference so that increases are always positive. A change
+/// from zero is undefined and returned as NaN, unless both values are zero.
+pub fn percent_change(current: f64, reference: f64) -> f64 {
+    if reference == 0.0 {
+        if current == 0.0 {
+            0.0
+        } else {
+            f64::NAN
+        }
+    } else {
+        (current - reference) / reference.abs() * 100.0
+    }
+}
+
+/// Whether `action` can move the Viewer to another slice of the dataset, i.e. change its axes or
+/// indices, which is what the history of views records.
+fn moves_slice(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::NextAxis(_)
+            | Action::PreviousAxis(_)
+            | Action::SetIndex(..)
+            | Action::IncrementAxis(_)
+            | Action::DecrementAxis(_)
+            | Action::SwapAxes
+            | Action::Pivot(..)
+            | Action::JumpToBookmark(_)
+    )
+}
+
+/// Columns of `labels` given as `first-last`, `first..last` or a single label, e.g.
+/// `2025-2035`, or `None` when `text` is empty.
+pub fn parse_column_range(text: &str, labels: &[String]) -> Result<Option<Range<usize>>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let position = |label: &str| labels.iter().position(|l| l == label.trim());
+    if let Some(i) = position(text) {
+        return Ok(Some(i..i + 1));
+    }
+    // Labels may have dashes of their own, so every split is tried
+    for separator in ["..", "–", "-"] {
+        for (at, _) in text.match_indices(separator) {
+            let (first, last) = (&text[..at], &text[at + separator.len()..]);
+            if let (Some(a), Some(b)) = (position(first), position(last)) {
+                return Ok(Some(a.min(b)..a.max(b) + 1));
+            }
+        }
+    }
+    Err(eyre!("No columns match {text:?}"))
+}
+
+#[derive(Debug, Default)]
+pub struct Viewer {
+    pub action_tx: Option<UnboundedSender<Action>>,
+    pub file: String,
+    pub config: Config,
+    pub name: String,
+    pub focus: bool,
+    pub data: Option<Data>,
+    pub ncol: usize,
+    pub nrow: usize,
+    pub state: TableState,
+    pub axis0: usize,
+    pub axis1: usize,
+    /// First value column shown
+    pub col: usize,
+    /// Column of the selected cell
+    pub selected_col: usize,
+    pub row_filter: RowFilter,
+    /// Rows of the slice shown on their own when they are filtered, from the last read
+    pub kept_rows: Option<Vec<usize>>,
+    /// Whether the rows left out by the filter are summed into an "Other" row
+    pub other_row: bool,
+    /// Labels of the rows shown above the others
+    pub pinned: Vec<String>,
+    /// Dimension the rows are grouped by, if any
+    pub nesting: Option<Nesting>,
+    /// What each row of the table holds when the rows are grouped, from the last read
+    pub row_keys: Vec<RowKey>,
+    /// Cell where the rectangular selection started, as (displayed row, column)
+    pub anchor: Option<(usize, usize)>,
+    /// Value columns the exports and the statistics are limited to, if not every one
+    pub column_range: Option<Range<usize>>,
+    pub row: usize,
+    pub active_index: Vec<usize>,
+    /// How each dimension that is not displayed is reduced, by set; missing entries are fixed
+    pub aggregations: Vec<Aggregation>,
+    /// Dataset weighting the weighted means, with the same sets as the open one
+    pub weights: Option<Data>,
+    /// Whether the roll-ups of the config are left out, to see every member of the sets
+    pub rollups_off: bool,
+    /// Long labels of set codes, from the dictionary of the config
+    pub dictionary: Option<Dictionary>,
+    /// Whether set codes are shown rather than their long labels
+    pub codes_only: bool,
+    /// Values typed in for cells, by index in the order of the sets, until they are saved
+    pub edits: HashMap<Vec<usize>, f64>,
+    /// New values of the cells of a transform waiting to be confirmed
+    pub transformed: HashMap<Vec<usize>, f64>,
+    /// File whose dataset of the same name is shown next to the open one, column by column
+    pub other_file: Option<String>,
+    /// The dataset in `other_file`, if it has the same sets
+    pub other: Option<Data>,
+    /// How zeros, whole numbers and missing values are written
+    pub formatting: Formatting,
+    pub input: Input,
+    pub mode: Mode,
+    pub summary: Summary,
+    pub select: Select,
+    pub pivot: Pivot,
+    pub frames: Frames,
+    pub bookmark_list: Bookmarks,
+    pub weight_list: Weights,
+    pub formatting_menu: FormattingMenu,
+    pub heatmap: Heatmap,
+    pub histogram: Histogram,
+    /// Outcome of the last command, shown until the next key press
+    pub message: Option<String>,
+    pub page_height: Option<usize>,
+    /// Number of value columns shown in the last draw
+    pub page_width: Option<usize>,
+    pub overview: Option<Overview>,
+    /// Extent of the last slice over `confirm_slice_cells` confirmed to be read, as (rows,
+    /// columns)
+    pub confirmed_extent: Option<(usize, usize)>,
+    /// Extent of the last slice asked about, so it is asked about once
+    pub asked_extent: Option<(usize, usize)>,
+    /// Row totals of the most recent slices read a window of columns at a time, before unit
+    /// scaling
+    pub row_totals: Vec<(TotalsKey, Array1<f64>)>,
+    /// Table of the slice last read, with its totals
+    pub model: Option<SliceModel>,
+    /// Table that could not be read, with the error, not read again until the slice changes
+    pub failed: Option<(TableKey, String)>,
+    /// Whether the last failure was reported, so it is reported once
+    pub failure_reported: bool,
+    /// Summary of the selected block, with the block, the table and the row order it was
+    /// worked out from
+    pub block_readout: Option<(BlockKey, TableKey, Vec<usize>, String)>,
+    /// Values of the last draw, shown again while stepping quickly through a dimension
+    pub last_items: Option<Vec<Vec<f64>>>,
+    /// Until when the slice is not read again, pushed back by every step through a dimension
+    pub settle_at: Option<Instant>,
+    pub timings: Timings,
+    /// Area of the last draw, to lay the table out again at once when the terminal is resized
+    pub area: Rect,
+    /// Show the timings over the top right of the table
+    pub show_timings: bool,
+    /// Whether the last draw changed the number of columns that fit, so the window read is stale
+    pub redraw: bool,
+    pub unit_scale: Option<Conversion>,
+    pub cumulative: bool,
+    /// Last index used for each set name, along with the set's labels
+    pub sticky_indices: HashMap<String, (Vec<String>, usize)>,
+    pub reference: Option<Reference>,
+    /// Overrides `config.sort_by_total` for the current dataset
+    pub sort_by_total: Option<bool>,
+    /// Overrides `config.decimals`
+    pub decimals: Option<usize>,
+    /// Display order of the rows from the last read
+    pub row_order: Vec<usize>,
+    /// Last view of each closed dataset, by name
+    pub views: HashMap<String, SavedView>,
+    /// Named views, in the order they were added
+    pub bookmarks: Vec<Bookmark>,
+    /// Views of the dataset before each change of axes or indices, most recent last
+    pub back_history: Vec<SavedView>,
+    /// Views left by going back, most recent last
+    pub forward_history: Vec<SavedView>,
+    /// View to open the next dataset with, from `--view`
+    pub pending_view: Option<ViewDefinition>,
+    /// Cell to open the next dataset at, from a search, with the index of each set
+    pub pending_cell: Option<Vec<Option<usize>>>,
+    /// Operations running in the background, shared with the App
+    pub tasks: Tasks,
+    pub journal: Journal,
+}
+
+impl Viewer {
+    /// The open dataset, or an error if none could be read.
+    pub fn dataset(&self) -> Result<&Data> {
+        self.data.as_ref().ok_or_else(|| {
+            color_eyre::eyre::eyre!("Unable to extract HDF dataset from internal Option<Data>.")
+        })
+    }
+
+    pub fn initialize_state(&mut self) -> Result<()> {
+        let data = self.data.as_ref().ok_or_else(|| {
+            color_eyre::eyre::eyre!("Unable to extract HDF dataset from internal Option<Data>.")
+        })?;
+        if self.axis0 == self.axis1 {
+            self.axis0 = data.ndims - 1;
+            self.axis1 = 0;
+        };
+        self.ncol = data.set_data[self.axis0].len();
+        self.nrow = data.set_data[self.axis1].len();
+        if self.nrow * self.ncol > self.config.max_slice_cells {
+            if self.overview.as_ref().map(|o| o.extent()) != Some((self.nrow, self.ncol)) {
+                log::info!(
+                    "Slice of {} x {} exceeds {} cells, opening overview",
+                    self.nrow,
+                    self.ncol,
+                    self.config.max_slice_cells
+                );
+                self.overview = Some(Overview::new(self.nrow, self.ncol));
+            }
+        } else {
+            self.overview = None;
+        }
+        if let Some(ref o) = self.overview {
+            (self.nrow, self.ncol) = o.shape();
+        }
+        // log::debug!("{:?}", data.set_data[self.axis1]);
+        if self.active_index.is_empty() {
+            self.active_index = self.initial_indices();
+        }
+        let set_data: Vec<Vec<String>> = (0..data.ndims).map(|i| self.set_labels(i)).collect();
+        self.summary.refresh(
+            Data {
+                set_data: set_data.clone(),
+                ..data.clone()
+            },
+            self.active_index.clone(),
+            self.axis0,
+            self.axis1,
+        )?;
+        self.summary.aggregations.clone_from(&self.aggregations);
+        self.summary.reference = self.reference.as_ref().map(|r| {
+            (
+                r.dim,
+                format!("{} vs {}", r.comparison.symbol(), set_data[r.dim][r.index]),
+            )
+        });
+        self.select.refresh(set_data, data.set_names.clone());
+        let view = self.view_description();
+        self.journal.record(&self.name, view);
+        Ok(())
+    }
+
+    /// Index each dimension starts at: the sticky index if enabled, otherwise the configured one.
+    pub fn initial_indices(&self) -> Vec<usize> {
+        let Some(ref data) = self.data else {
+            return vec![];
+        };
+        data.set_names
+            .iter()
+            .zip(data.set_data.iter())
+            .map(|(name, labels)| match self.sticky_indices.get(name) {
+                Some((l, i)) if self.config.sticky_indices && l == labels => *i,
+                _ => self.config.default_index(name, labels),
+            })
+            .collect()
+    }
+
+    /// The current table as a shareable view definition.
+    pub fn view_definition(&self) -> Result<ViewDefinition> {
+        let d = self.dataset()?;
+        let fixed = (0..d.ndims)
+            .filter(|&i| i != self.axis0 && i != self.axis1)
+            .filter(|&i| self.aggregation(i) == Aggregation::Fix)
+            .filter_map(|i| {
+                let label = d.set_data[i].get(*self.active_index.get(i)?)?;
+                Some((d.set_names[i].clone(), label.clone()))
+            })
+            .collect();
+        Ok(ViewDefinition {
+            dataset: self.name.clone(),
+            rows: d.set_names[self.axis1].clone(),
+            columns: d.set_names[self.axis0].clone(),
+            fixed,
+            aggregate: (0..d.ndims)
+                .filter(|&i| i != self.axis0 && i != self.axis1)
+                .filter(|&i| self.aggregation(i) != Aggregation::Fix)
+                .map(|i| (d.set_names[i].clone(), self.aggregation(i)))
+                .collect(),
+            weights: self.weights.as_ref().map(|w| w.name.clone()),
+            units: self.unit_scale.as_ref().map(|c| c.to.clone()),
+            cumulative: self.cumulative,
+            formatted: self.formatting.dash_zeros && self.formatting.integers,
+            formatting: Some(self.formatting),
+            sort_by_total: self.sort_by_total,
+            decimals: self.decimals,
+        })
+    }
+
+    /// Switches to the axes, indices and options of `view`.
+    pub fn apply_view(&mut self, view: &ViewDefinition) -> Result<()> {
+        let resolved = view.resolve(self.dataset()?, &self.config.unit_conversions)?;
+        let defaults = self.initial_indices();
+        self.axis0 = resolved.axis0;
+        self.axis1 = resolved.axis1;
+        self.active_index = resolved
+            .active_index
+            .into_iter()
+            .zip(defaults)
+            .map(|(index, default)| index.unwrap_or(default))
+            .collect();
+        self.aggregations = resolved.aggregations;
+        if let Some(ref name) = view.weights {
+            if self.weights.as_ref().map(|w| &w.name) != Some(name) {
+                self.set_weights(name)?;
+            }
+        }
+        self.unit_scale = resolved.unit_scale;
+        self.cumulative = view.cumulative;
+        self.formatting = view
+            .formatting
+            .unwrap_or_else(|| Formatting::from_formatted(view.formatted));
+        self.sort_by_total = view.sort_by_total;
+        if view.decimals.is_some() {
+            self.decimals = view.decimals;
+        }
+        self.reference = None;
+        self.overview = None;
+        Ok(())
+    }
+
+    /// Writes the view definition of the current table to the current directory.
+    pub fn export_view(&self) -> Result<PathBuf> {
+        let path = self.view_path();
+        self.view_definition()?.save(&path)?;
+        Ok(path)
+    }
+
+    pub fn view_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.view.json", self.export_name()))
+    }
+
+    /// Name of the dataset usable in file names.
+    pub fn export_name(&self) -> String {
+        // Datasets side by side are joined with `+`
+        self.name
+            .split(',')
+            .map(|name| name.trim().trim_matches('/').replace('/', "_"))
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// The displayed dimensions and the fixed indices, e.g. `Area × Year; Fuel=Gas [PJ]`.
+    pub fn view_description(&self) -> String {
+        let Some(ref d) = self.data else {
+            return String::default();
+        };
+        let fixed: Vec<String> = (0..d.ndims)
+            .filter(|&i| i != self.axis0 && i != self.axis1)
+            .filter_map(|i| {
+                let label = match self.aggregation(i) {
+                    Aggregation::Fix => d.set_data[i].get(*self.active_index.get(i)?)?.clone(),
+                    aggregation => aggregation.to_string(),
+                };
+                Some(format!("{}={}", d.set_names[i], label))
+            })
+            .collect();
+        let mut view = format!("{} × {}", d.set_names[self.axis1], d.set_names[self.axis0]);
+        if !fixed.is_empty() {
+            view = format!("{view}; {}", fixed.join(", "));
+        }
+        let units = self.units();
+        if !units.is_empty() {
+            view = format!("{view} [{units}]");
+        }
+        view
+    }
+
+    /// Reads the window `rows` x `cols` of the slice at `index`, indexed as `[[col, row]]`.
+    ///
+    /// Entries of `index` for the displayed axes are ignored.
+    pub fn read_slice(
+        &self,
+        index: &[usize],
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<Array2<f64>> {
+        self.dataset()?.read_aggregated_slice(
+            self.axis0,
+            self.axis1,
+            index,
+            &self.aggregations,
+            self.weights.as_ref(),
+            rows,
+            cols,
+        )
+    }
+
+    /// How dimension `i` is reduced when it is not displayed.
+    pub fn aggregation(&self, i: usize) -> Aggregation {
+        self.aggregations.get(i).copied().unwrap_or_default()
+    }
+
+    /// Cycles dimension `i` between fixed at its index, summed, averaged over and, once weights
+    /// are chosen, weighted averaged over.
+    pub fn cycle_aggregation(&mut self, i: usize) {
+        if i >= self.active_index.len() || i == self.axis0 || i == self.axis1 {
+            return;
+        }
+        self.aggregations
+            .resize(self.active_index.len(), Aggregation::Fix);
+        self.aggregations[i] = self.aggregations[i].next();
+        if self.aggregations[i] == Aggregation::Weighted && self.weights.is_none() {
+            self.aggregations[i] = self.aggregations[i].next();
+        }
+        // Overview sums are cached by index only
+        self.overview = None;
+    }
+
+    /// Opens the dataset or expression `name`, with the sets of the config rolled up unless
+    /// they are turned off.
+    pub fn open_data(&self, name: &str) -> Result<Data> {
+        self.open_data_in(&self.file, name)
+    }
+
+    /// Like [`Viewer::open_data`], from `file`.
+    pub fn open_data_in(&self, file: &str, name: &str) -> Result<Data> {
+        let mut data = compute::open(Path::new(file), name)?;
+        if !self.rollups_off && !self.config.rollups.is_empty() {
+            // Relative paths are from the config directory
+            let mappings = self
+                .config
+                .rollups
+                .iter()
+                .map(|(set, path)| Ok((set.clone(), read_mapping(&get_config_dir().join(path))?)))
+                .collect::<Result<HashMap<_, _>>>()?;
+            data.roll_up(&mappings);
+        }
+        Ok(data)
+    }
+
+    /// Labels of the set of dimension `dim` as shown, the long ones of the dictionary unless
+    /// codes are shown.
+    pub fn set_labels(&self, dim: usize) -> Vec<String> {
+        let Some(labels) = self.data.as_ref().and_then(|d| d.set_data.get(dim)) else {
+            return vec![];
+        };
+        match self.dictionary {
+            Some(ref dictionary) if !self.codes_only => dictionary.labels(labels),
+            _ => labels.clone(),
+        }
+    }
+
+    /// Reads the dictionary of the config, if any. Relative paths are from the config
+    /// directory.
+    pub fn read_dictionary(&mut self) -> Result<()> {
+        self.dictionary = match self.config.label_dictionary {
+            Some(ref path) => Some(Dictionary::read(&get_config_dir().join(path))?),
+            None => None,
+        };
+        Ok(())
+    }
+
+    /// Switches between the long labels of the dictionary and set codes.
+    pub fn toggle_long_labels(&mut self) {
+        if self.dictionary.is_none() {
+            self.message = Some("No label dictionary is configured".into());
+            return;
+        }
+        self.codes_only = !self.codes_only;
+        self.last_items = None;
+        self.message = Some(if self.codes_only {
+            "Showing set codes".into()
+        } else {
+            "Showing long labels".into()
+        });
+    }
+
+    /// Turns the roll-ups of the config off or back on, keeping the axes.
+    pub fn toggle_rollups(&mut self) -> Result<()> {
+        if self.config.rollups.is_empty() {
+            self.message = Some("No roll-up is configured".into());
+            return Ok(());
+        }
+        self.rollups_off = !self.rollups_off;
+        let started = Instant::now();
+        self.data = Some(self.open_data(&self.name)?);
+        self.timings.open = started.elapsed();
+        log::info!("Opened {} in {:?}", self.name, self.timings.open);
+        if let Some(name) = self.weights.take().map(|w| w.name) {
+            self.set_weights(&name)?;
+        }
+        if let Err(e) = self.open_other() {
+            log::warn!("Unable to compare files: {e}");
+        }
+        // Indices and bins refer to the labels before
+        self.active_index = self.initial_indices();
+        self.overview = None;
+        self.row_totals.clear();
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        self.last_items = None;
+        if let Some(ref mut n) = self.nesting {
+            n.collapsed.clear();
+        }
+        (self.row, self.col, self.selected_col) = (0, 0, 0);
+        self.state.select(Some(0));
+        self.message = Some(if self.rollups_off {
+            "Showing every member".into()
+        } else {
+            "Showing rolled up groups".into()
+        });
+        Ok(())
+    }
+
+    /// Shows the dataset in `file` next to the open one, or stops if `file` is empty.
+    pub fn compare_file(&mut self, file: &str) -> Result<()> {
+        self.other_file = (!file.is_empty()).then(|| file.to_string());
+        self.open_other()?;
+        self.last_items = None;
+        (self.col, self.selected_col) = (0, 0);
+        self.message = Some(match self.other_file {
+            Some(ref file) => format!("Columns are A = {}, B = {file} and Δ = A - B", self.file),
+            None => "Stopped comparing files".into(),
+        });
+        Ok(())
+    }
+
+    /// Opens the dataset in `other_file`, which must have the same sets.
+    fn open_other(&mut self) -> Result<()> {
+        self.other = None;
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        let Some(ref file) = self.other_file else {
+            return Ok(());
+        };
+        let other = self.open_data_in(file, &self.name)?;
+        let d = self.dataset()?;
+        if other.set_names != d.set_names || other.set_data != d.set_data {
+            return Err(eyre!(
+                "{} in {file} does not have the same dimensions",
+                d.name
+            ));
+        }
+        self.other = Some(other);
+        Ok(())
+    }
+
+    /// Index in the order of the sets of the cell at visible row `i` and column `col`, or why it
+    /// cannot be edited. Only the values of a slice shown as stored can be edited.
+    pub fn stored_cell(&self, i: usize, col: usize) -> Result<Vec<usize>> {
+        let d = self.dataset()?;
+        if d.expression.is_some()
+            || d.combination.is_some()
+            || d.rollups.iter().any(Option::is_some)
+        {
+            return Err(eyre!(
+                "Only datasets read from the file, without roll-ups, can be edited"
+            ));
+        }
+        let fixed = (0..d.ndims)
+            .filter(|&k| k != self.axis0 && k != self.axis1)
+            .all(|k| self.aggregations.get(k).copied().unwrap_or_default() == Aggregation::Fix);
+        if !fixed
+            || self.overview.is_some()
+            || self.nesting().is_some()
+            || self.kept_rows.is_some()
+            || self.reference_index().is_some()
+            || self.comparing_files()
+            || self.unit_scale.is_some()
+            || self.cumulative
+        {
+            return Err(eyre!(
+                "Only cells of a plain slice can be edited, without sums, bins, groups, filters, \
+                 comparisons, unit scaling or running sums"
+            ));
+        }
+        let row = self.row_order.get(i).copied();
+        match row {
+            Some(row) if row < self.nrow && col < self.ncol => {
+                let mut index = self.active_index.clone();
+                (index[self.axis1], index[self.axis0]) = (row, col);
+                Ok(index)
+            }
+            _ => Err(eyre!("Totals cannot be edited")),
+        }
+    }
+
+    /// Whether `cell` is on the slice at `index`, which is not summed or averaged over any
+    /// dimension.
+    fn on_slice(&self, cell: &[usize], index: &[usize]) -> bool {
+        self.overview.is_none()
+            && cell.len() == index.len()
+            && (0..cell.len())
+                .filter(|&k| k != self.axis0 && k != self.axis1)
+                .all(|k| {
+                    self.aggregations.get(k).copied().unwrap_or_default() == Aggregation::Fix
+                        && cell[k] == index[k]
+                })
+    }
+
+    /// Shows the unsaved edits on `data`, the slice at `index` from column `first`.
+    fn show_edits(&self, data: &mut Array2<f64>, index: &[usize], first: usize) {
+        for (cell, &value) in self.edits.iter() {
+            if !self.on_slice(cell, index) || cell[self.axis0] < first {
+                continue;
+            }
+            if let Some(v) = data.get_mut((cell[self.axis0] - first, cell[self.axis1])) {
+                *v = value;
+            }
+        }
+    }
+
+    /// Keeps the value typed in for the selected cell, to be saved along with the others.
+    /// An empty value makes the cell missing.
+    fn edit_cell(&mut self) -> Result<()> {
+        let i = self.state.selected().unwrap_or_default();
+        let index = self.stored_cell(i, self.selected_col)?;
+        let text = self.input.value().trim();
+        let value = if text.is_empty() {
+            f64::NAN
+        } else {
+            text.parse::<f64>()
+                .map_err(|_| eyre!("{text} is not a number"))?
+        };
+        self.edits.insert(index, value);
+        self.last_items = None;
+        self.message = Some(format!(
+            "{} unsaved edits, Space S to save them",
+            self.edits.len()
+        ));
+        Ok(())
+    }
+
+    /// Writes the edited cells to the file. Returns how many were written.
+    pub fn save_edits(&mut self) -> Result<usize> {
+        if self.edits.is_empty() {
+            return Ok(0);
+        }
+        let edits = std::mem::take(&mut self.edits);
+        if let Err(e) = self.write_cells(&edits) {
+            self.edits = edits;
+            return Err(e);
+        }
+        Ok(edits.len())
+    }
+
+    /// Writes `cells` to the file, dropping the unsaved edits of the same cells.
+    fn write_cells(&mut self, cells: &HashMap<Vec<usize>, f64>) -> Result<()> {
+        self.dataset()?.write_values(cells)?;
+        self.edits.retain(|cell, _| !cells.contains_key(cell));
+        // Totals and sums read before the edits are stale
+        self.row_totals.clear();
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        if let Some(ref mut o) = self.overview {
+            o.sums.clear();
+        }
+        self.last_items = None;
+        Ok(())
+    }
+
+    /// Writes the open dataset, computed or rolled up, to the file as the new dataset `name`.
+    ///
+    /// Rolled up sets become new sets named after the set and its mapping file, e.g.
+    /// `Area_regions`.
+    ///
+    /// The documentation of a computed dataset is its expression, cut to fit in an attribute.
+    /// Returns whether it was cut.
+    pub fn save_dataset(&self, name: &str) -> Result<bool> {
+        let d = self.dataset()?;
+        let set_names: Vec<String> = d
+            .set_names
+            .iter()
+            .enumerate()
+            .map(
+                |(i, set)| match (d.rollups.get(i), self.config.rollups.get(set)) {
+                    (Some(Some(_)), Some(path)) => format!(
+                        "{set}_{}",
+                        path.file_stem().unwrap_or_default().to_string_lossy()
+                    ),
+                    _ => set.clone(),
+                },
+            )
+            .collect();
+        let doc = match (&d.expression, &d.combination) {
+            (Some(e), _) => e.text.clone(),
+            (_, Some(_)) => self.name.clone(),
+            _ => d.doc.clone(),
+        };
+        let cut = data::attribute_prefix(&doc);
+        let f = open_file_rw(&self.file)?;
+        d.write_as(&f, name, &set_names, cut)?;
+        Ok(cut.len() < doc.len())
+    }
+
+    /// Works out `transform` on the selected block, or on the whole slice without one, and
+    /// keeps the new values until it is confirmed. Returns what it changes, with the sum of
+    /// the cells and the slice total before and after.
+    pub fn preview_transform(&mut self, transform: Transform) -> Result<String> {
+        let (col, row) = (self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let values = self.values();
+        (self.col, self.row) = (col, row);
+        let values = values?;
+        let (rows, cols) = match self.anchor.and(self.block()) {
+            Some(block) => block,
+            None => (0..self.row_order.len(), 0..self.ncol),
+        };
+        let mut transformed = HashMap::new();
+        let (mut before, mut after) = (vec![], vec![]);
+        for i in rows {
+            for c in cols.clone() {
+                let index = self.stored_cell(i, c)?;
+                let value = values
+                    .get(i)
+                    .and_then(|v| v.get(c + 1))
+                    .copied()
+                    .unwrap_or(f64::NAN);
+                let new = transform.apply(value);
+                transformed.insert(index, new);
+                before.push(value);
+                after.push(new);
+            }
+        }
+        let (before, after) = (nansum(&before), nansum(&after));
+        let total = values
+            .last()
+            .and_then(|v| v.first())
+            .copied()
+            .unwrap_or(f64::NAN);
+        let preview = format!(
+            "{transform}: {} cells, written to the file at once. Their sum goes from {} to {}, \
+             the slice total from {} to {}.",
+            transformed.len(),
+            self.format_value(before),
+            self.format_value(after),
+            self.format_value(total),
+            self.format_value(total - before + after),
+        );
+        self.transformed = transformed;
+        Ok(preview)
+    }
+
+    /// Unsaved edits on the displayed table, as (visible row, column) in the coordinates of the
+    /// values from `self.col`, where the first column holds the totals.
+    fn edited_cells(&self) -> Vec<(usize, usize)> {
+        if self.kept_rows.is_some() || !self.row_keys.is_empty() || self.comparing_files() {
+            return vec![];
+        }
+        self.edits
+            .keys()
+            .filter(|cell| self.on_slice(cell, &self.active_index) && cell[self.axis0] >= self.col)
+            .filter_map(|cell| {
+                let i = self.row_order.iter().position(|&r| r == cell[self.axis1])?;
+                Some((i, 1 + cell[self.axis0] - self.col))
+            })
+            .collect()
+    }
+
+    /// Whether the dataset in another file is shown next to the open one. Overview sums are
+    /// cached for the open file only, so files are not compared over bins.
+    pub fn comparing_files(&self) -> bool {
+        self.other.is_some() && self.overview.is_none()
+    }
+
+    /// Weights the weighted means by the dataset `name`, which must have the same sets.
+    pub fn set_weights(&mut self, name: &str) -> Result<()> {
+        let weights = self.open_data(name)?;
+        let d = self.dataset()?;
+        if weights.set_names != d.set_names || weights.set_data != d.set_data {
+            return Err(eyre!("{name} does not have the dimensions of {}", d.name));
+        }
+        self.weights = Some(weights);
+        self.overview = None;
+        self.row_totals.clear();
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        Ok(())
+    }
+
+    /// Sums of the current slice over the overview bins, read one row bin at a time.
+    fn overview_sums(&self, overview: &Overview, index: &[usize]) -> Result<Array2<f64>> {
+        let mut sums = Array2::zeros((overview.col_bins.len(), overview.row_bins.len()));
+        let (_, ncol) = overview.extent();
+        for (r, row_bin) in overview.row_bins.iter().enumerate() {
+            let block = self.read_slice(index, row_bin.clone(), 0..ncol)?;
+            for (c, col_bin) in overview.col_bins.iter().enumerate() {
+                sums[[c, r]] = nansum(block.slice(s![col_bin.clone(), ..]));
+            }
+        }
+        Ok(sums)
+    }
+
+    /// The values currently on display, indexed as `[[col, row]]`.
+    pub fn slice(&mut self) -> Result<Array2<f64>> {
+        self.slice_at(self.active_index.clone())
+    }
+
+    /// The values on display if the fixed dimensions were at `index`.
+    pub fn slice_at(&mut self, index: Vec<usize>) -> Result<Array2<f64>> {
+        let Some(overview) = self.overview.clone() else {
+            let d = self.dataset()?;
+            let (nrow, ncol) = (d.set_data[self.axis1].len(), d.set_data[self.axis0].len());
+            return self.read_slice(&index, 0..nrow, 0..ncol);
+        };
+        if let Some((rows, cols)) = overview.window {
+            return self.read_slice(&index, rows, cols);
+        }
+        let key = (self.axis0, self.axis1, index);
+        if let Some((_, sums)) = overview.sums.iter().find(|(k, _)| *k == key) {
+            return Ok(sums.clone());
+        }
+        let sums = self.overview_sums(&overview, &key.2)?;
+        if let Some(ref mut o) = self.overview {
+            // Keep the current slice and a reference slice
+            o.sums.truncate(1);
+            o.sums.insert(0, (key, sums.clone()));
+        }
+        Ok(sums)
+    }
+
+    /// Columns `cols` of the slice at `index`, with the row totals over every column.
+    ///
+    /// Only the window is read once the row totals of the slice are known; they are computed
+    /// from a full read the first time, and kept for the current and a reference slice.
+    pub fn slice_window(
+        &mut self,
+        index: Vec<usize>,
+        cols: Range<usize>,
+    ) -> Result<(Array2<f64>, Array1<f64>)> {
+        let d = self.dataset()?;
+        let (nrow, ncol) = (d.set_data[self.axis1].len(), d.set_data[self.axis0].len());
+        let key = (
+            self.name.clone(),
+            self.axis0,
+            self.axis1,
+            index,
+            self.aggregations.clone(),
+        );
+        if let Some((_, totals)) = self.row_totals.iter().find(|(k, _)| *k == key) {
+            let totals = totals.clone();
+            return Ok((self.read_slice(&key.3, 0..nrow, cols)?, totals));
+        }
+        let data = self.read_slice(&key.3, 0..nrow, 0..ncol)?;
+        let totals = data.map_axis(Axis(0), nansum);
+        self.row_totals.truncate(1);
+        self.row_totals.insert(0, (key, totals.clone()));
+        Ok((data.slice(s![cols, ..]).to_owned(), totals))
+    }
+
+    /// Fixed index to compare against, if a reference is pinned on a dimension that is not
+    /// displayed.
+    pub fn reference_index(&self) -> Option<Vec<usize>> {
+        let r = self.reference.as_ref()?;
+        if r.dim == self.axis0
+            || r.dim == self.axis1
+            || r.dim >= self.active_index.len()
+            || self.nesting().is_some_and(|n| n.dim == r.dim)
+        {
+            return None;
+        }
+        let mut index = self.active_index.clone();
+        index[r.dim] = r.index;
+        Some(index)
+    }
+
+    /// Pins the current index of dimension `dim` as the reference, or unpins it.
+    pub fn toggle_reference(&mut self, dim: usize) {
+        if dim >= self.active_index.len() {
+            return;
+        }
+        self.reference = match self.reference {
+            Some(ref r) if r.dim == dim => None,
+            _ => Some(Reference {
+                dim,
+                index: self.active_index[dim],
+                comparison: Comparison::default(),
+            }),
+        };
+    }
+
+    pub fn cycle_comparison(&mut self) {
+        if let Some(ref mut r) = self.reference {
+            r.comparison = match r.comparison {
+                Comparison::Delta => Comparison::Percent,
+                Comparison::Percent => Comparison::Delta,
+            };
+        }
+    }
+
+    /// Appends a totals column and a totals row to `data`, indexed as `[[col, row]]`.
+    ///
+    /// Applies unit scaling and, if enabled, the running sum across columns. Row totals are
+    /// always the plain sum across columns, or `row_totals` if `data` is a window of the
+    /// columns. Missing values are left out of the sums, which are only missing when every
+    /// value is.
+    pub fn with_totals(
+        &self,
+        mut data: Array2<f64>,
+        row_totals: Option<Array1<f64>>,
+    ) -> Array2<f64> {
+        let mut row_totals = row_totals.unwrap_or_else(|| data.map_axis(Axis(0), nansum));
+        if let Some(ref c) = self.unit_scale {
+            data.mapv_inplace(|v| v * c.factor);
+            row_totals.mapv_inplace(|v| v * c.factor);
+        }
+        let (cols, rows) = data.dim();
+        let mut table = Array2::zeros((cols + 1, rows + 1));
+        table.slice_mut(s![..cols, ..rows]).assign(&data);
+        table.slice_mut(s![cols, ..rows]).assign(&row_totals);
+        if self.cumulative {
+            table
+                .slice_mut(s![..cols, ..rows])
+                .accumulate_axis_inplace(Axis(0), |&prev, curr| *curr = nansum([&prev, &*curr]));
+        }
+        let column_totals = table.slice(s![..cols, ..rows]).map_axis(Axis(1), nansum);
+        table.slice_mut(s![..cols, rows]).assign(&column_totals);
+        table[[cols, rows]] = nansum(&row_totals);
+        table
+    }
+
+    /// The grouping of the rows, unless its dimension is displayed or the slice is binned.
+    pub fn nesting(&self) -> Option<&Nesting> {
+        let n = self.nesting.as_ref()?;
+        (n.dim != self.axis0
+            && n.dim != self.axis1
+            && n.dim < self.active_index.len()
+            && self.overview.is_none())
+        .then_some(n)
+    }
+
+    /// The slice at `index` with its rows grouped by `nesting.dim`, indexed as `[[col, row]]`,
+    /// and what each row holds: every group is a row of subtotals followed by its members,
+    /// unless it is collapsed.
+    pub fn nested_slice(
+        &self,
+        index: &[usize],
+        nesting: &Nesting,
+    ) -> Result<(Array2<f64>, Vec<RowKey>)> {
+        let d = self.dataset()?;
+        let (nrow, ncol) = (d.set_data[self.axis1].len(), d.set_data[self.axis0].len());
+        // The grouping dimension is read one index at a time, whatever its aggregation
+        let mut aggregations = self.aggregations.clone();
+        if let Some(a) = aggregations.get_mut(nesting.dim) {
+            *a = Aggregation::Fix;
+        }
+        let mut index = index.to_vec();
+        let (mut columns, mut keys) = (vec![], vec![]);
+        for g in 0..d.set_data[nesting.dim].len() {
+            index[nesting.dim] = g;
+            let data = d.read_aggregated_slice(
+                self.axis0,
+                self.axis1,
+                &index,
+                &aggregations,
+                self.weights.as_ref(),
+                0..nrow,
+                0..ncol,
+            )?;
+            columns.push(data.map_axis(Axis(1), nansum));
+            keys.push(RowKey::Group(g));
+            if !nesting.collapsed.contains(&g) {
+                for r in 0..nrow {
+                    columns.push(data.column(r).to_owned());
+                    keys.push(RowKey::Member(g, r));
+                }
+            }
+        }
+        let mut nested = Array2::zeros((ncol, columns.len()));
+        for (i, c) in columns.iter().enumerate() {
+            nested.column_mut(i).assign(c);
+        }
+        Ok((nested, keys))
+    }
+
+    /// Sets the totals row of a grouped `table` to the sums of the group rows, which already
+    /// hold those of their members.
+    fn nested_totals(&self, table: &mut Array2<f64>) {
+        let (cols, rows) = table.dim();
+        for c in 0..cols {
+            table[[c, rows - 1]] = nansum(
+                self.row_keys
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, k)| matches!(k, RowKey::Group(_)))
+                    .map(|(r, _)| &table[[c, r]]),
+            );
+        }
+    }
+
+    /// The displayed slice at `index` with totals, grouped if the rows are nested, and side by
+    /// side with that of the other file if comparing files, reading every column then.
+    fn table_at(&mut self, index: Vec<usize>, window: Option<Range<usize>>) -> Result<Array2<f64>> {
+        if !self.comparing_files() {
+            return self.file_table_at(index, window);
+        }
+        let current = self.file_table_at(index.clone(), None)?;
+        // Edits are to the open file only
+        let edits = std::mem::take(&mut self.edits);
+        std::mem::swap(&mut self.data, &mut self.other);
+        let other = self.file_table_at(index, None);
+        std::mem::swap(&mut self.data, &mut self.other);
+        self.edits = edits;
+        Ok(interleave(&current, &other?))
+    }
+
+    /// The displayed slice at `index` of the open file with totals, grouped if the rows are
+    /// nested.
+    fn file_table_at(
+        &mut self,
+        index: Vec<usize>,
+        window: Option<Range<usize>>,
+    ) -> Result<Array2<f64>> {
+        if let Some(nesting) = self.nesting().cloned() {
+            let (data, keys) = self.nested_slice(&index, &nesting)?;
+            self.row_keys = keys;
+            let mut table = self.with_totals(data, None);
+            self.nested_totals(&mut table);
+            return Ok(table);
+        }
+        if let Some((rows, cols)) = self.held_extent() {
+            return Err(eyre!(
+                "the slice of {rows} x {cols} cells is not read until confirmed with Enter"
+            ));
+        }
+        self.row_keys.clear();
+        let (mut data, row_totals) = match window {
+            Some(ref cols) => {
+                let (data, totals) = self.slice_window(index.clone(), cols.clone())?;
+                (data, Some(totals))
+            }
+            None => (self.slice_at(index.clone())?, None),
+        };
+        self.show_edits(&mut data, &index, window.map_or(0, |cols| cols.start));
+        Ok(self.with_totals(data, row_totals))
+    }
+
+    /// What the table read with `window` depends on, besides the values in the file.
+    pub fn table_key(&self, window: Option<Range<usize>>) -> TableKey {
+        TableKey {
+            name: self.name.clone(),
+            other_file: self.other_file.clone(),
+            axis0: self.axis0,
+            axis1: self.axis1,
+            index: self.active_index.clone(),
+            aggregations: self.aggregations.clone(),
+            weights: self.weights.as_ref().map(|w| w.name.clone()),
+            window,
+            bins: self
+                .overview
+                .as_ref()
+                .map(|o| (o.row_bins.clone(), o.col_bins.clone(), o.window.clone())),
+            nesting: self.nesting().cloned(),
+            reference: self.reference.clone(),
+            row_filter: self.row_filter,
+            other_share: self.config.other_share,
+            top_rows_other: self.config.top_rows_other,
+            unit_scale: self.unit_scale.clone(),
+            cumulative: self.cumulative,
+            edits: self.edits.clone(),
+        }
+    }
+
+    /// Reads the table of the displayed slice with `window` into `self.model`, unless it is
+    /// there already, in which case the shape and rows of the last read are restored.
+    pub fn update_model(&mut self, window: Option<Range<usize>>) -> Result<()> {
+        let key = self.table_key(window.clone());
+        if let Some(m) = self.model.as_ref().filter(|m| m.key == key) {
+            (self.nrow, self.ncol) = (m.nrow, m.ncol);
+            self.kept_rows.clone_from(&m.kept_rows);
+            self.other_row = m.other_row;
+            self.row_keys.clone_from(&m.row_keys);
+            self.summary.missing = m.missing;
+            return Ok(());
+        }
+        if let Some((_, ref e)) = self.failed.as_ref().filter(|(k, _)| *k == key) {
+            return Err(eyre!("{e}"));
+        }
+        self.model = None;
+        let table = match self.read_table(window) {
+            Ok(table) => table,
+            Err(e) => {
+                self.failed = Some((key, e.to_string()));
+                self.failure_reported = false;
+                return Err(e);
+            }
+        };
+        self.failed = None;
+        self.model = Some(SliceModel {
+            key,
+            table,
+            nrow: self.nrow,
+            ncol: self.ncol,
+            kept_rows: self.kept_rows.clone(),
+            other_row: self.other_row,
+            row_keys: self.row_keys.clone(),
+            missing: self.summary.missing,
+        });
+        Ok(())
+    }
+
+    /// The displayed slice with totals, compared against the reference if one is pinned.
+    ///
+    /// With a window, only its columns are read, and are the columns of the table. The table
+    /// is read again only if the slice changed since the last call.
+    pub fn table(&mut self, window: Option<Range<usize>>) -> Result<Array2<f64>> {
+        self.update_model(window)?;
+        Ok(self
+            .model
+            .as_ref()
+            .map(|m| m.table.clone())
+            .unwrap_or_default())
+    }
+
+    fn read_table(&mut self, window: Option<Range<usize>>) -> Result<Array2<f64>> {
+        let mut current = self.table_at(self.active_index.clone(), window.clone())?;
+        let (cols, rows) = current.dim();
+        self.summary.missing = current
+            .slice(s![..cols - 1, ..rows - 1])
+            .iter()
+            .filter(|v| v.is_nan())
+            .count();
+        if !self.row_keys.is_empty() {
+            self.nrow = self.row_keys.len();
+        }
+        if self.comparing_files() {
+            self.ncol = cols - 1;
+        }
+        (self.kept_rows, self.other_row) = match self.filter_rows(&current) {
+            Some((keep, other)) => (Some(keep), other),
+            None => (None, false),
+        };
+        if let Some(ref keep) = self.kept_rows {
+            current = group_rows(&current, keep, self.other_row);
+            self.nrow = keep.len() + self.other_row as usize;
+        }
+        let (Some(index), Some(comparison)) = (
+            self.reference_index(),
+            self.reference.as_ref().map(|r| r.comparison.clone()),
+        ) else {
+            return Ok(current);
+        };
+        let mut reference = self.table_at(index, window)?;
+        if let Some(ref keep) = self.kept_rows {
+            reference = group_rows(&reference, keep, self.other_row);
+        }
+        Ok(match comparison {
+            Comparison::Delta => current - reference,
+            Comparison::Percent => Zip::from(&current)
+                .and(&reference)
+                .map_collect(|&c, &r| percent_change(c, r)),
+        })
+    }
+
+    /// Rows of `table` kept by the row filter, in file order, and whether the others are
+    /// summed into "Other", or `None` if every row is shown.
+    pub fn filter_rows(&self, table: &Array2<f64>) -> Option<(Vec<usize>, bool)> {
+        // Overview rows are bins already, and groups keep all their members
+        if self.overview.is_some() || self.nesting().is_some() {
+            return None;
+        }
+        let (cols, rows) = table.dim();
+        let (cols, rows) = (cols - 1, rows - 1);
+        match self.row_filter {
+            RowFilter::All => None,
+            RowFilter::GroupSmall => {
+                let total = table[[cols, rows]].abs();
+                if total == 0.0 || total.is_nan() {
+                    return None;
+                }
+                let keep: Vec<usize> = (0..rows)
+                    .filter(|&r| {
+                        let share = table[[cols, r]].abs() / total * 100.0;
+                        share.is_nan() || share >= self.config.other_share
+                    })
+                    .collect();
+                // Grouping a single row would only rename it
+                (rows - keep.len() >= 2).then_some((keep, true))
+            }
+            RowFilter::Top(n) if n < rows => {
+                let total = |r: usize| match table[[cols, r]] {
+                    v if v.is_nan() => f64::NEG_INFINITY,
+                    v => v,
+                };
+                let mut keep: Vec<usize> = (0..rows).collect();
+                keep.sort_by(|&a, &b| total(b).total_cmp(&total(a)));
+                keep.truncate(n);
+                keep.sort();
+                Some((keep, self.config.top_rows_other))
+            }
+            RowFilter::Top(_) => None,
+        }
+    }
+
+    /// Values of the visible rows, each starting with the row total and followed by the
+    /// columns from `self.col`. The last row holds the column totals.
+    pub fn values(&mut self) -> Result<Vec<Vec<f64>>> {
+        self.values_in(None)
+    }
+
+    /// Like [`Viewer::values`], but only for the columns that can fit on screen, judging by
+    /// the last draw, so wide slices are read a window at a time.
+    pub fn visible_values(&mut self) -> Result<Vec<Vec<f64>>> {
+        let window = match self.page_width {
+            // A running sum needs every column before the window, and groups, files compared and
+            // edited slices are read whole
+            Some(width)
+                if self.overview.is_none()
+                    && !self.cumulative
+                    && self.nesting().is_none()
+                    && !self.comparing_files()
+                    && self.edits.is_empty() =>
+            {
+                Some(self.col..(self.col + 2 * width.max(1)).min(self.ncol))
+            }
+            _ => None,
+        };
+        self.values_in(window)
+    }
+
+    fn values_in(&mut self, window: Option<Range<usize>>) -> Result<Vec<Vec<f64>>> {
+        if self.data.is_none() {
+            return Ok(vec![]);
+        }
+        // Columns of the table before `self.col`
+        let skip = if window.is_some() { 0 } else { self.col };
+        self.update_model(window)?;
+        let Some(model) = self.model.take() else {
+            return Ok(vec![]);
+        };
+        let table = &model.table;
+        let (cols, rows) = table.dim();
+        let (cols, rows) = (cols - 1, rows - 1);
+        log::debug!("rows = {rows}, cols = {cols}");
+        log::debug!("self.row = {}, self.col = {}", self.row, self.col);
+        log::debug!("self.nrow = {}, self.ncol = {}", self.nrow, self.ncol);
+        log::debug!("axis0 = {}, axis1 = {}", self.axis0, self.axis1);
+        self.row_order = self.row_order(table);
+        let values = self
+            .row_order
+            .iter()
+            .chain(std::iter::once(&rows))
+            .map(|&r| {
+                std::iter::once(table[[cols, r]])
+                    .chain((skip..cols).map(|c| table[[c, r]]))
+                    .collect()
+            })
+            .collect();
+        self.model = Some(model);
+        Ok(values)
+    }
+
+    /// The exact value of cell `j` of visible row `i` of `items`, with its full labels and its
+    /// share of the slice total, e.g. `ON × 2020 = 612.4375 PJ (12.31% of total)`.
+    ///
+    /// `rows` and `columns` are the labels from [`Viewer::rows`] and [`Viewer::columns`].
+    pub fn readout(
+        &self,
+        items: &[Vec<f64>],
+        rows: &[String],
+        columns: &[String],
+        i: usize,
+        j: usize,
+    ) -> Option<String> {
+        let value = *items.get(i)?.get(j)?;
+        let mut readout = format!("{} × {} = {value}", rows.get(i)?, columns.get(j + 1)?);
+        let units = self.units();
+        if self.reference_index().is_none() {
+            if !units.is_empty() {
+                readout = format!("{readout} {units}");
+            }
+            let total = items.last()?.first()?;
+            if *total != 0.0 && !value.is_nan() {
+                readout = format!("{readout} ({:.2}% of total)", value / total * 100.0);
+            }
+        }
+        Some(readout)
+    }
+
+    pub fn sort_by_total(&self) -> bool {
+        self.sort_by_total.unwrap_or(self.config.sort_by_total)
+    }
+
+    /// Number of decimals values are shown and exported with.
+    pub fn decimals(&self) -> usize {
+        self.decimals
+            .unwrap_or(self.config.decimals)
+            .min(MAX_DECIMALS)
+    }
+
+    /// Shows one more decimal, or one fewer if `more` is false.
+    pub fn change_decimals(&mut self, more: bool) {
+        let decimals = self.decimals();
+        self.decimals = Some(if more {
+            (decimals + 1).min(MAX_DECIMALS)
+        } else {
+            decimals.saturating_sub(1)
+        });
+        self.message = Some(match self.decimals() {
+            1 => "Showing 1 decimal".into(),
+            n => format!("Showing {n} decimals"),
+        });
+    }
+
+    /// Order in which the rows of `table` (excluding its totals row) are displayed.
+    pub fn row_order(&self, table: &Array2<f64>) -> Vec<usize> {
+        let (cols, rows) = table.dim();
+        let (cols, rows) = (cols - 1, rows - 1);
+        let total = |r: usize| match table[[cols, r]] {
+            v if v.is_nan() => f64::NEG_INFINITY,
+            v => v,
+        };
+        // Groups are sorted as a whole, and their members within them
+        if self.row_keys.len() == rows {
+            let mut groups: Vec<Vec<usize>> = vec![];
+            for (r, key) in self.row_keys.iter().enumerate() {
+                match (key, groups.last_mut()) {
+                    (RowKey::Member(..), Some(group)) => group.push(r),
+                    _ => groups.push(vec![r]),
+                }
+            }
+            if self.sort_by_total() {
+                groups.sort_by(|a, b| total(b[0]).total_cmp(&total(a[0])));
+                for group in groups.iter_mut() {
+                    group[1..].sort_by(|&a, &b| total(b).total_cmp(&total(a)));
+                }
+            }
+            return groups.concat();
+        }
+        // "Other" stays last
+        let sorted = if self.other_row { rows - 1 } else { rows };
+        let mut order: Vec<usize> = (0..sorted).collect();
+        if self.sort_by_total() {
+            order.sort_by(|&a, &b| total(b).total_cmp(&total(a)));
+        }
+        order.extend(sorted..rows);
+        order
+    }
+
+    pub fn format_value(&self, f: f64) -> String {
+        let percent = self.reference_index().is_some()
+            && matches!(
+                self.reference,
+                Some(Reference {
+                    comparison: Comparison::Percent,
+                    ..
+                })
+            );
+        if f.is_nan() && self.formatting.blank_missing {
+            String::default()
+        } else if f.is_nan() {
+            MISSING.to_string()
+        } else if self.formatting.dash_zeros && abs_diff_eq!(f, 0.0) {
+            "-".to_string()
+        } else if percent {
+            format!("{:.*}%", self.decimals(), f)
+        } else if self.formatting.integers && f.fract() == 0.0 {
+            format!("{}", f as i64)
+        } else {
+            format!("{:.*}", self.decimals(), f)
+        }
+    }
+
+    /// `f` as written to CSV: rounded to the decimals shown, without separators, or empty if
+    /// missing.
+    fn csv_value(&self, f: f64) -> String {
+        if f.is_nan() {
+            String::default()
+        } else {
+            format!("{:.*}", self.decimals(), f)
+        }
+    }
+
+    /// `f` as written to JSON: rounded through its text, so it has the decimals shown only, or
+    /// `None`, written as `null`, if missing.
+    fn json_value(&self, f: f64) -> Option<f64> {
+        format!("{:.*}", self.decimals(), f)
+            .parse::<f64>()
+            .ok()
+            .filter(|v| !v.is_nan())
+    }
+
+    pub fn data(&mut self) -> Result<Vec<Vec<String>>> {
+        Ok(self
+            .values()?
+            .iter()
+            .map(|v| v.iter().map(|f| self.format_value(*f)).collect())
+            .collect())
+    }
+
+    /// Remembers the current index of every set so datasets sharing the set open at the same
+    /// element.
+    pub fn remember_indices(&mut self) {
+        if let Some(ref d) = self.data {
+            for (i, name) in d.set_names.iter().enumerate() {
+                if let Some(&index) = self.active_index.get(i) {
+                    self.sticky_indices
+                        .insert(name.clone(), (d.set_data[i].clone(), index));
+                }
+            }
+        }
+    }
+
+    /// Keeps the current view so reopening the dataset restores it.
+    pub fn save_view(&mut self) {
+        if let Some(view) = self.current_view() {
+            self.views.insert(self.name.clone(), view);
+        }
+    }
+
+    /// The layout of the open dataset.
+    pub fn current_view(&self) -> Option<SavedView> {
+        Some(SavedView {
+            set_data: self.data.as_ref()?.set_data.clone(),
+            axis0: self.axis0,
+            axis1: self.axis1,
+            active_index: self.active_index.clone(),
+            aggregations: self.aggregations.clone(),
+            selected: self.state.selected(),
+            col: self.col,
+            selected_col: self.selected_col,
+            formatting: self.formatting,
+            unit_scale: self.unit_scale.clone(),
+            reference: self.reference.clone(),
+            sort_by_total: self.sort_by_total,
+            cumulative: self.cumulative,
+        })
+    }
+
+    /// Restores the saved view of the current dataset, if its sets are unchanged.
+    pub fn restore_view(&mut self) -> bool {
+        let Some(ref d) = self.data else {
+            return false;
+        };
+        match self.views.get(&self.name) {
+            Some(view) if self.config.remember_views && view.set_data == d.set_data => {
+                self.set_view(view.clone());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn set_view(&mut self, view: SavedView) {
+        self.aggregations.clone_from(&view.aggregations);
+        self.formatting = view.formatting;
+        self.unit_scale.clone_from(&view.unit_scale);
+        self.reference.clone_from(&view.reference);
+        self.sort_by_total = view.sort_by_total;
+        self.cumulative = view.cumulative;
+        self.set_position(view);
+    }
+
+    /// Moves to the axes, indices and cursor of `view`, leaving the display options.
+    fn set_position(&mut self, view: SavedView) {
+        self.axis0 = view.axis0;
+        self.axis1 = view.axis1;
+        self.active_index = view.active_index;
+        self.state.select(view.selected);
+        self.col = view.col;
+        self.selected_col = view.selected_col;
+    }
+
+    /// Remembers `before` if the axes or indices changed since.
+    fn record_history(&mut self, before: Option<SavedView>) {
+        let (Some(before), Some(after)) = (before, self.current_view()) else {
+            return;
+        };
+        if before.active_index.is_empty() || before.set_data != after.set_data {
+            return;
+        }
+        if (before.axis0, before.axis1, &before.active_index)
+            != (after.axis0, after.axis1, &after.active_index)
+        {
+            self.back_history.push(before);
+            if self.back_history.len() > MAX_HISTORY {
+                self.back_history.remove(0);
+            }
+            self.forward_history.clear();
+        }
+    }
+
+    /// Goes back to the view before the last change of axes or indices.
+    pub fn navigate_back(&mut self) -> Result<()> {
+        let Some(view) = self.back_history.pop() else {
+            self.message = Some("No earlier view".into());
+            return Ok(());
+        };
+        self.forward_history.extend(self.current_view());
+        self.set_position(view);
+        self.overview = None;
+        self.initialize_state()
+    }
+
+    /// Undoes the last [`Viewer::navigate_back`].
+    pub fn navigate_forward(&mut self) -> Result<()> {
+        let Some(view) = self.forward_history.pop() else {
+            self.message = Some("No later view".into());
+            return Ok(());
+        };
+        self.back_history.extend(self.current_view());
+        self.set_position(view);
+        self.overview = None;
+        self.initialize_state()
+    }
+
+    /// Bookmarks the current view of the dataset as `name`, replacing one of the same name.
+    pub fn add_bookmark(&mut self, name: String) {
+        let Some(view) = self.current_view() else {
+            return;
+        };
+        let bookmark = Bookmark {
+            name,
+            dataset: self.name.clone(),
+            view,
+        };
+        match self
+            .bookmarks
+            .iter_mut()
+            .find(|b| b.dataset == bookmark.dataset && b.name == bookmark.name)
+        {
+            Some(b) => *b = bookmark,
+            None => self.bookmarks.push(bookmark),
+        }
+    }
+
+    /// Bookmarks of the open dataset, as (index in `bookmarks`, name).
+    pub fn dataset_bookmarks(&self) -> Vec<(usize, String)> {
+        self.bookmarks
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.dataset == self.name)
+            .map(|(i, b)| (i, b.name.clone()))
+            .collect()
+    }
+
+    /// Restores the view bookmarked at `index`, if it still fits the dataset.
+    pub fn jump_to_bookmark(&mut self, index: usize) -> Result<()> {
+        let Some(bookmark) = self.bookmarks.get(index).cloned() else {
+            return Ok(());
+        };
+        if self.data.as_ref().map(|d| &d.set_data) != Some(&bookmark.view.set_data) {
+            self.message = Some(format!(
+                "The sets of {} changed since {:?} was bookmarked",
+                bookmark.dataset, bookmark.name
+            ));
+            return Ok(());
+        }
+        self.set_view(bookmark.view);
+        self.overview = None;
+        self.initialize_state()?;
+        self.message = Some(format!("Jumped to {:?}", bookmark.name));
+        Ok(())
+    }
+
+    /// Holds off reading the slice until no step came for `config.navigation_delay_ms`, so
+    /// holding a key down only reads the slice it stops at.
+    fn delay_read(&mut self) {
+        let delay = Duration::from_millis(self.config.navigation_delay_ms);
+        self.settle_at = (!delay.is_zero()).then(|| Instant::now() + delay);
+    }
+
+    pub fn reset(&mut self) {
+        self.save_view();
+        self.remember_indices();
+        self.state = TableState::default();
+        self.active_index = Vec::default();
+        self.aggregations = Vec::default();
+        self.weights = None;
+        self.overview = None;
+        (self.confirmed_extent, self.asked_extent) = (None, None);
+        self.pinned.clear();
+        self.row_totals.clear();
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        self.last_items = None;
+        self.settle_at = None;
+        self.unit_scale = None;
+        self.reference = None;
+        self.sort_by_total = None;
+        self.nesting = None;
+        self.row_keys.clear();
+        self.column_range = None;
+        self.edits.clear();
+        self.back_history.clear();
+        self.forward_history.clear();
+        self.focus = true;
+    }
+
+    /// Cycles through the unit conversions that apply to the dataset's units, then back to none.
+    pub fn cycle_unit_scale(&mut self) {
+        let Some(ref d) = self.data else {
+            return;
+        };
+        let conversions = units::conversions_for(&d.units, &self.config.unit_conversions);
+        let next = match self.unit_scale {
+            Some(ref c) => conversions
+                .iter()
+                .position(|x| x == c)
+                .and_then(|i| conversions.get(i + 1)),
+            None => conversions.first(),
+        };
+        self.unit_scale = next.cloned();
+    }
+
+    /// Units of the displayed values, after any scaling.
+    pub fn units(&self) -> String {
+        let units = self
+            .data
+            .as_ref()
+            .map(|d| d.units.trim().to_string())
+            .unwrap_or_default();
+        match self.unit_scale {
+            Some(ref c) => format!(
+                "{} (from {units} × {})",
+                c.apply(&units).unwrap_or_default(),
+                c.factor
+            ),
+            None => units,
+        }
+    }
+
+    /// Opens the exact values of the selected bin.
+    pub fn drill_in(&mut self) {
+        let Some(ref mut o) = self.overview else {
+            return;
+        };
+        if o.window.is_some() {
+            return;
+        }
+        let selected = self.state.selected().unwrap_or_default();
+        let row = self.row_order.get(selected).copied().unwrap_or(selected);
+        if let (Some(rows), Some(cols)) = (o.row_bins.get(row), o.col_bins.get(self.selected_col)) {
+            o.window = Some((rows.clone(), cols.clone()));
+            self.state.select(Some(0));
+            self.col = 0;
+            self.selected_col = 0;
+        }
+    }
+
+    /// Returns to the overview from a drilled-in window, if one is open.
+    pub fn drill_out(&mut self) -> bool {
+        match self.overview {
+            Some(ref mut o) if o.window.is_some() => {
+                o.window = None;
+                self.state.select(Some(0));
+                self.col = 0;
+                self.selected_col = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Draws where the columns shown sit among all of them, on the line between the header and
+    /// the rows, over the value columns, if they do not all fit.
+    fn draw_minimap(&self, f: &mut super::Frame<'_>, table_area: Rect, label_width: usize) {
+        let Some(width) = self.page_width.filter(|&w| w > 0 && w < self.ncol) else {
+            return;
+        };
+        let inner = table_area.inner(&Margin {
+            vertical: 1,
+            horizontal: 1,
+        });
+        // After the highlight symbol, the row labels and the column spacing
+        let offset = (label_width as u16 + 4).min(inner.width);
+        let area = Rect {
+            x: inner.x + offset,
+            y: inner.y + 1,
+            width: inner.width - offset,
+            height: 1.min(inner.height.saturating_sub(1)),
+        };
+        let mut state = ScrollbarState::new(self.ncol.saturating_sub(width) + 1)
+            .viewport_content_length(width)
+            .position(self.col);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::HorizontalBottom)
+                .style(palette::fg(Color::DarkGray))
+                .thumb_style(palette::fg(Color::Yellow)),
+            area,
+            &mut state,
+        );
+    }
+
+    /// Draws the timings in a box over the top right corner of `table_area`.
+    fn draw_timings(&self, f: &mut super::Frame<'_>, table_area: Rect) {
+        let lines = self.timings.lines();
+        let width = 22.min(table_area.width.saturating_sub(2));
+        let height = (lines.len() as u16 + 2).min(table_area.height.saturating_sub(2));
+        let area = Rect {
+            x: table_area.right().saturating_sub(width + 1),
+            y: table_area.y + 1,
+            width,
+            height,
+        };
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>()).block(
+                Block::bordered()
+                    .title(tr("Timings"))
+                    .border_style(palette::fg(Color::DarkGray)),
+            ),
+            area,
+        );
+    }
+
+    /// Full extent of the slice, as (rows, columns), if it is held back until confirmed as too
+    /// large to read.
+    pub fn held_extent(&self) -> Option<(usize, usize)> {
+        let d = self.data.as_ref()?;
+        let extent = (d.set_data[self.axis1].len(), d.set_data[self.axis0].len());
+        (extent.0 * extent.1 > self.config.confirm_slice_cells
+            && self.confirmed_extent != Some(extent))
+        .then_some(extent)
+    }
+
+    pub fn title(&self) -> String {
+        if let Some((rows, cols)) = self.held_extent() {
+            return format!("Viewer (slice of {rows} x {cols} cells not read, Enter to read it)");
+        }
+        let title = match self.overview {
+            Some(Overview {
+                window: Some((ref rows, ref cols)),
+                ..
+            }) => format!(
+                "Viewer (window: rows {}-{}, columns {}-{}, ESC to go back)",
+                rows.start + 1,
+                rows.end,
+                cols.start + 1,
+                cols.end
+            ),
+            Some(_) => "Viewer (overview: sums over bins, Enter to drill in)".into(),
+            None => "Viewer".into(),
+        };
+        let title = if self.cumulative {
+            format!("{title} (cumulative)")
+        } else {
+            title
+        };
+        let title = match self.column_range_label() {
+            Some(label) => format!("{title} (exporting {label})"),
+            None => title,
+        };
+        let title = match (self.reference_index(), &self.reference, &self.data) {
+            (Some(_), Some(r), Some(d)) => format!(
+                "{title} ({} vs {}={})",
+                r.comparison.symbol(),
+                d.set_names[r.dim],
+                d.set_data[r.dim][r.index]
+            ),
+            _ => title,
+        };
+        let title = match (self.nesting(), &self.data) {
+            (Some(n), Some(d)) => format!(
+                "{title} (grouped by {}, Enter to collapse)",
+                d.set_names[n.dim]
+            ),
+            _ => title,
+        };
+        let title = match self.other_file {
+            Some(ref file) if self.comparing_files() => {
+                format!("{title} (A = {}, B = {file})", self.file)
+            }
+            Some(_) if self.other.is_some() => format!("{title} (files not compared over bins)"),
+            Some(ref file) => format!("{title} (not in {file})"),
+            None => title,
+        };
+        let rolled_up: Vec<&str> = self
+            .data
+            .iter()
+            .flat_map(|d| d.set_names.iter().zip(&d.rollups))
+            .filter_map(|(name, r)| r.as_ref().map(|_| name.as_str()))
+            .collect();
+        let title = if rolled_up.is_empty() {
+            title
+        } else {
+            format!("{title} ({} rolled up)", rolled_up.join(", "))
+        };
+        let title = match self.edits.len() {
+            0 => title,
+            n => format!("{title} ({n} unsaved edits, Space S to save)"),
+        };
+        let title = if self.sort_by_total() {
+            format!("{title} (sorted by total)")
+        } else {
+            title
+        };
+        let units = self.units();
+        if units.is_empty() {
+            title
+        } else {
+            format!("{title} [{units}]")
+        }
+    }
 
-use super::{select::Select, summary::Summary, Component};
-use crate::{action::Action, data::Data, trace_dbg};
+    /// Directory the frames along `dim` are exported to, in the current directory.
+    pub fn frames_dir(&self, dim: usize) -> Result<PathBuf> {
+        Ok(PathBuf::from(format!(
+            "{}-{}",
+            self.export_name(),
+            self.dataset()?.set_names[dim]
+        )))
+    }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
-pub enum Mode {
-    #[default]
-    Normal,
-    Editing,
-    Selection,
-}
+    /// Writes the table for every element of dimension `dim` to numbered text files, in a new
+    /// directory under the current one. Returns the directory.
+    pub fn export_frames(&mut self, dim: usize) -> Result<PathBuf> {
+        let d = self
+            .data
+            .clone()
+            .ok_or_else(|| color_eyre::eyre::eyre!("No dataset"))?;
+        let dir = self.frames_dir(dim)?;
+        std::fs::create_dir_all(&dir)?;
+        let (active_index, col, row) = (self.active_index.clone(), self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let mut result = Ok(dir.clone());
+        for (k, label) in d.set_data[dim].iter().enumerate() {
+            self.active_index[dim] = k;
+            let frame = self.values().map(|values| {
+                let cells: Vec<Vec<String>> = values
+                    .iter()
+                    .map(|v| v.iter().map(|f| self.format_value(*f)).collect())
+                    .collect();
+                let heading = format!(
+                    "{} {}={} [{}]",
+                    self.name,
+                    d.set_names[dim],
+                    label,
+                    self.units()
+                );
+                let (columns, rows, cells) =
+                    self.place_totals(&self.columns(), &self.rows(), &cells);
+                format!("{heading}\n\n{}", text_table(&columns, &rows, &cells))
+            });
+            let path = dir.join(format!("frame_{:04}.txt", k + 1));
+            if let Err(e) = frame.and_then(|f| Ok(std::fs::write(path, f)?)) {
+                result = Err(e);
+                break;
+            }
+        }
+        (self.active_index, self.col, self.row) = (active_index, col, row);
+        result
+    }
 
-#[derive(Debug, Default)]
-pub struct Viewer {
-    pub file: String,
-    pub name: String,
-    pub focus: bool,
-    pub data: Option<Data>,
-    pub ncol: usize,
-    pub nrow: usize,
-    pub state: TableState,
-    pub axis0: usize,
-    pub axis1: usize,
-    pub col: usize,
-    pub row: usize,
-    pub active_index: Vec<usize>,
-    pub show_zeros_as_dashes: bool,
-    pub input: Input,
-    pub mode: Mode,
-    pub summary: Summary,
-    pub select: Select,
-    pub page_height: Option<usize>,
-}
+    /// `columns`, `rows` and the formatted `cells` of the whole slice, as from
+    /// [`Viewer::columns`], [`Viewer::rows`] and [`Viewer::values`], with the totals moved where
+    /// configured.
+    fn place_totals(
+        &self,
+        columns: &[String],
+        rows: &[String],
+        cells: &[Vec<String>],
+    ) -> (Vec<String>, Vec<String>, Vec<Vec<String>>) {
+        let n = cells.first().map_or(0, Vec::len);
+        let (column_order, row_order) = totals_layout(
+            n,
+            rows.len(),
+            n,
+            self.config.total_column,
+            self.config.total_row,
+        );
+        let columns = columns
+            .first()
+            .into_iter()
+            .chain(column_order.iter().filter_map(|&j| columns.get(j + 1)))
+            .cloned()
+            .collect();
+        let rows = row_order
+            .iter()
+            .filter_map(|&i| rows.get(i))
+            .cloned()
+            .collect();
+        let cells = row_order
+            .iter()
+            .filter_map(|&i| cells.get(i))
+            .map(|row| {
+                column_order
+                    .iter()
+                    .filter_map(|&j| row.get(j))
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+        (columns, rows, cells)
+    }
 
-impl Viewer {
-    pub fn initialize_state(&mut self) -> Result<()> {
-        let data = self.data.as_ref().ok_or_else(|| {
-            color_eyre::eyre::eyre!("Unable to extract HDF dataset from internal Option<Data>.")
-        })?;
-        if self.axis0 == self.axis1 {
-            self.axis0 = data.ndims - 1;
-            self.axis1 = 0;
+    /// Cycles the totals between the first and last column, and the bottom and top row.
+    pub fn cycle_total_placement(&mut self) {
+        let (column, row) = match (self.config.total_column, self.config.total_row) {
+            (TotalColumn::First, TotalRow::Bottom) => (TotalColumn::Last, TotalRow::Bottom),
+            (TotalColumn::Last, TotalRow::Bottom) => (TotalColumn::Last, TotalRow::Top),
+            (TotalColumn::Last, TotalRow::Top) => (TotalColumn::First, TotalRow::Top),
+            (TotalColumn::First, TotalRow::Top) => (TotalColumn::First, TotalRow::Bottom),
         };
-        self.ncol = data.set_data[self.axis0].len();
-        self.nrow = data.set_data[self.axis1].len();
-        // log::debug!("{:?}", data.set_data[self.axis1]);
-        if self.active_index.is_empty() {
-            self.active_index = vec![0; data.ndims];
-        }
-        self.summary.refresh(
-            data.clone(),
-            self.active_index.clone(),
-            self.axis0,
-            self.axis1,
-        )?;
-        self.select
-            .refresh(data.set_data.clone(), data.set_names.clone());
-        Ok(())
+        (self.config.total_column, self.config.total_row) = (column, row);
+        self.message = Some(format!(
+            "Total column {}, Total row at the {}",
+            match column {
+                TotalColumn::First => "first",
+                TotalColumn::Last => "last",
+            },
+            match row {
+                TotalRow::Bottom => "bottom",
+                TotalRow::Top => "top",
+            }
+        ));
     }
 
-    pub fn data(&mut self) -> Result<Vec<Vec<String>>> {
-        if let Some(ref d) = self.data {
-            let mut slices = Vec::new();
-            for i in (0..d.ndims).rev() {
-                if i == self.axis0 || i == self.axis1 {
-                    slices.push(SliceInfoElem::Slice {
-                        start: 0,
-                        end: None,
-                        step: 1,
-                    });
-                } else {
-                    slices.push(SliceInfoElem::Index(self.active_index[i] as isize));
+    /// Writes the whole current slice, or its column range, with its totals, to the current
+    /// directory.
+    pub fn export_slice(&mut self, format: SliceFormat) -> Result<PathBuf> {
+        let path = self.slice_path(format);
+        std::fs::write(&path, self.slice_text(format)?)?;
+        Ok(path)
+    }
+
+    /// The whole current slice in `format`, or its column range if one is set.
+    pub fn slice_text(&mut self, format: SliceFormat) -> Result<String> {
+        let (col, row) = (self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let text = self.values().map(|values| {
+            let values = self.range_values(values);
+            let cells: Vec<Vec<String>> = values
+                .iter()
+                .map(|v| v.iter().map(|f| self.format_value(*f)).collect())
+                .collect();
+            match format {
+                SliceFormat::Markdown => {
+                    let (columns, rows, cells) =
+                        self.place_totals(&self.range_columns(), &self.rows(), &cells);
+                    Ok(format!(
+                        "**{}** {}\n\n{}",
+                        self.name,
+                        self.view_description(),
+                        markdown_table(&columns, &rows, &cells)
+                    ))
                 }
+                SliceFormat::Json => self.json_records(&values),
+                SliceFormat::Csv => self.csv_table(&values),
+                SliceFormat::Text => self.linear_text(&cells),
             }
-            log::debug!("{:?} {:?} = {:?}", self.axis0, self.axis1, &slices);
-            let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
-            log::debug!("Start reading slice");
-            let data = d.dataset.read_slice_2d(s)?;
-            log::debug!("End reading slice");
-            let data = if self.axis1 > self.axis0 {
-                data.t().to_owned()
-            } else {
-                data
-            };
-            let (cols, rows) = data.dim();
-            log::debug!("rows = {rows}, cols = {cols}");
-            log::debug!("self.row = {}, self.col = {}", self.row, self.col);
-            log::debug!("self.nrow = {}, self.ncol = {}", self.nrow, self.ncol);
-            let totals_0 = data.sum_axis(Axis(0)).into_raw_vec();
-            let totals_1 = data.sum_axis(Axis(1)).into_raw_vec();
-            let vec_of_vecs = data.map_axis(ndarray::Axis(0), |row| row.to_vec()).to_vec();
-            let mut vov: Vec<Vec<_>> = Vec::with_capacity(rows);
-            for i in 0..=rows {
-                if i == rows {
-                    let mut v = totals_1[self.col..].to_vec();
-                    v.insert(0, totals_0.iter().sum::<f64>());
-                    vov.push(v);
-                } else {
-                    let mut v = vec_of_vecs[i][self.col..].to_vec();
-                    v.insert(0, totals_0[i]);
-                    vov.push(v);
-                }
+        });
+        (self.col, self.row) = (col, row);
+        text?
+    }
+
+    /// Every value of the slice, without the totals, indexed as `[[row, col]]`.
+    pub fn cells(&mut self) -> Result<Array2<f64>> {
+        let table = self.table(None)?;
+        let (cols, rows) = table.dim();
+        let cells = table.slice(s![..cols - 1, ..rows - 1]).t().to_owned();
+        if self.row_keys.is_empty() {
+            return Ok(cells);
+        }
+        // Subtotals would count the members twice
+        let members: Vec<usize> = (0..rows - 1)
+            .filter(|&r| matches!(self.row_keys[r], RowKey::Member(..)))
+            .collect();
+        Ok(cells.select(Axis(0), &members))
+    }
+
+    pub fn chart_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.svg", self.export_name()))
+    }
+
+    /// Writes the selected rows, or those of the rectangular selection, across every column as
+    /// an SVG line chart to the current directory.
+    pub fn export_chart(&mut self) -> Result<PathBuf> {
+        let rows = match (self.anchor, self.block()) {
+            (Some(_), Some((rows, _))) => rows,
+            _ => {
+                let i = self
+                    .state
+                    .selected()
+                    .ok_or_else(|| eyre!("No row is selected"))?;
+                i..i + 1
             }
-            log::debug!(
-                "vec_of_vecs: rows = {}, cols = {}",
-                vec_of_vecs.len(),
-                vec_of_vecs[0].len()
-            );
-            log::debug!("axis0 = {}, axis1 = {}", self.axis0, self.axis1);
-            let vec_of_vecs: Vec<Vec<String>> = vov
-                .iter()
-                .map(|v| {
-                    Vec::from_iter(v.iter().map(|f: &f64| {
-                        if self.show_zeros_as_dashes && abs_diff_eq!(*f, 0.0) {
-                            "-".to_string()
-                        } else if self.show_zeros_as_dashes && f.fract() == 0.0 {
-                            format!("{}", *f as i64)
+        };
+        let (col, row) = (self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let values = self.values();
+        let labels = self.rows();
+        (self.col, self.row) = (col, row);
+        // The first value is the row total, and the first two columns its headers
+        let series: Vec<(String, Vec<f64>)> = self
+            .range_values(values?)
+            .iter()
+            .zip(labels)
+            .skip(rows.start)
+            .take(rows.len())
+            .map(|(v, label)| (label, v.iter().skip(1).copied().collect()))
+            .collect();
+        let columns = self.column_labels()[self.export_range()].to_vec();
+        let title = format!("{} {}", self.name, self.view_description());
+        let path = self.chart_path();
+        std::fs::write(
+            &path,
+            chart::line_svg(&title, &self.units(), &columns, &series),
+        )?;
+        Ok(path)
+    }
+
+    /// The selected rectangle of cells, as (displayed rows, columns), excluding the totals.
+    ///
+    /// Without a rectangular selection, this is the selected cell.
+    pub fn block(&self) -> Option<(Range<usize>, Range<usize>)> {
+        let (r1, c1) = (self.state.selected()?, self.selected_col);
+        let (r0, c0) = self.anchor.unwrap_or((r1, c1));
+        let last = self.row_order.len().checked_sub(1)?;
+        let (r0, r1) = (r0.min(last), r1.min(last));
+        Some((r0.min(r1)..r0.max(r1) + 1, c0.min(c1)..c0.max(c1) + 1))
+    }
+
+    /// Values of the selected block, with its row and column labels.
+    pub fn block_values(&mut self) -> Result<BlockValues> {
+        let Some((rows, cols)) = self.block() else {
+            return Ok(Default::default());
+        };
+        let (col, row) = (self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let values = self.values();
+        let row_labels = self.rows();
+        (self.col, self.row) = (col, row);
+        let values: Vec<Vec<f64>> = values?
+            .get(rows.clone())
+            .unwrap_or_default()
+            .iter()
+            .map(|v| {
+                v.get(cols.start + 1..cols.end + 1)
+                    .unwrap_or_default()
+                    .to_vec()
+            })
+            .collect();
+        Ok((
+            row_labels.get(rows).unwrap_or_default().to_vec(),
+            self.column_labels().get(cols).unwrap_or_default().to_vec(),
+            values,
+        ))
+    }
+
+    /// Size, sum and mean of the selected block, e.g. `3 × 4 cells: sum 12.5, mean 1.04`.
+    ///
+    /// Missing values are left out of the sum and mean, which are missing if every value is.
+    pub fn block_summary(&mut self) -> Result<String> {
+        let (rows, columns, values) = self.block_values()?;
+        Ok(format!(
+            "{} × {} cells: sum {}, mean {}",
+            rows.len(),
+            columns.len(),
+            self.format_value(nansum(values.iter().flatten())),
+            self.format_value(nanmean(values.iter().flatten())),
+        ))
+    }
+
+    /// [`Viewer::block_summary`] of `block`, worked out again only when the block or the slice
+    /// changes: it reads the whole slice, which would otherwise replace the window the table
+    /// reads in the slice model on every draw.
+    fn block_readout(&mut self, block: BlockKey) -> String {
+        let key = self.table_key(None);
+        if let Some((_, _, _, summary)) = self
+            .block_readout
+            .as_ref()
+            .filter(|(b, k, order, _)| *b == block && *k == key && *order == self.row_order)
+        {
+            return summary.clone();
+        }
+        let summary = self
+            .block_summary()
+            .unwrap_or_else(|e| format!("Unable to read the selection: {e}"));
+        self.block_readout = Some((block, key, self.row_order.clone(), summary.clone()));
+        summary
+    }
+
+    /// Copies the values of the selected block to the clipboard, tab-separated so they paste
+    /// into a spreadsheet as cells.
+    pub fn copy_block(&mut self) -> Result<()> {
+        let (_, _, values) = self.block_values()?;
+        let text: String = values
+            .iter()
+            .map(|v| {
+                v.iter()
+                    .map(|f| {
+                        if f.is_nan() {
+                            String::default()
                         } else {
-                            format!("{:.2}", f)
+                            f.to_string()
                         }
-                    }))
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\t")
+                    + "\n"
+            })
+            .collect();
+        copy_to_clipboard(&text)
+    }
+
+    pub fn block_path(&self) -> PathBuf {
+        PathBuf::from(format!("{}.block.csv", self.export_name()))
+    }
+
+    /// Writes the selected block with its labels as CSV to the current directory.
+    pub fn export_block(&mut self) -> Result<PathBuf> {
+        let d = self.dataset()?;
+        let header = std::iter::once(d.set_names[self.axis1].clone());
+        let (rows, columns, values) = self.block_values()?;
+        let mut csv = header
+            .chain(columns)
+            .map(|c| csv_field(&c))
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\n";
+        for (row, v) in rows.iter().zip(values.iter()) {
+            let cells: Vec<String> = std::iter::once(csv_field(row))
+                .chain(v.iter().map(|&f| self.csv_value(f)))
+                .collect();
+            csv.push_str(&(cells.join(",") + "\n"));
+        }
+        let path = self.block_path();
+        std::fs::write(&path, csv)?;
+        Ok(path)
+    }
+
+    /// Statistics of the selected column, or of the cells of the column range if one is set,
+    /// over every row of the slice, and its largest values.
+    pub fn column_stats(&mut self) -> Result<(String, String)> {
+        let (col, row) = (self.col, self.row);
+        (self.col, self.row) = (0, 0);
+        let values = self.values();
+        let rows = self.rows();
+        (self.col, self.row) = (col, row);
+        let values = values?;
+        let range = match self.column_range {
+            Some(_) => self.export_range(),
+            None => self.selected_col..self.selected_col + 1,
+        };
+        let labels = self.column_labels();
+        let label = match (
+            labels.get(range.start),
+            labels.get(range.end.saturating_sub(1)),
+        ) {
+            (Some(first), Some(last)) if range.len() > 1 => format!("{first}–{last}"),
+            (Some(first), _) => first.clone(),
+            _ => String::default(),
+        };
+        // Every row but the totals, its cells named after their column too in a range
+        let column: Vec<(String, f64)> = rows
+            .iter()
+            .zip(values.iter())
+            .take(rows.len().saturating_sub(1))
+            .flat_map(|(r, v)| {
+                let (range, labels) = (range.clone(), &labels);
+                range.clone().filter_map(move |c| {
+                    let name = if range.len() > 1 {
+                        format!("{r}, {}", labels[c])
+                    } else {
+                        r.clone()
+                    };
+                    Some((name, *v.get(c + 1)?))
                 })
+            })
+            .collect();
+        let s = Stats::of(
+            Array1::from_iter(column.iter().map(|(_, v)| *v))
+                .into_dyn()
+                .view(),
+        );
+        let statistics = [
+            ("Count", s.count.to_string()),
+            ("Min", self.format_value(s.min)),
+            ("Max", self.format_value(s.max)),
+            ("Mean", self.format_value(s.mean)),
+            ("Std", self.format_value(s.std)),
+            ("Sum", self.format_value(s.sum)),
+        ];
+        let mut top: Vec<&(String, f64)> = column.iter().filter(|(_, v)| !v.is_nan()).collect();
+        top.sort_by(|a, b| b.1.total_cmp(&a.1));
+        top.truncate(5);
+        let text = format!(
+            "{}\n{}",
+            text_table(
+                &["".into(), self.units()],
+                &statistics
+                    .iter()
+                    .map(|(n, _)| n.to_string())
+                    .collect::<Vec<_>>(),
+                &statistics
+                    .iter()
+                    .map(|(_, v)| vec![v.clone()])
+                    .collect::<Vec<_>>(),
+            ),
+            text_table(
+                &["Largest".into(), label.clone()],
+                &top.iter().map(|(r, _)| r.to_string()).collect::<Vec<_>>(),
+                &top.iter()
+                    .map(|(_, v)| vec![self.format_value(*v)])
+                    .collect::<Vec<_>>(),
+            ),
+        );
+        let d = self.dataset()?;
+        Ok((
+            format!("{} {}={label}", self.name, d.set_names[self.axis0]),
+            text,
+        ))
+    }
+
+    /// Runs the configured hook in the background with the current slice as CSV on stdin, and
+    /// reports its output once it exits, or stops it after `hook_timeout_secs`.
+    pub fn run_hook(&mut self) -> Result<()> {
+        let Some(command) = self.config.hook.clone() else {
+            return Err(color_eyre::eyre::eyre!(
+                "No hook configured, set \"hook\" in config.json"
+            ));
+        };
+        let args = shlex::split(&command)
+            .filter(|args| !args.is_empty())
+            .ok_or_else(|| color_eyre::eyre::eyre!("Invalid hook command {command:?}"))?;
+        let csv = self.slice_text(SliceFormat::Csv)?;
+        let env = [
+            ("E2020_FILE", self.file.clone()),
+            ("E2020_DATASET", self.name.clone()),
+            ("E2020_VIEW", self.view_description()),
+        ];
+        let action_tx = self.action_tx.clone();
+        self.message = Some(format!("Running {command}"));
+        let task = self.tasks.start(tr("Running hook"), 0);
+        let timeout = self.config.hook_timeout_secs;
+        tokio::spawn(async move {
+            let run = run_command_async(&args, &env, csv);
+            let result = if timeout == 0 {
+                Ok(run.await)
+            } else {
+                tokio::time::timeout(Duration::from_secs(timeout), run).await
+            };
+            let (title, text, success) = match result {
+                Err(_) => (
+                    format!("{command}: timed out"),
+                    format!("Stopped after {timeout} s without exiting"),
+                    false,
+                ),
+                Ok(Ok(output)) => (
+                    format!("{command}: {}", output.status),
+                    String::from_utf8_lossy(&output.stdout).to_string()
+                        + &String::from_utf8_lossy(&output.stderr),
+                    output.status.success(),
+                ),
+                Ok(Err(e)) => (format!("{command}: failed to run"), e.to_string(), false),
+            };
+            if task.is_cancelled() {
+                return;
+            }
+            if let Some(tx) = action_tx {
+                tx.send(Action::ShowOutput(title, text, success))
+                    .unwrap_or_default();
+            }
+        });
+        Ok(())
+    }
+
+    /// `values` of the whole slice as CSV, with the column labels as header and without the
+    /// totals.
+    fn csv_table(&self, values: &[Vec<f64>]) -> Result<String> {
+        let d = self.dataset()?;
+        let (rows, columns) = (self.rows(), self.range_columns());
+        let mut csv = std::iter::once(d.set_names[self.axis1].as_str())
+            .chain(columns.iter().skip(2).map(|c| c.as_str()))
+            .map(csv_field)
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\n";
+        for (v, row) in values
+            .iter()
+            .zip(rows.iter())
+            .take(rows.len().saturating_sub(1))
+        {
+            let cells: Vec<String> = std::iter::once(csv_field(row))
+                .chain(v.iter().skip(1).map(|&f| self.csv_value(f)))
                 .collect();
-            if let Some(first_size) = vec_of_vecs.first().map(|v| v.len()) {
-                assert!(vec_of_vecs.iter().all(|vec| vec.len() == first_size));
+            csv.push_str(&(cells.join(",") + "\n"));
+        }
+        Ok(csv)
+    }
+
+    /// The formatted `cells` as lines such as `Area ON, Year 2030: 12.50`, a row at a time with
+    /// the row total after its cells and the total row last.
+    fn linear_text(&self, cells: &[Vec<String>]) -> Result<String> {
+        let d = self.dataset()?;
+        let (row_set, col_set) = (&d.set_names[self.axis1], &d.set_names[self.axis0]);
+        let (rows, columns) = (self.rows(), self.range_columns());
+        let mut text = format!("{}: {}\n", self.name, self.view_description());
+        for (i, (v, row)) in cells.iter().zip(rows.iter()).enumerate() {
+            let row = if i + 1 == rows.len() {
+                format!("{row_set} total")
+            } else {
+                format!("{row_set} {row}")
             };
-            Ok(vec_of_vecs)
+            // The first value is the row total, and the first two columns its headers
+            let labelled = v
+                .iter()
+                .skip(1)
+                .zip(columns.iter().skip(2).map(|c| format!("{col_set} {c}")));
+            for (value, col) in labelled.chain(v.first().map(|t| (t, format!("{col_set} total")))) {
+                let value = match value.as_str() {
+                    MISSING | "" => "missing",
+                    "-" => "0",
+                    value => value,
+                };
+                text.push_str(&format!("{row}, {col}: {value}\n"));
+            }
+        }
+        Ok(text)
+    }
+
+    /// `values` of the whole slice as JSON records, one per cell, leaving out the totals.
+    fn json_records(&self, values: &[Vec<f64>]) -> Result<String> {
+        let view = self.view_definition()?;
+        let (rows, columns) = (self.rows(), self.range_columns());
+        let records: Vec<serde_json::Value> = values
+            .iter()
+            .zip(rows.iter())
+            .take(rows.len().saturating_sub(1))
+            .flat_map(|(v, row)| {
+                // The first value is the row total, and the first two columns its headers
+                v.iter()
+                    .skip(1)
+                    .zip(columns.iter().skip(2))
+                    .map(move |(&value, col)| {
+                        let value = self.json_value(value);
+                        serde_json::json!({"row_label": row, "col_label": col, "value": value})
+                    })
+            })
+            .collect();
+        let json = serde_json::json!({
+            "dataset": self.name,
+            "units": self.units(),
+            "rows": view.rows,
+            "columns": view.columns,
+            "fixed": view.fixed,
+            "records": records,
+        });
+        Ok(serde_json::to_string_pretty(&json)? + "\n")
+    }
+
+    /// Value columns the exports and the statistics cover: the column range if one is set,
+    /// else every column.
+    pub fn export_range(&self) -> Range<usize> {
+        let n = self.column_labels().len();
+        match self.column_range {
+            Some(ref r) => r.start.min(n)..r.end.min(n),
+            None => 0..n,
+        }
+    }
+
+    /// The column range as its set and first and last labels, e.g. `Year 2025–2035`.
+    pub fn column_range_label(&self) -> Option<String> {
+        let range = self.column_range.as_ref()?;
+        let d = self.data.as_ref()?;
+        let labels = self.column_labels();
+        let (first, last) = (labels.get(range.start)?, labels.get(range.end - 1)?);
+        let set = &d.set_names[self.axis0];
+        Some(if range.len() == 1 {
+            format!("{set} {first}")
         } else {
-            Ok(vec![])
+            format!("{set} {first}–{last}")
+        })
+    }
+
+    /// `values` of the whole slice limited to the column range, with the row totals taken
+    /// over it. Totals that are not sums, of comparisons or running sums, are left missing.
+    fn range_values(&self, values: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+        if self.column_range.is_none() {
+            return values;
         }
+        let range = self.export_range();
+        let summable =
+            self.reference_index().is_none() && !self.cumulative && !self.comparing_files();
+        values
+            .into_iter()
+            .map(|v| {
+                // The first value is the row total
+                let cells = v.get(range.start + 1..range.end + 1).unwrap_or_default();
+                let total = if summable { nansum(cells) } else { f64::NAN };
+                std::iter::once(total)
+                    .chain(cells.iter().copied())
+                    .collect()
+            })
+            .collect()
     }
 
-    pub fn reset(&mut self) {
-        self.state = TableState::default();
-        self.active_index = Vec::default();
-        self.focus = true;
+    /// Headers of the whole slice, as in [`Viewer::columns`], limited to the column range.
+    fn range_columns(&self) -> Vec<String> {
+        let range = self.export_range();
+        self.columns()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| *i < 2 || range.contains(&(i - 2)))
+            .map(|(_, c)| c)
+            .collect()
+    }
+
+    pub fn slice_path(&self, format: SliceFormat) -> PathBuf {
+        PathBuf::from(format!("{}.{}", self.export_name(), format.extension()))
+    }
+
+    /// Labels of the displayed columns, accounting for the overview bins or window.
+    pub fn column_labels(&self) -> Vec<String> {
+        let labels = &self.set_labels(self.axis0);
+        match self.overview {
+            Some(Overview {
+                window: Some((_, ref cols)),
+                ..
+            }) => labels.get(cols.clone()).unwrap_or_default().to_vec(),
+            Some(ref o) => o
+                .col_bins
+                .iter()
+                .map(|b| Overview::label(labels, b))
+                .collect(),
+            None if self.comparing_files() => labels
+                .iter()
+                .flat_map(|l| [format!("{l} A"), format!("{l} B"), format!("{l} Δ")])
+                .collect(),
+            None => labels.clone(),
+        }
+    }
+
+    /// Labels of the displayed rows, accounting for the overview bins or window.
+    ///
+    /// When the rows are grouped, members are indented under the label of their group.
+    pub fn row_labels(&self) -> Vec<String> {
+        let labels = &self.set_labels(self.axis1);
+        if let (Some(n), false) = (self.nesting(), self.row_keys.is_empty()) {
+            let groups = &self.set_labels(n.dim);
+            return self
+                .row_keys
+                .iter()
+                .map(|key| match *key {
+                    RowKey::Group(g) => groups.get(g).cloned().unwrap_or_default(),
+                    RowKey::Member(_, r) => {
+                        format!("  {}", labels.get(r).cloned().unwrap_or_default())
+                    }
+                })
+                .collect();
+        }
+        let labels = match self.overview {
+            Some(Overview {
+                window: Some((ref rows, _)),
+                ..
+            }) => labels.get(rows.clone()).unwrap_or_default().to_vec(),
+            Some(ref o) => o
+                .row_bins
+                .iter()
+                .map(|b| Overview::label(labels, b))
+                .collect(),
+            None => labels.clone(),
+        };
+        match self.kept_rows {
+            Some(ref keep) => keep
+                .iter()
+                .filter_map(|&r| labels.get(r).cloned())
+                .chain(self.other_row.then(|| "Other".into()))
+                .collect(),
+            None => labels,
+        }
     }
 
     pub fn columns(&self) -> Vec<String> {
-        let set_data = self.data.as_ref().unwrap().set_data.clone();
-        let set_names = self.data.as_ref().unwrap().set_names.clone();
-        let mut columns = set_data[self.axis0][self.col..self.ncol].to_vec();
-        columns.insert(0, "Total".into());
+        let Some(ref d) = self.data else {
+            return vec![];
+        };
+        let labels = self.column_labels();
+        let mut columns = labels
+            .get(self.col..self.ncol.min(labels.len()))
+            .unwrap_or_default()
+            .to_vec();
         columns.insert(
             0,
-            format!(
-                "{}＼{}",
-                set_names[self.axis1].clone(),
-                set_names[self.axis0].clone()
-            ),
+            if self.comparing_files() {
+                "Total Δ".into()
+            } else {
+                "Total".into()
+            },
         );
+        let rows = match self.nesting() {
+            Some(n) => format!("{} › {}", d.set_names[n.dim], d.set_names[self.axis1]),
+            None => d.set_names[self.axis1].clone(),
+        };
+        columns.insert(0, format!("{rows}＼{}", d.set_names[self.axis0]));
         columns
     }
 
     pub fn rows(&self) -> Vec<String> {
-        let mut v = self.data.as_ref().unwrap().set_data[self.axis1][self.row..].to_vec();
+        let labels = self.row_labels();
+        let mut v = if self.row_order.len() == labels.len() {
+            self.row_order
+                .iter()
+                .filter_map(|&r| labels.get(r).cloned())
+                .collect()
+        } else {
+            labels
+        };
+        v.drain(..self.row.min(v.len()));
         v.push("Total".into());
         v
     }
 
-    pub fn constraints(&self, width: u16) -> Vec<Constraint> {
-        let mut constraints = vec![Constraint::Length(20)];
-        let mut total_width = 21;
-        while total_width + 10 < width {
-            constraints.push(Constraint::Length(9));
-            total_width += 10;
+    /// Column widths fitted to the header and cell contents, for as many columns as fit in
+    /// `width`, up to `max_columns` of the config after the totals.
+    ///
+    /// `columns` are the header labels, `rows` the row labels and `items` the formatted cells of
+    /// each row, without the row label.
+    pub fn constraints(
+        &self,
+        width: u16,
+        columns: &[String],
+        rows: &[String],
+        items: &[Vec<String>],
+    ) -> Vec<Constraint> {
+        let label_width = rows
+            .iter()
+            .chain(columns.first())
+            .map(|s| s.width())
+            .max()
+            .unwrap_or_default()
+            .clamp(MIN_LABEL_WIDTH, MAX_LABEL_WIDTH);
+        let mut constraints = vec![Constraint::Length(label_width as u16)];
+        // Leave room for the highlight symbol and the column spacing
+        let mut total_width = label_width + 4;
+        for (j, header) in columns.iter().enumerate().skip(1) {
+            // The first column holds the totals
+            if self.config.max_columns.is_some_and(|max| j > max + 1) {
+                break;
+            }
+            let column_width = items
+                .iter()
+                .filter_map(|row| row.get(j - 1))
+                .map(|s| s.width())
+                .chain(std::iter::once(header.width()))
+                .max()
+                .unwrap_or_default()
+                .max(MIN_COLUMN_WIDTH);
+            if total_width + column_width > width as usize {
+                break;
+            }
+            constraints.push(Constraint::Length(column_width as u16));
+            total_width += column_width + 1;
         }
         constraints
     }
@@ -191,6 +3065,7 @@ impl Viewer {
         }
     }
 
+    /// Scrolls up a page of rows, keeping the selection at the same place on screen.
     pub fn move_page_up(&mut self) {
         if self.nrow == 0 {
             self.state.select(None)
@@ -199,10 +3074,16 @@ impl Viewer {
                 (Some(s), Some(h)) => s.saturating_sub(h),
                 (_, _) => 0,
             };
+            let offset = self
+                .state
+                .offset()
+                .saturating_sub(self.page_height.unwrap_or_default());
+            *self.state.offset_mut() = offset;
             self.state.select(Some(new_height))
         }
     }
 
+    /// Scrolls down a page of rows, keeping the selection at the same place on screen.
     pub fn move_page_down(&mut self) {
         if self.nrow == 0 {
             self.state.select(None)
@@ -211,6 +3092,9 @@ impl Viewer {
                 (Some(s), Some(h)) => (s + h).min(self.nrow),
                 (_, _) => 0,
             };
+            // Clamped to the last page when drawn
+            let offset = self.state.offset() + self.page_height.unwrap_or_default();
+            *self.state.offset_mut() = offset;
             self.state.select(Some(new_height))
         }
     }
@@ -252,25 +3136,193 @@ impl Viewer {
     }
 
     pub fn move_right(&mut self) {
-        self.col += 1;
-        self.col = self.col.min(self.ncol);
+        self.selected_col = (self.selected_col + 1).min(self.ncol.saturating_sub(1));
+        self.scroll_to_selected_col();
     }
 
     pub fn move_left(&mut self) {
-        self.col = self.col.saturating_sub(1);
+        self.selected_col = self.selected_col.saturating_sub(1);
+        self.scroll_to_selected_col();
+    }
+
+    pub fn move_page_right(&mut self) {
+        let width = self.page_width.unwrap_or(1).max(1);
+        self.col = (self.col + width).min(self.ncol.saturating_sub(1));
+        self.selected_col = (self.selected_col + width).min(self.ncol.saturating_sub(1));
+        self.scroll_to_selected_col();
+    }
+
+    pub fn move_page_left(&mut self) {
+        let width = self.page_width.unwrap_or(1).max(1);
+        self.col = self.col.saturating_sub(width);
+        self.selected_col = self.selected_col.saturating_sub(width);
+        self.scroll_to_selected_col();
     }
 
     pub fn move_home(&mut self) {
         self.col = 0;
+        self.selected_col = 0;
+    }
+
+    /// Shows the cell at `index`, the index of each set or `None` to leave it as it is: fixes the
+    /// other dimensions at it, and selects its row and column.
+    pub fn go_to_cell(&mut self, index: &[Option<usize>]) {
+        if self.active_index.is_empty() {
+            self.active_index = self.initial_indices();
+        }
+        for (dim, i) in index.iter().enumerate() {
+            let Some(i) = *i else { continue };
+            if let Some(active) = self.active_index.get_mut(dim) {
+                *active = i;
+            }
+            if dim != self.axis0 && dim != self.axis1 {
+                if let Some(a) = self.aggregations.get_mut(dim) {
+                    *a = Aggregation::Fix;
+                }
+            }
+        }
+        if let Some(Some(row)) = index.get(self.axis1) {
+            self.row = 0;
+            self.state.select(Some(*row));
+        }
+        if let Some(Some(col)) = index.get(self.axis0) {
+            self.selected_col = *col;
+            self.scroll_to_selected_col();
+        }
+        self.overview = None;
     }
 
+    /// Scrolls to the last screen of columns.
     pub fn move_end(&mut self) {
-        self.col = self.ncol;
+        let width = self.page_width.unwrap_or(1).max(1);
+        self.col = self.ncol.saturating_sub(width);
+        self.selected_col = self.ncol.saturating_sub(1);
+    }
+
+    /// Selects the column labelled `label`, or else the one it matches best, and scrolls the
+    /// columns for it to be the first shown, or as near as the last page allows.
+    pub fn go_to_column(&mut self, label: &str) -> Result<()> {
+        let labels = self.column_labels();
+        let i = *fuzzy_matches(label, &labels)
+            .first()
+            .ok_or_else(|| eyre!("No column matches {label:?}"))?;
+        let width = self.page_width.unwrap_or(1).max(1);
+        self.selected_col = i;
+        self.col = i.min(self.ncol.saturating_sub(width));
+        Ok(())
+    }
+
+    /// Labels of the rows shown, in their order and without the indentation of grouped rows or
+    /// the totals.
+    pub fn row_names(&self) -> Vec<String> {
+        let mut rows = self.rows();
+        rows.pop();
+        rows.iter().map(|r| r.trim().to_string()).collect()
+    }
+
+    /// Selects the row labelled `label`, or else the one it matches best.
+    pub fn go_to_row(&mut self, label: &str) -> Result<()> {
+        let i = *fuzzy_matches(label, &self.row_names())
+            .first()
+            .ok_or_else(|| eyre!("No row matches {label:?}"))?;
+        self.state.select(Some(i));
+        Ok(())
+    }
+
+    /// Scrolls the columns just enough for the selected one to be shown.
+    fn scroll_to_selected_col(&mut self) {
+        let width = self.page_width.unwrap_or(1).max(1);
+        if self.selected_col < self.col {
+            self.col = self.selected_col;
+        } else if self.selected_col >= self.col + width {
+            self.col = self.selected_col + 1 - width;
+        }
+    }
+
+    /// Displays dimension `rows` as the rows and dimension `columns` as the columns.
+    pub fn pivot_axes(&mut self, rows: usize, columns: usize) {
+        if (rows, columns) == (self.axis1, self.axis0) {
+            return;
+        }
+        if (rows, columns) == (self.axis0, self.axis1) {
+            self.swap_axes();
+            return;
+        }
+        self.axis1 = rows;
+        self.axis0 = columns;
+        self.row = 0;
+        self.col = 0;
+        self.selected_col = 0;
+        self.state.select(Some(0));
+        self.overview = None;
+    }
+
+    /// Groups the rows by dimension `dim`, or stops grouping them.
+    pub fn nest_rows(&mut self, dim: Option<usize>) {
+        if self.nesting().map(|n| n.dim) == dim {
+            return;
+        }
+        self.nesting = dim.map(|dim| Nesting {
+            dim,
+            collapsed: HashSet::default(),
+        });
+        self.row_keys.clear();
+        self.row = 0;
+        self.state.select(Some(0));
+    }
+
+    /// Collapses the group of the selected row, or expands it if it is collapsed.
+    pub fn toggle_group(&mut self) {
+        let selected = self.state.selected().unwrap_or_default();
+        let row = self.row_order.get(selected).copied().unwrap_or(selected);
+        let Some(&key) = self.row_keys.get(row) else {
+            return;
+        };
+        let Some(ref mut n) = self.nesting else {
+            return;
+        };
+        let (RowKey::Group(g) | RowKey::Member(g, _)) = key;
+        if !n.collapsed.remove(&g) {
+            n.collapsed.insert(g);
+        }
+        // Keep the cursor on the group row, which stays where it is
+        if let Some(i) = self
+            .row_order
+            .iter()
+            .position(|&r| self.row_keys[r] == RowKey::Group(g))
+        {
+            self.state.select(Some(i));
+        }
+    }
+
+    /// Transposes the table, keeping the cursor on the same cell.
+    pub fn swap_axes(&mut self) {
+        std::mem::swap(&mut self.axis0, &mut self.axis1);
+        // The selected cell and the scroll offsets swap on their own: the first row shown
+        // becomes the first column shown, and the other way around
+        let (row, top) = (
+            self.state
+                .selected()
+                .unwrap_or_default()
+                .min(self.nrow.saturating_sub(1)),
+            self.state.offset(),
+        );
+        let (col, left) = (self.selected_col.min(self.ncol.saturating_sub(1)), self.col);
+        self.state.select(Some(col));
+        *self.state.offset_mut() = left.min(col);
+        self.selected_col = row;
+        // Keep the selected column within the columns that fit
+        let width = self.page_width.unwrap_or(1).max(1);
+        self.col = top.clamp((row + 1).saturating_sub(width), row);
+        self.row = 0;
+        // Bins are laid out per axis, so rebuild them for the transposed slice
+        self.overview = None;
     }
 
     pub fn increment_axis0(&mut self) {
         self.row = 0;
         self.col = 0;
+        self.selected_col = 0;
         self.axis0 += 1;
         // cycle around to first
         if self.axis0 >= self.active_index.len() {
@@ -289,6 +3341,7 @@ impl Viewer {
     pub fn increment_axis1(&mut self) {
         self.row = 0;
         self.col = 0;
+        self.selected_col = 0;
         self.axis1 += 1;
         // cycle around to first
         if self.axis1 >= self.active_index.len() {
@@ -345,7 +3398,7 @@ impl Viewer {
             log::error!("Trying to modify index position `{i}` in array of shape `{s:?}`.");
         } else {
             self.active_index[i] += 1;
-            if self.active_index[i] >= self.data.as_ref().unwrap().set_data[i].len() {
+            if self.active_index[i] >= self.dataset()?.set_data[i].len() {
                 self.active_index[i] = 0;
             }
         }
@@ -357,210 +3410,501 @@ impl Viewer {
             let s = &self.active_index;
             log::error!("Trying to modify index position `{i}` in array of shape `{s:?}`.");
         } else if self.active_index[i] == 0 {
-            self.active_index[i] = self.data.as_ref().unwrap().set_data[i]
-                .len()
-                .saturating_sub(1);
+            self.active_index[i] = self.dataset()?.set_data[i].len().saturating_sub(1);
         } else {
             self.active_index[i] = self.active_index[i].saturating_sub(1);
         }
         Ok(())
     }
-}
-
-impl Component for Viewer {
-    fn init(&mut self) -> Result<()> {
-        self.focus = true;
-        self.show_zeros_as_dashes = true;
-
-        self.data = Some(Data::new(self.file.clone().into(), self.name.clone())?);
-        self.axis1 = 0;
-        self.axis0 = self.data.as_ref().unwrap().ndims - 1;
-
-        self.initialize_state().unwrap();
-
-        Ok(())
-    }
 
-    fn handle_key_events(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
-        let action = match self.mode {
-            Mode::Normal => {
-                match key.code {
-                    KeyCode::Char('?') => Action::SwitchModeToHelp,
-                    KeyCode::Char('q') => Action::Quit,
-                    KeyCode::F(1) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(0)
-                    }
-                    KeyCode::F(2) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(1)
-                    }
-                    KeyCode::F(3) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(2)
-                    }
-                    KeyCode::F(4) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(3)
-                    }
-                    KeyCode::F(5) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(4)
-                    }
-                    KeyCode::F(6) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(5)
-                    }
-                    KeyCode::F(7) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(6)
-                    }
-                    KeyCode::F(8) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(7)
-                    }
-                    KeyCode::F(9) if key.modifiers.contains(KeyModifiers::SHIFT) => {
-                        Action::PreviousAxis(8)
-                    }
-                    KeyCode::F(1) if key.modifiers.is_empty() => Action::NextAxis(0),
-                    KeyCode::F(2) if key.modifiers.is_empty() => Action::NextAxis(1),
-                    KeyCode::F(3) if key.modifiers.is_empty() => Action::NextAxis(2),
-                    KeyCode::F(4) if key.modifiers.is_empty() => Action::NextAxis(3),
-                    KeyCode::F(5) if key.modifiers.is_empty() => Action::NextAxis(4),
-                    KeyCode::F(6) if key.modifiers.is_empty() => Action::NextAxis(5),
-                    KeyCode::F(7) if key.modifiers.is_empty() => Action::NextAxis(6),
-                    KeyCode::F(8) if key.modifiers.is_empty() => Action::NextAxis(7),
-                    KeyCode::F(9) if key.modifiers.is_empty() => Action::NextAxis(8),
-                    KeyCode::Char('1') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(0)
-                    }
-                    KeyCode::Char('2') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(1)
-                    }
-                    KeyCode::Char('3') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(2)
-                    }
-                    KeyCode::Char('4') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(3)
-                    }
-                    KeyCode::Char('5') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(4)
-                    }
-                    KeyCode::Char('6') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(5)
-                    }
-                    KeyCode::Char('7') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(6)
-                    }
-                    KeyCode::Char('8') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(7)
-                    }
-                    KeyCode::Char('9') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                        Action::PreviousAxis(8)
-                    }
-                    KeyCode::Char('1') if key.modifiers.is_empty() => Action::NextAxis(0),
-                    KeyCode::Char('2') if key.modifiers.is_empty() => Action::NextAxis(1),
-                    KeyCode::Char('3') if key.modifiers.is_empty() => Action::NextAxis(2),
-                    KeyCode::Char('4') if key.modifiers.is_empty() => Action::NextAxis(3),
-                    KeyCode::Char('5') if key.modifiers.is_empty() => Action::NextAxis(4),
-                    KeyCode::Char('6') if key.modifiers.is_empty() => Action::NextAxis(5),
-                    KeyCode::Char('7') if key.modifiers.is_empty() => Action::NextAxis(6),
-                    KeyCode::Char('8') if key.modifiers.is_empty() => Action::NextAxis(7),
-                    KeyCode::Char('9') if key.modifiers.is_empty() => Action::NextAxis(8),
-                    // KeyCode::Char('s') => Action::EnterSubset,
-                    KeyCode::Char(']') => Action::IncrementAxis(0),
-                    KeyCode::Char('}') => Action::IncrementAxis(1),
-                    KeyCode::Char('[') => Action::DecrementAxis(0),
-                    KeyCode::Char('{') => Action::DecrementAxis(1),
-                    KeyCode::Char('j') | KeyCode::Down => Action::MoveSelectionNext,
-                    KeyCode::Char('k') | KeyCode::Up => Action::MoveSelectionPrevious,
-                    KeyCode::Char('h') | KeyCode::Left => Action::MoveSelectionLeft,
-                    KeyCode::Char('l') | KeyCode::Right => Action::MoveSelectionRight,
-                    KeyCode::Home => Action::MoveSelectionHome,
-                    KeyCode::End => Action::MoveSelectionEnd,
-                    KeyCode::PageUp => Action::MoveSelectionPageUp,
-                    KeyCode::PageDown => Action::MoveSelectionPageDown,
-                    KeyCode::Enter => Action::SubmitSelection,
-                    KeyCode::Esc => Action::Close,
-                    KeyCode::Char('.') => Action::ToggleFormattedData,
-                    _ => return None,
+    /// Handles `action` in the current mode, returning the action it leads to.
+    fn update_mode(&mut self, action: Action) -> Result<Option<Action>> {
+        match self.mode {
+            Mode::Selection => {
+                match action {
+                    Action::EnterNormal => {
+                        self.mode = Mode::Normal;
+                        self.init()?;
+                    }
+                    _ => {
+                        self.select.update(action)?;
+                    }
+                };
+            }
+            Mode::Pivot => {
+                match action {
+                    Action::EnterNormal => self.mode = Mode::Normal,
+                    Action::Pivot(rows, columns, groups) => {
+                        self.mode = Mode::Normal;
+                        self.pivot_axes(rows, columns);
+                        self.nest_rows(groups);
+                        self.initialize_state()?;
+                    }
+                    _ => {
+                        self.pivot.update(action)?;
+                    }
+                };
+            }
+            Mode::Frames => {
+                match action {
+                    Action::EnterNormal => self.mode = Mode::Normal,
+                    Action::ExportFrames(dim, overwrite) => {
+                        let dir = self.frames_dir(dim)?;
+                        if !overwrite && dir.exists() {
+                            return Ok(Some(Action::Confirm(
+                                format!("{} already exists. Overwrite its frames?", dir.display()),
+                                Box::new(Action::ExportFrames(dim, true)),
+                            )));
+                        }
+                        self.mode = Mode::Normal;
+                        let exported = self.export_frames(dim);
+                        self.initialize_state()?;
+                        match exported {
+                            Ok(dir) => {
+                                self.message =
+                                    Some(format!("Exported frames to {}", dir.display()));
+                            }
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to export frames: {e}"
+                                ))));
+                            }
+                        }
+                    }
+                    _ => {
+                        self.frames.update(action)?;
+                    }
+                };
+            }
+            Mode::Bookmarks => {
+                match action {
+                    Action::EnterNormal => self.mode = Mode::Normal,
+                    Action::JumpToBookmark(index) => {
+                        self.mode = Mode::Normal;
+                        self.jump_to_bookmark(index)?;
+                    }
+                    Action::DeleteBookmark => {
+                        if let Some(index) = self.bookmark_list.choice() {
+                            self.bookmarks.remove(index);
+                            self.bookmark_list.refresh(self.dataset_bookmarks());
+                        }
+                    }
+                    _ => {
+                        self.bookmark_list.update(action)?;
+                    }
+                };
+            }
+            Mode::Editing => match action {
+                Action::EnterNormal => self.mode = Mode::Normal,
+                Action::SubmitSelection => {
+                    self.mode = Mode::Normal;
+                    if let Err(e) = self.edit_cell() {
+                        return Ok(Some(Action::Error(format!("Unable to edit the cell: {e}"))));
+                    }
+                }
+                _ => (),
+            },
+            Mode::Heatmap => {
+                if action == Action::EnterNormal {
+                    self.mode = Mode::Normal;
                 }
             }
-            Mode::Editing => match key.code {
-                KeyCode::Esc => Action::EnterNormal,
-                KeyCode::Enter => Action::EnterNormal,
+            Mode::Histogram => match action {
+                Action::EnterNormal => self.mode = Mode::Normal,
                 _ => {
-                    self.input.handle_event(&crossterm::event::Event::Key(key));
-                    return None;
+                    self.histogram.update(action)?;
                 }
             },
-            Mode::Selection => self.select.handle_key_events(key)?,
-        };
-        Some(action)
-    }
-
-    fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        match self.mode {
-            Mode::Selection => {
+            Mode::Formatting => match action {
+                Action::EnterNormal => self.mode = Mode::Normal,
+                Action::ToggleFormatting(option) => {
+                    self.formatting.toggle(option);
+                    self.formatting_menu.refresh(self.formatting);
+                }
+                _ => {
+                    self.formatting_menu.update(action)?;
+                }
+            },
+            Mode::Weights => {
                 match action {
-                    Action::EnterNormal => {
+                    Action::EnterNormal => self.mode = Mode::Normal,
+                    Action::SetWeights(ref name) => {
                         self.mode = Mode::Normal;
-                        self.init()?;
+                        if let Err(e) = self.set_weights(name) {
+                            return Ok(Some(Action::Error(format!(
+                                "Unable to weight by {name}: {e}"
+                            ))));
+                        }
+                        self.message = Some(format!(
+                            "Weighted means are weighted by {name}, press Alt + F<n> to use them"
+                        ));
                     }
                     _ => {
-                        self.select.update(action)?;
+                        self.weight_list.update(action)?;
                     }
                 };
             }
             _ => {
                 match action {
-                    Action::SwitchModeToViewer(_) => {
+                    Action::SwitchModeToViewer(_)
+                    | Action::OpenExpression(_)
+                    | Action::OpenCombination(_) => {
                         self.init()?;
-                        return Ok(Some(Action::MoveSelectionNext));
+                        if self.state.selected().is_none() {
+                            return Ok(Some(Action::MoveSelectionNext));
+                        }
+                    }
+                    Action::EnterFormatting => {
+                        self.formatting_menu.refresh(self.formatting);
+                        self.mode = Mode::Formatting;
+                    }
+                    Action::CycleUnitScale => {
+                        self.cycle_unit_scale();
+                        self.initialize_state()?;
+                    }
+                    Action::ToggleReference(i) => {
+                        self.toggle_reference(i);
+                        self.initialize_state()?;
+                    }
+                    Action::CycleComparison => {
+                        self.cycle_comparison();
+                        self.initialize_state()?;
+                    }
+                    // Full, then on one line, then hidden
+                    Action::ToggleSummary => {
+                        if !self.config.show_summary {
+                            self.config.show_summary = true;
+                        } else if self.summary.collapsed {
+                            self.summary.collapsed = false;
+                            self.config.show_summary = false;
+                        } else {
+                            self.summary.collapsed = true;
+                        }
+                    }
+                    Action::ScrollSummary(delta) => self.summary.scroll_by(delta),
+                    Action::CycleTotalPlacement => self.cycle_total_placement(),
+                    Action::ExportSlice(format, overwrite) => {
+                        let path = self.slice_path(format);
+                        if !overwrite && path.exists() {
+                            return Ok(Some(Action::Confirm(
+                                format!("{} already exists. Overwrite it?", path.display()),
+                                Box::new(Action::ExportSlice(format, true)),
+                            )));
+                        }
+                        match self.export_slice(format) {
+                            Ok(path) => {
+                                self.message =
+                                    Some(format!("Exported slice to {}", path.display()));
+                            }
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to export slice: {e}"
+                                ))));
+                            }
+                        }
+                    }
+                    Action::ExportChart(overwrite) => {
+                        let path = self.chart_path();
+                        if !overwrite && path.exists() {
+                            return Ok(Some(Action::Confirm(
+                                format!("{} already exists. Overwrite it?", path.display()),
+                                Box::new(Action::ExportChart(true)),
+                            )));
+                        }
+                        match self.export_chart() {
+                            Ok(path) => {
+                                self.message = Some(format!("Saved chart to {}", path.display()));
+                            }
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to export chart: {e}"
+                                ))));
+                            }
+                        }
+                    }
+                    Action::RunHook => self.run_hook()?,
+                    Action::ToggleGroupSmallRows => {
+                        self.row_filter = match self.row_filter {
+                            RowFilter::GroupSmall => RowFilter::All,
+                            _ => RowFilter::GroupSmall,
+                        };
+                        self.initialize_state()?;
+                    }
+                    Action::ColumnRange(ref text) => {
+                        match parse_column_range(text, &self.column_labels()) {
+                            Ok(range) => self.column_range = range,
+                            Err(e) => return Ok(Some(Action::Notify(e.to_string()))),
+                        }
+                        self.message = Some(match self.column_range_label() {
+                            Some(label) => format!("Exports and statistics limited to {label}"),
+                            None => "Exports and statistics cover every column".into(),
+                        });
+                    }
+                    Action::GoToColumn(ref label) if !label.is_empty() => {
+                        if let Err(e) = self.go_to_column(label) {
+                            return Ok(Some(Action::Notify(e.to_string())));
+                        }
+                        self.initialize_state()?;
+                    }
+                    Action::GoToRow(ref label) if !label.is_empty() => {
+                        if let Err(e) = self.go_to_row(label) {
+                            return Ok(Some(Action::Notify(e.to_string())));
+                        }
+                        self.initialize_state()?;
+                    }
+                    Action::TopRows(ref text) => {
+                        let Ok(n) = text.parse::<usize>() else {
+                            return Ok(Some(Action::Error(format!(
+                                "{text} is not a number of rows"
+                            ))));
+                        };
+                        self.row_filter = match n {
+                            0 => RowFilter::All,
+                            n => RowFilter::Top(n),
+                        };
+                        self.state.select(Some(0));
+                        self.initialize_state()?;
                     }
-                    Action::ToggleFormattedData => {
-                        self.show_zeros_as_dashes = !self.show_zeros_as_dashes;
-                        self.initialize_state().unwrap();
+                    Action::TogglePinRow => {
+                        if let Some(label) = self
+                            .state
+                            .selected()
+                            .and_then(|i| self.rows().get(i).cloned())
+                        {
+                            match self.pinned.iter().position(|l| *l == label) {
+                                Some(i) => {
+                                    self.pinned.remove(i);
+                                }
+                                None => self.pinned.push(label),
+                            }
+                        }
+                    }
+                    Action::ColumnStats => {
+                        let (title, text) = self.column_stats()?;
+                        return Ok(Some(Action::ShowOutput(title, text, true)));
+                    }
+                    Action::ToggleBlockSelection => {
+                        self.anchor = match self.anchor {
+                            Some(_) => None,
+                            None => self.state.selected().map(|row| (row, self.selected_col)),
+                        };
+                    }
+                    Action::CopyBlock => match self.copy_block() {
+                        Ok(()) => {
+                            self.anchor = None;
+                            self.message = Some("Copied to the clipboard".into());
+                        }
+                        Err(e) => {
+                            return Ok(Some(Action::Error(format!("Unable to copy: {e}"))));
+                        }
+                    },
+                    Action::ExportBlock(overwrite) => {
+                        let path = self.block_path();
+                        if !overwrite && path.exists() {
+                            return Ok(Some(Action::Confirm(
+                                format!("{} already exists. Overwrite it?", path.display()),
+                                Box::new(Action::ExportBlock(true)),
+                            )));
+                        }
+                        match self.export_block() {
+                            Ok(path) => {
+                                self.message =
+                                    Some(format!("Exported selection to {}", path.display()));
+                            }
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to export selection: {e}"
+                                ))));
+                            }
+                        }
                     }
+                    Action::ExportView(overwrite) => {
+                        let path = self.view_path();
+                        if !overwrite && path.exists() {
+                            return Ok(Some(Action::Confirm(
+                                format!("{} already exists. Overwrite it?", path.display()),
+                                Box::new(Action::ExportView(true)),
+                            )));
+                        }
+                        match self.export_view() {
+                            Ok(path) => {
+                                self.message = Some(format!("Saved view to {}", path.display()));
+                            }
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to save view: {e}"
+                                ))));
+                            }
+                        }
+                    }
+                    Action::ToggleCumulative => {
+                        self.cumulative = !self.cumulative;
+                        self.initialize_state()?;
+                    }
+                    Action::IncreaseDecimals => self.change_decimals(true),
+                    Action::DecreaseDecimals => self.change_decimals(false),
                     Action::MoveSelectionNext => {
                         self.move_next();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionPrevious => {
                         self.move_previous();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionLeft => {
                         self.move_left();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionRight => {
                         self.move_right();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionTop => {
                         self.move_top();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionBottom => {
                         self.move_bottom();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionPageUp => {
                         self.move_page_up();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionPageDown => {
                         self.move_page_down();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
+                    }
+                    Action::MoveSelectionPageLeft => {
+                        self.move_page_left();
+                        self.initialize_state()?;
+                    }
+                    Action::MoveSelectionPageRight => {
+                        self.move_page_right();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionHome => {
                         self.move_home();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::MoveSelectionEnd => {
                         self.move_end();
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
+                    }
+                    // Stepping through an aggregated dimension fixes it again at its index
+                    Action::NextAxis(i) | Action::PreviousAxis(i)
+                        if self.aggregation(i) != Aggregation::Fix =>
+                    {
+                        self.aggregations[i] = Aggregation::Fix;
+                        self.overview = None;
+                        self.initialize_state()?;
                     }
                     Action::NextAxis(i) => {
+                        self.summary.follow(i);
                         self.increment_index(i)?;
-                        self.initialize_state().unwrap();
+                        self.delay_read();
+                        self.initialize_state()?;
                     }
                     Action::PreviousAxis(i) => {
+                        self.summary.follow(i);
                         self.decrement_index(i)?;
-                        self.initialize_state().unwrap();
+                        self.delay_read();
+                        self.initialize_state()?;
+                    }
+                    Action::StepScrubbed(forward) => {
+                        if let Some(i) = self.summary.scrubbed() {
+                            return Ok(Some(if forward {
+                                Action::NextAxis(i)
+                            } else {
+                                Action::PreviousAxis(i)
+                            }));
+                        }
+                    }
+                    Action::SetIndex(i, index) => {
+                        if self.active_index.get(i).is_some_and(|&j| j != index) {
+                            self.summary.follow(i);
+                            self.active_index[i] = index;
+                            self.delay_read();
+                            self.initialize_state()?;
+                        }
+                    }
+                    Action::CycleAggregation(i) => {
+                        self.summary.follow(i);
+                        self.cycle_aggregation(i);
+                        self.initialize_state()?;
+                    }
+                    Action::CompareFile(ref file) => {
+                        if let Err(e) = self.compare_file(file) {
+                            return Ok(Some(Action::Error(format!(
+                                "Unable to compare with {file}: {e}"
+                            ))));
+                        }
+                        self.initialize_state()?;
+                    }
+                    Action::ToggleTimings => self.show_timings = !self.show_timings,
+                    Action::ToggleRollups => {
+                        self.toggle_rollups()?;
+                        self.initialize_state()?;
+                    }
+                    Action::ToggleLongLabels => {
+                        self.toggle_long_labels();
+                        self.initialize_state()?;
+                    }
+                    Action::ToggleSortByTotal => {
+                        self.sort_by_total = Some(!self.sort_by_total());
+                        self.initialize_state()?;
+                    }
+                    Action::EnterFrames => {
+                        if let Some(ref d) = self.data {
+                            let dims = (0..d.ndims)
+                                .filter(|&i| i != self.axis0 && i != self.axis1)
+                                .filter(|&i| self.aggregation(i) == Aggregation::Fix)
+                                .map(|i| (i, d.set_names[i].clone(), d.set_data[i].len()))
+                                .collect();
+                            self.frames.refresh(dims);
+                            self.mode = Mode::Frames;
+                        }
+                    }
+                    Action::AddBookmark(ref name) if !name.is_empty() => {
+                        self.add_bookmark(name.clone());
+                        self.message = Some(format!("Bookmarked {name:?}"));
+                    }
+                    Action::EnterBookmarks => {
+                        self.bookmark_list.refresh(self.dataset_bookmarks());
+                        self.mode = Mode::Bookmarks;
+                    }
+                    Action::EnterHeatmap => {
+                        let values = self.cells()?;
+                        let title = format!("{} {}", self.name, self.view_description());
+                        self.heatmap.refresh(values, title, self.config.palette);
+                        self.mode = Mode::Heatmap;
+                    }
+                    Action::EnterHistogram => {
+                        let values = self.cells()?.into_iter().collect();
+                        let title = format!("{} {}", self.name, self.view_description());
+                        self.histogram
+                            .refresh(values, self.config.histogram_bins, title);
+                        self.mode = Mode::Histogram;
+                    }
+                    Action::ChooseWeights(ref names) => {
+                        let current = self.weights.as_ref().map(|w| w.name.as_str());
+                        self.weight_list.refresh(names.clone(), current);
+                        self.mode = Mode::Weights;
+                    }
+                    Action::EnterPivot => {
+                        if let Some(ref d) = self.data {
+                            self.pivot.refresh(
+                                d.set_names.clone(),
+                                self.axis0,
+                                self.axis1,
+                                self.nesting().map(|n| n.dim),
+                            );
+                            self.mode = Mode::Pivot;
+                        }
+                    }
+                    Action::SwapAxes => {
+                        self.swap_axes();
+                        self.initialize_state()?;
                     }
                     Action::IncrementAxis(i) => {
                         if i == 1 {
@@ -570,7 +3914,7 @@ impl Component for Viewer {
                             // log::debug!("Incrementing axis 1");
                             self.increment_axis1();
                         }
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
                     }
                     Action::DecrementAxis(i) => {
                         if i == 1 {
@@ -580,30 +3924,257 @@ impl Component for Viewer {
                             // log::debug!("Decrementing axis 1");
                             self.decrement_axis1();
                         }
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
+                    }
+                    Action::EnterInsert if self.config.read_only_reason(&self.file).is_some() => {
+                        let reason = self.config.read_only_reason(&self.file).unwrap_or_default();
+                        self.message = Some(tr(reason).into());
+                    }
+                    Action::EnterInsert => {
+                        let i = self.state.selected().unwrap_or_default();
+                        match self.stored_cell(i, self.selected_col) {
+                            Ok(_) => {
+                                let value = self
+                                    .last_items
+                                    .as_ref()
+                                    .and_then(|items| {
+                                        items.get(i)?.get(1 + self.selected_col - self.col)
+                                    })
+                                    .filter(|v| !v.is_nan())
+                                    .map(|v| v.to_string())
+                                    .unwrap_or_default();
+                                self.input = Input::new(value);
+                                self.mode = Mode::Editing;
+                            }
+                            Err(e) => self.message = Some(e.to_string()),
+                        }
+                    }
+                    Action::SaveEdits => match self.save_edits() {
+                        Ok(0) => self.message = Some("No edits to save".into()),
+                        Ok(n) => self.message = Some(format!("Saved {n} cells to {}", self.file)),
+                        Err(e) => {
+                            return Ok(Some(Action::Error(format!(
+                                "Unable to save the edits: {e}"
+                            ))))
+                        }
+                    },
+                    Action::TransformCells(ref text) => {
+                        let preview = Transform::parse(text)
+                            .and_then(|transform| self.preview_transform(transform));
+                        return Ok(Some(match preview {
+                            Ok(preview) => {
+                                Action::Confirm(preview, Box::new(Action::ApplyTransform))
+                            }
+                            Err(e) => Action::Error(format!("Unable to transform the cells: {e}")),
+                        }));
+                    }
+                    Action::SaveDataset(ref name) => {
+                        let cut = match self.save_dataset(name) {
+                            Ok(cut) => cut,
+                            Err(e) => {
+                                return Ok(Some(Action::Error(format!(
+                                    "Unable to save {name}: {e}"
+                                ))))
+                            }
+                        };
+                        self.message = Some(format!(
+                            "Saved {name} to {}{}, reload the Picker to see it",
+                            self.file,
+                            if cut {
+                                format!(", its doc cut to {} bytes", data::ATTRIBUTE_BYTES)
+                            } else {
+                                String::default()
+                            }
+                        ));
+                    }
+                    Action::ApplyTransform => {
+                        let cells = std::mem::take(&mut self.transformed);
+                        if let Err(e) = self.write_cells(&cells) {
+                            return Ok(Some(Action::Error(format!(
+                                "Unable to write the cells: {e}"
+                            ))));
+                        }
+                        self.message =
+                            Some(format!("Wrote {} cells to {}", cells.len(), self.file));
+                    }
+                    Action::DiscardEdits => {
+                        self.edits.clear();
+                        return Ok(Some(Action::Close));
                     }
-                    Action::EnterInsert => self.mode = Mode::Editing,
                     Action::EnterNormal => {
                         self.mode = Mode::Normal;
-                        self.initialize_state().unwrap();
+                        self.initialize_state()?;
+                    }
+                    Action::SubmitSelection if self.held_extent().is_some() => {
+                        self.asked_extent = None;
+                    }
+                    Action::SubmitSelection => {
+                        self.drill_in();
+                        self.toggle_group();
+                        self.initialize_state()?;
+                    }
+                    Action::ReadLargeSlice => {
+                        self.confirmed_extent = self.held_extent();
+                        self.initialize_state()?;
+                    }
+                    Action::Close if self.anchor.is_some() => self.anchor = None,
+                    Action::Close
+                        if !self.edits.is_empty()
+                            && self.overview.as_ref().is_none_or(|o| o.window.is_none()) =>
+                    {
+                        return Ok(Some(Action::Confirm(
+                            format!(
+                                "{} edited cells are not saved. Close anyway?",
+                                self.edits.len()
+                            ),
+                            Box::new(Action::DiscardEdits),
+                        )));
                     }
                     Action::Close => {
+                        if self.drill_out() {
+                            self.initialize_state()?;
+                            return Ok(None);
+                        }
                         self.reset();
                         return Ok(Some(Action::SwitchModeToPicker));
                     }
                     Action::EnterSubset => {
                         self.mode = Mode::Selection;
                     }
+                    Action::NavigateBack => self.navigate_back()?,
+                    Action::NavigateForward => self.navigate_forward()?,
                     _ => return Ok(None),
                 };
             }
         };
+        if let Some(extent @ (rows, cols)) = self.held_extent() {
+            if self.asked_extent != Some(extent) && self.mode == Mode::Normal {
+                self.asked_extent = Some(extent);
+                return Ok(Some(Action::Confirm(
+                    format!(
+                        "The slice has {rows} x {cols} cells, over confirm_slice_cells, and may \
+                         take long to read even as an overview. Read it anyway?"
+                    ),
+                    Box::new(Action::ReadLargeSlice),
+                )));
+            }
+        }
         Ok(None)
     }
+}
+
+impl Component for Viewer {
+    fn needs_redraw(&self) -> bool {
+        self.redraw || self.settle_at.is_some()
+    }
+
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn init(&mut self) -> Result<()> {
+        self.focus = true;
+        self.formatting = self.config.formatting;
+
+        // Leave no stale dataset behind if this one cannot be read
+        self.data = None;
+        self.model = None;
+        self.failed = None;
+        self.block_readout = None;
+        self.data = Some(self.open_data(&self.name)?);
+        if let Err(e) = self.open_other() {
+            self.message = Some(format!("Not comparing files: {e}"));
+        }
+        if let Err(e) = self.read_dictionary() {
+            self.message = Some(format!("Showing set codes: {e}"));
+        }
+        let data = self.dataset()?;
+        let lengths: Vec<usize> = data.set_data.iter().map(Vec::len).collect();
+        (self.axis0, self.axis1) =
+            data::default_axes(&data.set_names, &lengths, &self.config.column_sets);
+        if self.restore_view() {
+            log::debug!("Restored previous view of {}", self.name);
+        }
+        if let Some(view) = self.pending_view.take() {
+            self.apply_view(&view)?;
+        }
+        if let Some(index) = self.pending_cell.take() {
+            self.go_to_cell(&index);
+        }
+
+        self.initialize_state()?;
+
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: crossterm::event::KeyEvent) -> Option<Action> {
+        self.message = None;
+        let action = match self.mode {
+            Mode::Normal => keymap::action(Context::Viewer, key)?,
+            Mode::Editing => match keymap::action(Context::ViewerEditing, key) {
+                Some(action) => action,
+                None => {
+                    self.input.handle_event(&crossterm::event::Event::Key(key));
+                    return None;
+                }
+            },
+            Mode::Selection => self.select.handle_key_events(key)?,
+            Mode::Pivot => self.pivot.handle_key_events(key)?,
+            Mode::Frames => self.frames.handle_key_events(key)?,
+            Mode::Bookmarks => self.bookmark_list.handle_key_events(key)?,
+            Mode::Weights => self.weight_list.handle_key_events(key)?,
+            Mode::Formatting => self.formatting_menu.handle_key_events(key)?,
+            Mode::Heatmap => self.heatmap.handle_key_events(key)?,
+            Mode::Histogram => self.histogram.handle_key_events(key)?,
+        };
+        Some(action)
+    }
+
+    fn handle_mouse_events(&mut self, mouse: MouseEvent) -> Option<Action> {
+        // Clicking or dragging along a scrubber of the summary moves to that element
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) | MouseEventKind::Drag(MouseButton::Left)
+                if self.mode == Mode::Normal =>
+            {
+                let (i, index) = self.summary.index_at(mouse.column, mouse.row)?;
+                Some(Action::SetIndex(i, index))
+            }
+            _ => None,
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        let axes = (self.axis0, self.axis1);
+        // Views are recorded in the history only when the slice moves, other than by going back
+        // or forward in it
+        let before = moves_slice(&action).then(|| self.current_view()).flatten();
+        let next = self.update_mode(action);
+        // Rows and columns of the selection mean something else on other axes
+        if (self.axis0, self.axis1) != axes {
+            self.anchor = None;
+            self.column_range = None;
+            self.pinned.clear();
+        }
+        self.record_history(before);
+        next
+    }
 
     fn draw(&mut self, f: &mut super::Frame<'_>, rect: Rect) {
-        let summary_constraint = if self.active_index.len() > 2 {
-            Constraint::Min(self.active_index.len() as u16 + 5)
+        if self.data.is_none() {
+            f.render_widget(Block::bordered().title("Viewer"), rect);
+            return;
+        }
+        let started = Instant::now();
+        let resized = std::mem::replace(&mut self.area, rect) != rect;
+        // Many dimensions scroll in the summary rather than push the table off small screens
+        let summary_constraint = if !self.config.show_summary {
+            Constraint::Length(0)
+        } else if self.active_index.len() > 2 || self.summary.collapsed {
+            Constraint::Length(
+                self.summary
+                    .height(self.active_index.len(), rect.height / 3),
+            )
         } else {
             Constraint::Min(0)
         };
@@ -611,60 +4182,344 @@ impl Component for Viewer {
         let [summary_area, table_area] = Layout::default()
             .constraints([summary_constraint, Constraint::Percentage(100)])
             .areas(rect);
-        self.summary.draw(f, summary_area);
+        if self.config.show_summary {
+            self.summary.draw(f, summary_area);
+        }
 
         log::debug!("getting data");
-        let items = self.data().unwrap();
+        let items = match (self.settle_at, &self.last_items) {
+            // Nothing is read until confirmed
+            _ if self.held_extent().is_some() => vec![],
+            // Only the summary follows while the index keeps changing
+            (Some(t), Some(items)) if Instant::now() < t && !resized => {
+                self.timings.read = Duration::ZERO;
+                items.clone()
+            }
+            _ => {
+                self.settle_at = None;
+                let read = Instant::now();
+                let values = self.visible_values();
+                self.timings.read = read.elapsed();
+                let items = match values {
+                    Ok(items) => items,
+                    Err(e) => {
+                        // Once per slice, as it is not read again until it changes
+                        if let Some(tx) = self.action_tx.as_ref().filter(|_| !self.failure_reported)
+                        {
+                            tx.send(Action::Error(format!("Unable to read {}: {e}", self.name)))
+                                .unwrap_or_default();
+                        }
+                        self.failure_reported = true;
+                        vec![]
+                    }
+                };
+                self.last_items = Some(items.clone());
+                items
+            }
+        };
         log::debug!("got data");
+        let formatting = Instant::now();
+        let comparing = self.reference_index().is_some();
         log::debug!("items.len() = {}", items.len());
-        let columns = self.columns();
+        let mut columns = self.columns();
+        // Only a window of the columns may have been read
+        if let Some(v) = items.first() {
+            columns.truncate(v.len() + 1);
+        }
         log::debug!("columns.len() = {}", columns.len());
         let rows = self.rows();
         log::debug!("rows.len() = {}", rows.len());
-        let constraints = self.constraints(rect.width);
+        // Pinned rows are repeated between the header and the other rows, if they were read
+        let pinned: Vec<usize> = self
+            .pinned
+            .iter()
+            .filter_map(|label| rows.iter().position(|r| r == label))
+            .filter(|&i| i < items.len())
+            .collect();
+        let pinned_height = match pinned.len() {
+            0 => 0,
+            n => (n as u16 + 1).min(table_area.height.saturating_sub(5) / 2),
+        };
+        self.page_height = Some(table_area.height.saturating_sub(4 + pinned_height) as usize);
+        // Positions in `items` of the rows, in display order
+        let (_, row_order) = totals_layout(
+            0,
+            items.len(),
+            0,
+            self.config.total_column,
+            self.config.total_row,
+        );
+        // Only the rows in view, the pinned ones and the totals are labelled and formatted, so
+        // tall slices cost no more to draw than short ones
+        let selected_row = self
+            .state
+            .selected()
+            .and_then(|i| row_order.iter().position(|&r| r == i));
+        let window = row_window(
+            self.state.offset(),
+            selected_row,
+            self.page_height.unwrap_or_default(),
+            row_order.len(),
+        );
+        let mut shown = vec![false; items.len()];
+        for &i in row_order[window.clone()]
+            .iter()
+            .chain(&pinned)
+            .chain(items.len().checked_sub(1).as_ref())
+        {
+            shown[i] = true;
+        }
+        // Group rows are marked as collapsed or expanded, and highlighted
+        let groups: Vec<Option<bool>> = (0..rows.len())
+            .map(
+                |i| match self.row_order.get(i).and_then(|&r| self.row_keys.get(r)) {
+                    Some(RowKey::Group(g)) if shown.get(i).copied().unwrap_or_default() => {
+                        Some(self.nesting().is_some_and(|n| n.collapsed.contains(g)))
+                    }
+                    _ => None,
+                },
+            )
+            .collect();
+        let labels: Vec<String> = rows
+            .iter()
+            .zip(&groups)
+            .enumerate()
+            .map(|(i, (label, group))| match group {
+                _ if !shown.get(i).copied().unwrap_or_default() => String::default(),
+                Some(true) => format!("▸ {label}"),
+                Some(false) => format!("▾ {label}"),
+                None => label.clone(),
+            })
+            .collect();
+        let formatted: Vec<Vec<String>> = items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| match shown[i] {
+                true => v.iter().map(|f| self.format_value(*f)).collect(),
+                false => vec![],
+            })
+            .collect();
+        let constraints = self.constraints(
+            table_area.width.saturating_sub(2),
+            &columns,
+            &labels,
+            &formatted,
+        );
 
-        let header_cells = columns.iter().enumerate().map(|(i, h)| {
-            if i == 0 {
-                Cell::from(line![h]).style(Style::default().fg(Color::Yellow))
-            } else {
-                Cell::from(line![h].alignment(Alignment::Right))
-                    .style(Style::default().add_modifier(Modifier::BOLD))
-            }
-        });
-        let header = Row::new(header_cells).height(1).bottom_margin(1);
-        let rows = items.iter().enumerate().map(|(i, item)| {
+        // Every constraint but the row labels and totals is a value column
+        let page_width = Some(constraints.len().saturating_sub(2));
+        // Positions in `items` of the columns shown, in order
+        let (column_order, _) = totals_layout(
+            items.first().map_or(0, Vec::len),
+            items.len(),
+            constraints.len().saturating_sub(1),
+            self.config.total_column,
+            self.config.total_row,
+        );
+        let constraints: Vec<Constraint> = constraints
+            .first()
+            .copied()
+            .into_iter()
+            .chain(
+                column_order
+                    .iter()
+                    .take(constraints.len().saturating_sub(1))
+                    .map(|&j| constraints[j + 1]),
+            )
+            .collect();
+        self.redraw = self.page_width != page_width;
+        self.page_width = page_width;
+        let label_width = match constraints.first() {
+            Some(Constraint::Length(w)) => *w as usize,
+            _ => MAX_LABEL_WIDTH,
+        };
+        // Keep the selected cell on screen when the columns were scrolled or resized
+        self.selected_col = self.selected_col.clamp(
+            self.col,
+            (self.col + self.page_width.unwrap_or(1).max(1))
+                .min(self.ncol)
+                .saturating_sub(1)
+                .max(self.col),
+        );
+        let selected_cell = self
+            .state
+            .selected()
+            .map(|i| (i, 1 + self.selected_col - self.col));
+        let block = self.anchor.and(self.block());
+        let readout = match block {
+            Some(ref b) => Some(self.block_readout(b.clone())),
+            None => selected_cell.and_then(|(i, j)| self.readout(&items, &rows, &columns, i, j)),
+        };
+        let edited = self.edited_cells();
+        let editing = selected_cell
+            .and_then(|(i, j)| Some(format!("{} × {}", rows.get(i)?, columns.get(j + 1)?)))
+            .unwrap_or_default();
+        // Cells of the block, in the coordinates of `items`
+        let in_block = |i: usize, j: usize| {
+            block
+                .as_ref()
+                .is_some_and(|(r, c)| r.contains(&i) && j > 0 && c.contains(&(j - 1 + self.col)))
+        };
+
+        let header_cells = columns
+            .first()
+            .into_iter()
+            .chain(column_order.iter().filter_map(|&j| columns.get(j + 1)))
+            .enumerate()
+            .map(|(i, h)| {
+                if i == 0 {
+                    Cell::from(line![ellipsize(h, label_width)]).style(palette::fg(Color::Yellow))
+                } else {
+                    Cell::from(line![h].alignment(Alignment::Right))
+                        .style(Style::default().add_modifier(Modifier::BOLD))
+                }
+            });
+        let header = Row::new(header_cells)
+            .height(1)
+            .bottom_margin(1 + pinned_height);
+        let make_row = |i: usize| {
+            let item = &items[i];
             let height = 1;
-            let mut cells: Vec<_> = item
+            let mut cells: Vec<_> = column_order
                 .iter()
-                .enumerate()
-                .map(|(j, c)| Cell::from(line![c].alignment(Alignment::Right)))
+                .map(|&j| (j, &item[j]))
+                .map(|(j, v)| {
+                    let style = match v {
+                        v if v.is_nan() => palette::dim(),
+                        v if comparing && *v > 0.0 => {
+                            Style::default().fg(self.config.palette.increase())
+                        }
+                        v if comparing && *v < 0.0 => {
+                            Style::default().fg(self.config.palette.decrease())
+                        }
+                        _ => Style::default(),
+                    };
+                    let style = if edited.contains(&(i, j)) {
+                        style
+                            .fg(palette::color(Color::Magenta))
+                            .add_modifier(Modifier::ITALIC)
+                    } else {
+                        style
+                    };
+                    let style = if self.focus && selected_cell == Some((i, j)) {
+                        style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
+                    } else if in_block(i, j) {
+                        palette::selected(style, Color::DarkGray)
+                    } else {
+                        style
+                    };
+                    Cell::from(line![formatted[i][j].clone()].alignment(Alignment::Right))
+                        .style(style)
+                })
                 .collect();
             cells.insert(
                 0,
-                Cell::from(line![&rows[i]].alignment(Alignment::Left))
-                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                Cell::from(
+                    line![ellipsize(
+                        labels.get(i).map(String::as_str).unwrap_or_default(),
+                        label_width
+                    )]
+                    .alignment(Alignment::Left),
+                )
+                .style(if pinned.contains(&i) {
+                    palette::fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else if groups.get(i).is_some_and(Option::is_some) {
+                    palette::fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().add_modifier(Modifier::BOLD)
+                }),
             );
             Row::new(cells).height(height as u16)
-        });
+        };
+        let pinned_rows: Vec<Row> = pinned.iter().map(|&i| make_row(i)).collect();
+        let rows = row_order[window.clone()].iter().map(|&i| make_row(i));
         let highlight_symbol = if self.focus { " \u{2022} " } else { "" };
         let nrows = rows.len();
+        let mut block = Block::bordered().title(self.title());
+        if let Some(ref message) = self.message {
+            block = block.title(
+                block::Title::from(Span::styled(message.clone(), palette::fg(Color::Yellow)))
+                    .position(block::Position::Bottom)
+                    .alignment(Alignment::Right),
+            );
+        }
+        if let Some(readout) = readout {
+            block = block.title(
+                block::Title::from(Span::styled(readout, palette::fg(Color::DarkGray)))
+                    .position(block::Position::Bottom),
+            );
+        }
+        let pinned_table = Table::new(pinned_rows, constraints.clone())
+            .highlight_symbol(highlight_symbol)
+            .highlight_spacing(if self.state.selected().is_some() {
+                HighlightSpacing::Always
+            } else {
+                HighlightSpacing::Never
+            });
         let table = Table::new(rows, constraints)
             .header(header)
-            .block(
-                Block::bordered()
-                    .title("Viewer")
-                    .border_style(if self.focus {
-                        Style::default().fg(Color::Yellow)
-                    } else {
-                        Style::default()
-                    }),
-            )
+            .block(block.border_style(if self.focus {
+                palette::fg(Color::Yellow)
+            } else {
+                Style::default()
+            }))
             .highlight_style(Modifier::REVERSED)
             .highlight_symbol(highlight_symbol);
+        let format_time = formatting.elapsed();
 
-        self.page_height = Some(table_area.height.saturating_sub(4) as usize);
-        f.render_stateful_widget(table, table_area, &mut self.state);
+        // The selection is kept as a row of `items`, and the offset as the first row in view
+        let mut state = TableState::default().with_selected(
+            selected_row
+                .filter(|s| window.contains(s))
+                .map(|s| s - window.start),
+        );
+        f.render_stateful_widget(table, table_area, &mut state);
+        *self.state.offset_mut() = window.start;
+        if pinned_height > 0 {
+            // Below the header and its margin, leaving a blank line before the other rows
+            let inner = table_area.inner(&Margin {
+                vertical: 1,
+                horizontal: 1,
+            });
+            let pinned_area = Rect {
+                y: inner.y + 2,
+                height: pinned_height - 1,
+                ..inner
+            };
+            f.render_widget(pinned_table, pinned_area);
+        }
+        self.timings.format = format_time;
+        self.draw_minimap(f, table_area, label_width);
+        if self.show_timings {
+            self.draw_timings(f, table_area);
+        }
 
+        if self.mode == Mode::Editing {
+            let width = 60.min(table_area.width);
+            let input_area = Rect {
+                x: table_area.x + (table_area.width - width) / 2,
+                y: table_area.y + table_area.height.saturating_sub(3) / 2,
+                width,
+                height: 3.min(table_area.height),
+            };
+            let scroll = self.input.visual_scroll(width.saturating_sub(3) as usize);
+            f.render_widget(Clear, input_area);
+            f.render_widget(
+                Paragraph::new(self.input.value())
+                    .scroll((0, scroll as u16))
+                    .block(
+                        Block::bordered()
+                            .title(format!("{editing}, empty for missing (Enter to keep)"))
+                            .border_style(palette::fg(Color::Yellow)),
+                    ),
+                input_area,
+            );
+            f.set_cursor(
+                (input_area.x + 1 + self.input.cursor() as u16)
+                    .min(input_area.x + input_area.width.saturating_sub(2)),
+                input_area.y + 1,
+            );
+        }
         if self.mode == Mode::Selection {
             let tabs_area = rect.inner(&Margin {
                 vertical: 4,
@@ -672,5 +4527,229 @@ impl Component for Viewer {
             });
             self.select.draw(f, tabs_area);
         }
+        if self.mode == Mode::Frames {
+            let height = (self.frames.dims.len() as u16 + 4).min(rect.height);
+            let width = 70.min(rect.width);
+            let frames_area = Rect {
+                x: rect.x + (rect.width - width) / 2,
+                y: rect.y + (rect.height - height) / 2,
+                width,
+                height,
+            };
+            self.frames.draw(f, frames_area);
+        }
+        if self.mode == Mode::Bookmarks {
+            let height = (self.bookmark_list.items.len() as u16 + 4).min(rect.height);
+            let width = 70.min(rect.width);
+            let bookmarks_area = Rect {
+                x: rect.x + (rect.width - width) / 2,
+                y: rect.y + (rect.height - height) / 2,
+                width,
+                height,
+            };
+            self.bookmark_list.draw(f, bookmarks_area);
+        }
+        if self.mode == Mode::Weights {
+            let height = (self.weight_list.names.len() as u16 + 4).min(rect.height);
+            let width = 70.min(rect.width);
+            let weights_area = Rect {
+                x: rect.x + (rect.width - width) / 2,
+                y: rect.y + (rect.height - height) / 2,
+                width,
+                height,
+            };
+            self.weight_list.draw(f, weights_area);
+        }
+        if self.mode == Mode::Formatting {
+            let height = (Formatting::OPTIONS.len() as u16 + 5).min(rect.height);
+            let width = 60.min(rect.width);
+            let formatting_area = Rect {
+                x: rect.x + (rect.width - width) / 2,
+                y: rect.y + (rect.height - height) / 2,
+                width,
+                height,
+            };
+            self.formatting_menu.draw(f, formatting_area);
+        }
+        if self.mode == Mode::Heatmap {
+            self.heatmap.draw(f, rect);
+        }
+        if self.mode == Mode::Histogram {
+            self.histogram.draw(f, rect);
+        }
+        if self.mode == Mode::Pivot {
+            let height = (self.pivot.set_names.len() as u16 + 5).min(rect.height);
+            let width = 60.min(rect.width);
+            let pivot_area = Rect {
+                x: rect.x + (rect.width - width) / 2,
+                y: rect.y + (rect.height - height) / 2,
+                width,
+                height,
+            };
+            self.pivot.draw(f, pivot_area);
+        }
+        self.timings.draw = started.elapsed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_column_range() -> Result<()> {
+        let labels = ["2025", "2030", "2035", "2040-2045", "Other"].map(String::from);
+        assert_eq!(parse_column_range(" ", &labels)?, None);
+        assert_eq!(parse_column_range("2030", &labels)?, Some(1..2));
+        assert_eq!(parse_column_range("2025-2035", &labels)?, Some(0..3));
+        assert_eq!(parse_column_range(" 2025 - 2030 ", &labels)?, Some(0..2));
+        assert_eq!(parse_column_range("2035..2025", &labels)?, Some(0..3));
+        assert_eq!(parse_column_range("2035–2030", &labels)?, Some(1..3));
+        // Dashed labels
+        assert_eq!(parse_column_range("2040-2045", &labels)?, Some(3..4));
+        assert_eq!(parse_column_range("2030-2040-2045", &labels)?, Some(1..4));
+        assert_eq!(parse_column_range("2040-2045-Other", &labels)?, Some(3..5));
+        assert!(parse_column_range("2050", &labels).is_err());
+        assert!(parse_column_range("2025-2050", &labels).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_totals_layout() {
+        use TotalColumn::{First, Last};
+        use TotalRow::{Bottom, Top};
+        assert_eq!(
+            totals_layout(4, 3, 2, First, Bottom),
+            (vec![0, 1, 2, 3], vec![0, 1, 2])
+        );
+        assert_eq!(
+            totals_layout(4, 3, 2, Last, Top),
+            (vec![1, 0, 2, 3], vec![2, 0, 1])
+        );
+        // Every column fits, or none
+        assert_eq!(totals_layout(4, 3, 10, Last, Bottom).0, vec![1, 2, 3, 0]);
+        assert_eq!(totals_layout(4, 3, 0, Last, Bottom).0, vec![0, 1, 2, 3]);
+        assert_eq!(totals_layout(0, 0, 0, Last, Top), (vec![], vec![]));
+    }
+
+    #[test]
+    fn test_row_window() {
+        assert_eq!(row_window(0, Some(0), 10, 100), 0..10);
+        assert_eq!(row_window(0, Some(15), 10, 100), 6..16);
+        assert_eq!(row_window(20, Some(5), 10, 100), 5..15);
+        // At the ends
+        assert_eq!(row_window(95, None, 10, 100), 90..100);
+        assert_eq!(row_window(0, Some(99), 10, 100), 90..100);
+        assert_eq!(row_window(3, None, 10, 4), 0..4);
+        assert_eq!(row_window(0, None, 0, 5), 0..1);
+        assert_eq!(row_window(0, None, 10, 0), 0..0);
+    }
+
+    #[test]
+    fn test_percent_change() {
+        assert_eq!(percent_change(150.0, 100.0), 50.0);
+        // Increases are positive from a negative reference too
+        assert_eq!(percent_change(-50.0, -100.0), 50.0);
+        assert_eq!(percent_change(0.0, 50.0), -100.0);
+        assert_eq!(percent_change(0.0, 0.0), 0.0);
+        assert!(percent_change(5.0, 0.0).is_nan());
+    }
+
+    #[test]
+    fn test_interleave() {
+        // Two columns and their totals, of two rows
+        let a = array![[1.0, 2.0], [3.0, 4.0], [4.0, 6.0]];
+        let b = array![[0.0, 1.0], [1.0, 1.0], [1.0, 2.0]];
+        assert_eq!(
+            interleave(&a, &b),
+            array![
+                [1.0, 2.0],
+                [0.0, 1.0],
+                [1.0, 1.0],
+                [3.0, 4.0],
+                [1.0, 1.0],
+                [2.0, 3.0],
+                [3.0, 4.0]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_transform() -> Result<()> {
+        assert_eq!(Transform::parse("*1.05")?, Transform::Scale(1.05));
+        assert_eq!(Transform::parse(" * -1 ")?, Transform::Scale(-1.0));
+        assert_eq!(Transform::parse("= 2.5")?, Transform::Set(2.5));
+        assert_eq!(Transform::parse("0")?, Transform::Set(0.0));
+        assert_eq!(Transform::parse("0.0")?, Transform::Set(0.0));
+        for text in ["", "5", "*", "=x", "*2x", "double"] {
+            assert!(Transform::parse(text).is_err(), "{text}");
+        }
+        assert!(Transform::Scale(2.0).apply(f64::NAN).is_nan());
+        assert_eq!(Transform::Set(0.0).to_string(), "Zero out");
+        Ok(())
+    }
+
+    #[test]
+    fn test_moves_slice() {
+        for action in [
+            Action::NextAxis(0),
+            Action::SetIndex(1, 3),
+            Action::IncrementAxis(2),
+            Action::SwapAxes,
+            Action::Pivot(0, 1, None),
+            Action::JumpToBookmark(0),
+        ] {
+            assert!(moves_slice(&action), "{action:?}");
+        }
+        // Moving within the slice or changing how it is shown is not worth a step back
+        for action in [
+            Action::MoveSelectionNext,
+            Action::ToggleBlockSelection,
+            Action::CycleTotalPlacement,
+            Action::Refresh,
+        ] {
+            assert!(!moves_slice(&action), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn test_export_values() {
+        let mut viewer = Viewer::default();
+        viewer.formatting.dash_zeros = true;
+        viewer.decimals = Some(2);
+        // Rounded as shown, but not written as shown
+        assert_eq!(viewer.csv_value(1234.5678), "1234.57");
+        assert_eq!(viewer.csv_value(0.0), "0.00");
+        assert_eq!(viewer.csv_value(f64::NAN), "");
+        assert_eq!(viewer.json_value(1.0 / 3.0), Some(0.33));
+        assert_eq!(viewer.json_value(f64::NAN), None);
+        viewer.decimals = Some(0);
+        assert_eq!(viewer.csv_value(2.5e6 + 0.4), "2500000");
+    }
+
+    #[test]
+    fn test_swap_axes() {
+        let mut viewer = Viewer {
+            nrow: 30,
+            ncol: 20,
+            selected_col: 8,
+            col: 3,
+            page_width: Some(6),
+            ..Default::default()
+        };
+        viewer.state.select(Some(12));
+        *viewer.state.offset_mut() = 5;
+        viewer.swap_axes();
+        assert_eq!(viewer.state.selected(), Some(8));
+        assert_eq!(viewer.state.offset(), 3);
+        assert_eq!(viewer.selected_col, 12);
+        // The first row shown would leave the selected column out of the 6 that fit
+        assert_eq!(viewer.col, 7);
+        viewer.swap_axes();
+        assert_eq!(viewer.state.selected(), Some(12));
+        assert_eq!(viewer.state.offset(), 7);
+        assert_eq!((viewer.selected_col, viewer.col), (8, 3));
     }
 }
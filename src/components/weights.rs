@@ -0,0 +1,113 @@
+use color_eyre::eyre::Result;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    layout::{Constraint, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use super::{Component, Frame};
+use crate::{
+    action::Action,
+    i18n::tr,
+    keymap::{self, Context},
+    palette,
+};
+
+/// Popup to choose the dataset weighting the weighted means, among those with the same sets.
+#[derive(Default, Debug)]
+pub struct Weights {
+    /// Names of the datasets with the sets of the open one
+    pub names: Vec<String>,
+    pub state: ListState,
+}
+
+impl Weights {
+    pub fn refresh(&mut self, names: Vec<String>, current: Option<&str>) {
+        self.names = names;
+        let selected = current
+            .and_then(|c| self.names.iter().position(|n| n == c))
+            .or(if self.names.is_empty() { None } else { Some(0) });
+        self.state.select(selected);
+    }
+
+    pub fn next(&mut self) {
+        let n = self.names.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + 1) % n)));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        let n = self.names.len();
+        if n > 0 {
+            self.state
+                .select(Some(self.state.selected().map_or(0, |i| (i + n - 1) % n)));
+        }
+    }
+
+    pub fn choice(&self) -> Option<&String> {
+        self.state.selected().and_then(|i| self.names.get(i))
+    }
+}
+
+impl Component for Weights {
+    fn handle_key_events(&mut self, key: KeyEvent) -> Option<Action> {
+        match keymap::action(Context::ViewerWeights, key)? {
+            Action::SubmitSelection => Some(Action::SetWeights(self.choice()?.clone())),
+            action => Some(action),
+        }
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::MoveSelectionNext => self.next(),
+            Action::MoveSelectionPrevious => self.previous(),
+            _ => (),
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, rect: Rect) {
+        f.render_widget(Clear, rect);
+        f.render_widget(
+            Block::default()
+                .title(Line::from(vec![Span::styled(
+                    tr("Weights"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )]))
+                .borders(Borders::ALL)
+                .border_style(palette::fg(Color::Yellow)),
+            rect,
+        );
+        let inner = rect.inner(&Margin {
+            vertical: 1,
+            horizontal: 2,
+        });
+        let [list_area, hint_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(inner);
+        let hint = if self.names.is_empty() {
+            Span::styled(
+                "No other dataset has the same dimensions and labels.",
+                palette::fg(Color::Red),
+            )
+        } else {
+            Span::styled(
+                "Press Enter to weight means by this dataset, ESC to cancel.",
+                palette::fg(Color::DarkGray),
+            )
+        };
+        let items: Vec<ListItem> = self
+            .names
+            .iter()
+            .map(|name| ListItem::new(Line::from(name.clone())))
+            .collect();
+        let list = List::new(items)
+            .highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("→ ");
+        f.render_stateful_widget(list, list_area, &mut self.state);
+        f.render_widget(Paragraph::new(Line::from(hint)), hint_area);
+    }
+}
@@ -0,0 +1,477 @@
+use std::{path::Path, sync::Arc};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::{stack, ArrayD, ArrayViewD, Axis, IxDyn, SliceInfo, SliceInfoElem};
+
+use crate::data::{self, Data, LabelCache};
+
+/// Arithmetic on datasets with the same sets, e.g. `ooutput/Emissions / ooutput/Demand`.
+///
+/// Dataset names contain `/`, so division needs spaces around it. Expressions are evaluated
+/// lazily: each slice the Viewer reads is read from every operand and combined element-wise.
+#[derive(Debug)]
+pub struct Expression {
+    pub text: String,
+    pub expr: Expr,
+    /// Datasets the expression refers to, each once
+    pub operands: Vec<Data>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// Index in the operands
+    Dataset(usize),
+    Neg(Box<Expr>),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn apply(self, a: f64, b: f64) -> f64 {
+        match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+        }
+    }
+}
+
+/// Name of the set a [`Combination`] adds, whose labels are the names of its datasets.
+pub const COMBINATION_SET: &str = "Variable";
+
+/// Whether `text` is a valid expression rather than the name of a single dataset.
+pub fn is_expression(text: &str) -> bool {
+    matches!(parse(text), Ok((expr, _)) if !matches!(expr, Expr::Dataset(_)))
+}
+
+/// Whether `text` lists several datasets to combine, separated by commas.
+pub fn is_combination(text: &str) -> bool {
+    text.contains(',')
+}
+
+/// What the text of [`open`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Dataset,
+    Combination,
+    Expression,
+}
+
+/// What `name` refers to, given whether `exists` finds a dataset of that name. A dataset wins, so
+/// names such as `ioutput/CO2-eq` or `Imports, total` open as they are.
+pub fn kind(name: &str, exists: impl FnOnce(&str) -> bool) -> Kind {
+    if exists(name.trim()) {
+        Kind::Dataset
+    } else if is_combination(name) {
+        Kind::Combination
+    } else if is_expression(name) {
+        Kind::Expression
+    } else {
+        Kind::Dataset
+    }
+}
+
+/// Opens the dataset, the expression or the combination `name` of `file`.
+pub fn open(file: &Path, name: &str) -> Result<Data> {
+    let f = data::open_file(file)?;
+    match kind(name, |name| f.dataset(name).is_ok()) {
+        Kind::Dataset => Data::open(&f, name.trim(), &LabelCache::of(file)),
+        Kind::Combination => Combination::open(file, name),
+        Kind::Expression => Expression::open(file, name),
+    }
+}
+
+/// Datasets with the same sets side by side, e.g. `ooutput/Imports, ooutput/Exports`, along an
+/// extra set labelled by their names, so related variables can be compared in one table.
+#[derive(Debug)]
+pub struct Combination {
+    pub operands: Vec<Data>,
+}
+
+impl Combination {
+    /// Opens the datasets of `text`, as a [`Data`] with the sets of the first one followed by
+    /// [`COMBINATION_SET`].
+    pub fn open(file: &Path, text: &str) -> Result<Data> {
+        let (f, labels) = (data::open_file(file)?, LabelCache::of(file));
+        let operands = text
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(|name| Data::open(&f, name, &labels))
+            .collect::<Result<Vec<_>>>()?;
+        let first = operands
+            .first()
+            .ok_or_else(|| eyre!("{text:?} refers to no dataset"))?
+            .clone();
+        for d in operands.iter().skip(1) {
+            if d.set_names != first.set_names || d.set_data != first.set_data {
+                return Err(eyre!(
+                    "{} does not have the dimensions of {}: {} instead of {}",
+                    d.name,
+                    first.name,
+                    d.set_names.join(", "),
+                    first.set_names.join(", ")
+                ));
+            }
+        }
+        let names: Vec<String> = operands.iter().map(|d| d.name.clone()).collect();
+        let mut units: Vec<&str> = operands.iter().map(|d| d.units.trim()).collect();
+        units.dedup();
+        let (mut set_names, mut set_data, mut shape) = (
+            first.set_names.clone(),
+            first.set_data.clone(),
+            first.shape.clone(),
+        );
+        set_names.push(COMBINATION_SET.into());
+        set_data.push(names.clone());
+        shape.push(names.len());
+        Ok(Data {
+            name: names.join(", "),
+            doc: format!("Side by side: {}", names.join(", ")),
+            units: units.join(", "),
+            typ: "combined".into(),
+            ndims: first.ndims + 1,
+            set_names,
+            set_data,
+            shape,
+            chunk: None,
+            combination: Some(Arc::new(Combination { operands })),
+            ..first
+        })
+    }
+
+    /// Reads the selection `s` of every dataset it selects and stacks them along the first axis,
+    /// which is that of [`COMBINATION_SET`] since the file stores dimensions in reverse order.
+    pub fn read_selection(
+        &self,
+        s: &SliceInfo<Vec<SliceInfoElem>, IxDyn, IxDyn>,
+    ) -> Result<ArrayD<f64>> {
+        let elems: &[SliceInfoElem] = s.as_ref();
+        let (first, rest) = elems
+            .split_first()
+            .ok_or_else(|| eyre!("Empty selection"))?;
+        let rest = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(rest.to_vec())?;
+        let read = |i: usize| -> Result<ArrayD<f64>> {
+            self.operands
+                .get(i)
+                .ok_or_else(|| eyre!("No dataset at index {i}"))?
+                .read_selection(&rest)
+        };
+        match *first {
+            SliceInfoElem::Index(i) => read(i as usize),
+            SliceInfoElem::Slice { start, end, .. } => {
+                let end = end.map_or(self.operands.len(), |e| e as usize);
+                self.stack(
+                    (start as usize..end)
+                        .map(read)
+                        .collect::<Result<Vec<_>>>()?,
+                )
+            }
+            SliceInfoElem::NewAxis => Err(eyre!("Unexpected new axis in {s:?}")),
+        }
+    }
+
+    /// Every value of every dataset, stacked along the first axis.
+    pub fn read_all(&self) -> Result<ArrayD<f64>> {
+        self.stack(
+            self.operands
+                .iter()
+                .map(|d| d.read_all())
+                .collect::<Result<Vec<_>>>()?,
+        )
+    }
+
+    fn stack(&self, arrays: Vec<ArrayD<f64>>) -> Result<ArrayD<f64>> {
+        let views: Vec<ArrayViewD<f64>> = arrays.iter().map(|a| a.view()).collect();
+        Ok(stack(Axis(0), &views)?)
+    }
+}
+
+impl Expression {
+    /// Parses `text` and opens its datasets, as a [`Data`] with the sets of the first one.
+    pub fn open(file: &Path, text: &str) -> Result<Data> {
+        let (expr, names) = parse(text)?;
+        let (f, labels) = (data::open_file(file)?, LabelCache::of(file));
+        let operands = names
+            .into_iter()
+            .map(|name| Data::open(&f, &name, &labels))
+            .collect::<Result<Vec<_>>>()?;
+        let first = operands
+            .first()
+            .ok_or_else(|| eyre!("{text:?} refers to no dataset"))?
+            .clone();
+        for d in operands.iter().skip(1) {
+            if d.set_names != first.set_names || d.set_data != first.set_data {
+                return Err(eyre!(
+                    "{} does not have the dimensions of {}: {} instead of {}",
+                    d.name,
+                    first.name,
+                    d.set_names.join(", "),
+                    first.set_names.join(", ")
+                ));
+            }
+        }
+        let text = text.trim().to_string();
+        let expression = Expression {
+            text: text.clone(),
+            expr,
+            operands,
+        };
+        Ok(Data {
+            name: text.clone(),
+            doc: format!("Computed as {text}"),
+            units: expression.units(&expression.expr),
+            typ: "computed".into(),
+            expression: Some(Arc::new(expression)),
+            ..first
+        })
+    }
+
+    /// Combines the arrays `read` returns for every operand, which all have the same shape.
+    pub fn evaluate(&self, read: &dyn Fn(&Data) -> Result<ArrayD<f64>>) -> Result<ArrayD<f64>> {
+        let values = self.operands.iter().map(read).collect::<Result<Vec<_>>>()?;
+        match evaluate(&self.expr, &values) {
+            Value::Array(a) => Ok(a),
+            // Every expression has at least one dataset
+            Value::Scalar(_) => Err(eyre!("{:?} refers to no dataset", self.text)),
+        }
+    }
+
+    /// Units of `expr`, from those of the operands, e.g. `Mt/PJ`.
+    fn units(&self, expr: &Expr) -> String {
+        let group = |u: String| {
+            if u.contains(['/', '·']) {
+                format!("({u})")
+            } else {
+                u
+            }
+        };
+        match expr {
+            Expr::Number(_) => String::default(),
+            Expr::Dataset(i) => self.operands[*i].units.trim().to_string(),
+            Expr::Neg(e) => self.units(e),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (self.units(a), self.units(b));
+                match op {
+                    Op::Add | Op::Sub if a.is_empty() => b,
+                    Op::Add | Op::Sub => a,
+                    Op::Mul if a.is_empty() || b.is_empty() => a + &b,
+                    Op::Mul => format!("{a}·{}", group(b)),
+                    Op::Div if a == b => String::default(),
+                    Op::Div if b.is_empty() => a,
+                    Op::Div => {
+                        format!("{}/{}", if a.is_empty() { "1".into() } else { a }, group(b))
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum Value {
+    Scalar(f64),
+    Array(ArrayD<f64>),
+}
+
+fn evaluate(expr: &Expr, values: &[ArrayD<f64>]) -> Value {
+    match expr {
+        Expr::Number(x) => Value::Scalar(*x),
+        Expr::Dataset(i) => Value::Array(values[*i].clone()),
+        Expr::Neg(e) => match evaluate(e, values) {
+            Value::Scalar(x) => Value::Scalar(-x),
+            Value::Array(a) => Value::Array(a.mapv(|x| -x)),
+        },
+        Expr::Binary(op, a, b) => match (evaluate(a, values), evaluate(b, values)) {
+            (Value::Scalar(x), Value::Scalar(y)) => Value::Scalar(op.apply(x, y)),
+            (Value::Array(a), Value::Scalar(y)) => Value::Array(a.mapv(|x| op.apply(x, y))),
+            (Value::Scalar(x), Value::Array(b)) => Value::Array(b.mapv(|y| op.apply(x, y))),
+            (Value::Array(mut a), Value::Array(b)) => {
+                a.zip_mut_with(&b, |x, &y| *x = op.apply(*x, y));
+                Value::Array(a)
+            }
+        },
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+    Open,
+    Close,
+}
+
+/// Splits `text` into tokens. A `/` followed by a letter, digit or `_` is part of a name.
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_name = |c: char| c.is_alphanumeric() || c == '_' || c == '.';
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '(' | ')' => {
+                tokens.push(if c == '(' { Token::Open } else { Token::Close });
+                i += 1;
+            }
+            '/' if !chars.get(i + 1).is_some_and(|&n| is_name(n)) => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '+' | '-' | '*' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            c if is_name(c) || c == '/' => {
+                let start = i;
+                while i < chars.len()
+                    && (is_name(chars[i])
+                        || chars[i] == '/' && chars.get(i + 1).is_some_and(|&n| is_name(n)))
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                tokens.push(match word.parse::<f64>() {
+                    Ok(x) if c.is_ascii_digit() || c == '.' => Token::Number(x),
+                    _ => Token::Name(word),
+                });
+            }
+            c => return Err(eyre!("Unexpected {c:?} in {text:?}")),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parses `text` into an expression and the names of the datasets it refers to.
+pub fn parse(text: &str) -> Result<(Expr, Vec<String>)> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+        names: Vec::new(),
+    };
+    let expr = parser.sum()?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(eyre!("Unexpected {token:?} in {text:?}"));
+    }
+    Ok((expr, parser.names))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    names: Vec<String>,
+}
+
+impl Parser {
+    fn next_op(&mut self, ops: &[char]) -> Option<Op> {
+        let op = match self.tokens.get(self.pos) {
+            Some(Token::Op(c)) if ops.contains(c) => match c {
+                '+' => Op::Add,
+                '-' => Op::Sub,
+                '*' => Op::Mul,
+                _ => Op::Div,
+            },
+            _ => return None,
+        };
+        self.pos += 1;
+        Some(op)
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut expr = self.product()?;
+        while let Some(op) = self.next_op(&['+', '-']) {
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.product()?));
+        }
+        Ok(expr)
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut expr = self.factor()?;
+        while let Some(op) = self.next_op(&['*', '/']) {
+            expr = Expr::Binary(op, Box::new(expr), Box::new(self.factor()?));
+        }
+        Ok(expr)
+    }
+
+    fn factor(&mut self) -> Result<Expr> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| eyre!("Unexpected end of expression"))?;
+        self.pos += 1;
+        Ok(match token {
+            Token::Op('-') => Expr::Neg(Box::new(self.factor()?)),
+            Token::Number(x) => Expr::Number(x),
+            Token::Name(name) => {
+                let name = name.trim_start_matches('/').to_string();
+                let i = match self.names.iter().position(|n| *n == name) {
+                    Some(i) => i,
+                    None => {
+                        self.names.push(name);
+                        self.names.len() - 1
+                    }
+                };
+                Expr::Dataset(i)
+            }
+            Token::Open => {
+                let expr = self.sum()?;
+                if self.tokens.get(self.pos) != Some(&Token::Close) {
+                    return Err(eyre!("Missing )"));
+                }
+                self.pos += 1;
+                expr
+            }
+            token => return Err(eyre!("Unexpected {token:?}")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (expr, names) = parse("ooutput/Emissions / ooutput/Demand * 1000").unwrap();
+        assert_eq!(names, vec!["ooutput/Emissions", "ooutput/Demand"]);
+        assert_eq!(
+            expr,
+            Expr::Binary(
+                Op::Mul,
+                Box::new(Expr::Binary(
+                    Op::Div,
+                    Box::new(Expr::Dataset(0)),
+                    Box::new(Expr::Dataset(1))
+                )),
+                Box::new(Expr::Number(1000.0))
+            )
+        );
+        assert!(!is_expression("/routput/Dmd"));
+        assert!(is_expression("-(a/x + a/x)"));
+        assert!(parse("a/x / (b/y").is_err());
+        assert!(is_combination("a/x, b/y"));
+        assert!(!is_combination("a/x / b/y"));
+    }
+
+    #[test]
+    fn test_kind() {
+        assert!(is_expression("ioutput/CO2-eq"));
+        assert_eq!(kind("ioutput/CO2-eq", |_| true), Kind::Dataset);
+        assert_eq!(kind("ioutput/CO2-eq", |_| false), Kind::Expression);
+        assert_eq!(kind("a/x, b/y", |name| name == "a/x, b/y"), Kind::Dataset);
+        assert_eq!(kind("a/x, b/y", |_| false), Kind::Combination);
+        assert_eq!(kind("/routput/Dmd", |_| false), Kind::Dataset);
+    }
+}
@@ -0,0 +1,424 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    i18n::Language,
+    palette::Palette,
+    units::Conversion,
+    utils::{get_config_dir, get_data_dir},
+};
+
+pub const CONFIG_FILE: &str = "config.json";
+pub const SESSION_FILE: &str = "session.json";
+pub const PICKER_FILE: &str = "picker.json";
+
+/// User configuration, read from `config.json` in the config directory.
+///
+/// Every field is optional in the file; anything missing falls back to its default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Disable animations and transient highlights (spinners, flashes, last key pressed).
+    pub reduced_motion: bool,
+    /// Slices with more cells than this open as an aggregated overview.
+    pub max_slice_cells: usize,
+    /// Slices with more cells than this are only read, even as an overview, once confirmed.
+    pub confirm_slice_cells: usize,
+    /// Most columns of values the Viewer shows at once, however wide the screen, if set.
+    pub max_columns: Option<usize>,
+    /// Index to start at for each set name, e.g. `{"Year": "last", "Area": "ON"}`.
+    pub default_indices: HashMap<String, DefaultIndex>,
+    /// Keep the index of sets shared with the previously viewed dataset.
+    pub sticky_indices: bool,
+    /// Reopen a dataset with the axes, indices and options it had when it was closed.
+    pub remember_views: bool,
+    /// Sets datasets open with as columns, the first one they have, e.g. `["Year"]`. Their
+    /// largest other set is shown as rows.
+    pub column_sets: Vec<String>,
+    /// Open datasets with rows sorted by descending total instead of file order.
+    pub sort_by_total: bool,
+    /// What to show on launch when no `--dataset` is given.
+    pub startup: Startup,
+    /// Show the summary of the current indices above the Viewer table.
+    pub show_summary: bool,
+    /// Capture the mouse, to click in the scrubbers of the summary, instead of selecting text.
+    pub mouse: bool,
+    /// Log every dataset and slice visited, written as a Markdown journal on exit.
+    pub journal: bool,
+    /// Colours of deltas and gradients.
+    pub palette: Palette,
+    /// Command run on the current slice, which it receives as CSV on stdin.
+    pub hook: Option<String>,
+    /// Seconds after which a hook still running is stopped, `0` to let it run as long as it takes.
+    pub hook_timeout_secs: u64,
+    /// Command run on the marked datasets of the Picker, which it receives as CSV files.
+    pub plugin: Option<String>,
+    /// Share of the slice total, in percent, below which rows are grouped into "Other".
+    pub other_share: f64,
+    /// Number of rows first offered when showing only the rows with the largest totals.
+    pub top_rows: usize,
+    /// Sum the rows left out of the top rows into an "Other" row.
+    pub top_rows_other: bool,
+    /// Number of decimals values are shown with.
+    pub decimals: usize,
+    /// How zeros, whole numbers and missing values are written, e.g. `{"integers": false}`.
+    pub formatting: Formatting,
+    /// Unit conversions offered before the built-in ones, which they replace for the same units.
+    pub unit_conversions: Vec<Conversion>,
+    /// Mapping files rolling up the members of a set into groups, by set name, e.g.
+    /// `{"Area": "regions.csv"}`.
+    pub rollups: HashMap<String, PathBuf>,
+    /// Dictionary file of long labels for set codes, e.g. `labels.csv`.
+    pub label_dictionary: Option<PathBuf>,
+    /// Number of bins the distribution of the values of a slice first shows.
+    pub histogram_bins: usize,
+    /// Ticks per second, which drive spinners and notification timeouts.
+    pub tick_rate: f64,
+    /// Frames rendered per second.
+    pub frame_rate: f64,
+    /// Milliseconds to wait after stepping through a dimension before reading the new slice.
+    pub navigation_delay_ms: u64,
+    /// Where the Viewer shows the Total column: `"first"`, after the row labels, or `"last"`.
+    pub total_column: TotalColumn,
+    /// Where the Viewer shows the Total row: `"bottom"` or `"top"`.
+    pub total_row: TotalRow,
+    /// Language of the interface, from the locale if missing.
+    pub language: Option<Language>,
+    /// File opened by `--writable`, the only one whose values can be edited and saved.
+    #[serde(skip)]
+    pub writable: Option<String>,
+}
+
+/// Initial screen when no dataset is given on the command line.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Startup {
+    /// Start in the Picker
+    #[default]
+    Picker,
+    /// Reopen the last dataset viewed in the same file
+    LastDataset,
+}
+
+/// How the Viewer writes values, each option independent of the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Formatting {
+    /// Write zeros as `-`.
+    pub dash_zeros: bool,
+    /// Write whole numbers without decimals.
+    pub integers: bool,
+    /// Leave missing values blank instead of writing `·`.
+    pub blank_missing: bool,
+}
+
+impl Default for Formatting {
+    fn default() -> Self {
+        Self {
+            dash_zeros: true,
+            integers: true,
+            blank_missing: false,
+        }
+    }
+}
+
+impl Formatting {
+    /// The options, in the order of the formatting menu.
+    pub const OPTIONS: [&'static str; 3] = [
+        "Zeros as dashes",
+        "Whole numbers without decimals",
+        "Missing values left blank",
+    ];
+
+    pub fn get(&self, option: usize) -> bool {
+        match option {
+            0 => self.dash_zeros,
+            1 => self.integers,
+            _ => self.blank_missing,
+        }
+    }
+
+    pub fn toggle(&mut self, option: usize) {
+        match option {
+            0 => self.dash_zeros = !self.dash_zeros,
+            1 => self.integers = !self.integers,
+            _ => self.blank_missing = !self.blank_missing,
+        }
+    }
+
+    /// The options of a view file written before they could be set one at a time, when
+    /// `"formatted"` switched dashes and whole numbers together.
+    pub fn from_formatted(formatted: bool) -> Self {
+        Self {
+            dash_zeros: formatted,
+            integers: formatted,
+            blank_missing: false,
+        }
+    }
+}
+
+/// Position of the Total column of the Viewer table and its exports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TotalColumn {
+    #[default]
+    First,
+    Last,
+}
+
+/// Position of the Total row of the Viewer table and its exports.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TotalRow {
+    #[default]
+    Bottom,
+    Top,
+}
+
+/// Where a dimension starts when a dataset is opened.
+///
+/// Either a position (negative values count from the end, so `-1` is the last element) or a set
+/// label. The labels `"first"` and `"last"` are accepted as shorthands.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DefaultIndex {
+    Position(i64),
+    Label(String),
+}
+
+impl DefaultIndex {
+    /// Parses `Set=element` as given to `--index`, e.g. `Year=last`, `Area=ON` or `Year=-2`.
+    pub fn parse_assignment(s: &str) -> Result<(String, Self), String> {
+        let (set, element) = s
+            .split_once('=')
+            .filter(|(set, element)| !set.trim().is_empty() && !element.trim().is_empty())
+            .ok_or_else(|| format!("expected SET=ELEMENT, e.g. Year=last, not {s:?}"))?;
+        let element = element.trim();
+        let index = match element.parse::<i64>() {
+            Ok(i) => DefaultIndex::Position(i),
+            Err(_) => DefaultIndex::Label(element.into()),
+        };
+        Ok((set.trim().into(), index))
+    }
+
+    pub fn resolve(&self, labels: &[String]) -> Option<usize> {
+        let len = labels.len() as i64;
+        match self {
+            DefaultIndex::Position(i) if *i < 0 && -i <= len => Some((len + i) as usize),
+            DefaultIndex::Position(i) if *i >= 0 && *i < len => Some(*i as usize),
+            DefaultIndex::Position(_) => None,
+            DefaultIndex::Label(l) if l == "first" => (len > 0).then_some(0),
+            DefaultIndex::Label(l) if l == "last" => (len > 0).then_some(len as usize - 1),
+            DefaultIndex::Label(l) => labels.iter().position(|x| x.trim() == l.trim()),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            reduced_motion: false,
+            max_slice_cells: 1_000_000,
+            confirm_slice_cells: 50_000_000,
+            max_columns: None,
+            default_indices: HashMap::default(),
+            sticky_indices: true,
+            remember_views: true,
+            column_sets: vec!["Year".into()],
+            sort_by_total: false,
+            startup: Startup::default(),
+            show_summary: true,
+            mouse: true,
+            journal: false,
+            palette: Palette::default(),
+            hook: None,
+            hook_timeout_secs: 300,
+            plugin: None,
+            other_share: 1.0,
+            top_rows: 10,
+            top_rows_other: true,
+            decimals: 2,
+            formatting: Formatting::default(),
+            unit_conversions: Vec::default(),
+            rollups: HashMap::default(),
+            label_dictionary: None,
+            histogram_bins: 20,
+            tick_rate: 4.0,
+            frame_rate: 4.0,
+            navigation_delay_ms: 150,
+            total_column: TotalColumn::default(),
+            total_row: TotalRow::default(),
+            language: None,
+            writable: None,
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Result<Self> {
+        Self::from_path(get_config_dir().join(CONFIG_FILE))
+    }
+
+    /// Why the values of `file` cannot be edited, or `None` if it is the file opened with
+    /// `--writable`. Other files, such as the next ones given with `--file` or a copy of a locked
+    /// file, are open read-only.
+    pub fn read_only_reason(&self, file: &str) -> Option<&'static str> {
+        match self.writable {
+            None => Some("Start with --writable to edit values"),
+            Some(ref writable) if writable != file => {
+                Some("Only the file opened with --writable can be edited")
+            }
+            Some(_) => None,
+        }
+    }
+
+    pub fn from_path(path: PathBuf) -> Result<Self> {
+        if !path.exists() {
+            log::debug!("No config found at {path:?}, using defaults");
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        let config: Self = serde_json::from_str(&contents)
+            .map_err(|e| eyre!("Unable to parse config {path:?}: {e}"))?;
+        for (name, rate) in [
+            ("tick_rate", config.tick_rate),
+            ("frame_rate", config.frame_rate),
+        ] {
+            if !is_rate(rate) {
+                return Err(eyre!(
+                    "{name} in config {path:?} must be a number per second between {MIN_RATE} \
+                     and {MAX_RATE}, not {rate}"
+                ));
+            }
+        }
+        Ok(config)
+    }
+
+    /// Starting index for the set `name`, falling back to the first element.
+    pub fn default_index(&self, name: &str, labels: &[String]) -> usize {
+        self.default_indices
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .and_then(|(_, d)| {
+                let i = d.resolve(labels);
+                if i.is_none() {
+                    log::warn!("Default index {d:?} for {name} does not match any element");
+                }
+                i
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Bounds of the tick and frame rates, in events per second, also when changed at runtime.
+pub const MIN_RATE: f64 = 0.5;
+pub const MAX_RATE: f64 = 120.0;
+
+/// Whether `rate` can be a tick or frame rate: a number of events per second between
+/// [`MIN_RATE`] and [`MAX_RATE`].
+pub fn is_rate(rate: f64) -> bool {
+    (MIN_RATE..=MAX_RATE).contains(&rate)
+}
+
+/// Parses a tick or frame rate given on the command line.
+pub fn parse_rate(s: &str) -> Result<f64, String> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|&rate| is_rate(rate))
+        .ok_or_else(|| {
+            format!("{s:?} is not a number per second between {MIN_RATE} and {MAX_RATE}")
+        })
+}
+
+/// Last dataset opened, kept in the data directory between runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub file: PathBuf,
+    pub dataset: String,
+}
+
+/// `file` as an absolute path, so the same file is found under any relative path.
+fn canonical(file: &str) -> PathBuf {
+    std::fs::canonicalize(file).unwrap_or_else(|_| file.into())
+}
+
+impl Session {
+    pub fn new(file: &str, dataset: &str) -> Self {
+        Self {
+            file: canonical(file),
+            dataset: dataset.into(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = get_data_dir().join(SESSION_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Last session of `file`, if there is one.
+    pub fn load(file: &str) -> Option<Self> {
+        let contents = std::fs::read_to_string(get_data_dir().join(SESSION_FILE)).ok()?;
+        let session: Self = serde_json::from_str(&contents)
+            .map_err(|e| log::warn!("Ignoring unreadable session: {e}"))
+            .ok()?;
+        (session.file == Self::new(file, "").file).then_some(session)
+    }
+}
+
+/// How the Picker of a file was left, kept in the data directory between runs for every file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PickerState {
+    /// Column the datasets are sorted by, in scan order if `None`
+    pub sort_column: Option<usize>,
+    pub descending: bool,
+    /// Fuzzy find filter
+    pub filter: String,
+}
+
+impl PickerState {
+    /// The Picker of `file` as it was last left, or the default.
+    pub fn load(file: &str) -> Self {
+        Self::load_all()
+            .remove(&canonical(file))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, file: &str) -> Result<()> {
+        let mut states = Self::load_all();
+        states.insert(canonical(file), self.clone());
+        let path = get_data_dir().join(PICKER_FILE);
+        std::fs::write(&path, serde_json::to_string_pretty(&states)?)?;
+        Ok(())
+    }
+
+    fn load_all() -> HashMap<PathBuf, Self> {
+        std::fs::read_to_string(get_data_dir().join(PICKER_FILE))
+            .ok()
+            .and_then(|contents| {
+                serde_json::from_str(&contents)
+                    .map_err(|e| log::warn!("Ignoring unreadable Picker states: {e}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("4"), Ok(4.0));
+        assert_eq!(parse_rate(" 0.5 "), Ok(MIN_RATE));
+        assert_eq!(parse_rate("120"), Ok(MAX_RATE));
+        for s in ["0", "-4", "1e-20", "121", "inf", "NaN", "fast"] {
+            assert!(parse_rate(s).is_err(), "{s}");
+        }
+    }
+}
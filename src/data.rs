@@ -1,29 +1,285 @@
-use std::{ops::Range, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use hdf5::{
     types::{FixedUnicode, VarLenUnicode},
     Dataset, Selection,
 };
-use ndarray::{Array2, ArrayD};
+use lazy_static::lazy_static;
+use ndarray::{
+    s, Array1, Array2, ArrayD, ArrayView2, Axis, Ix2, IxDyn, SliceInfo, SliceInfoElem, Zip,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    compute::{Combination, Expression},
+    rollup::Rollup,
+    stats::{nanmean, nansum},
+    utils::csv_field,
+};
+
+/// Most cells read at once while streaming a dataset to a file.
+pub const BLOCK_CELLS: usize = 1 << 20;
+
+/// Length of the text attributes of the datasets, e.g. `units` and `doc`, in bytes.
+pub const ATTRIBUTE_BYTES: usize = 100;
+
+/// Opens `path` read-only, with the path and OS error number in the error message.
+///
+/// The file is opened once with the standard library first, so permission problems are reported
+/// with their errno rather than as a generic HDF5 error.
+pub fn open_file(path: impl AsRef<Path>) -> Result<hdf5::File> {
+    open_with(path.as_ref(), false)
+}
+
+/// Opens `path` read-write, to write edited values or attributes or a new dataset to it.
+///
+/// HDF5 refuses to open a file read-write while it is open read-only, so the file edited with
+/// `--writable` is held open this way for the whole session, and the read-only opens share it.
+pub fn open_file_rw(path: impl AsRef<Path>) -> Result<hdf5::File> {
+    open_with(path.as_ref(), true)
+}
+
+fn open_with(path: &Path, writable: bool) -> Result<hdf5::File> {
+    // The message of an OS error already ends with its errno, e.g. `(os error 13)`
+    if let Err(e) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(writable)
+        .open(path)
+    {
+        return Err(eyre!("Unable to open {}: {e}", path.display()));
+    }
+    let f = if writable {
+        hdf5::File::open_rw(path)
+    } else {
+        hdf5::File::open(path)
+    };
+    f.map_err(|e| eyre!("Unable to open {}: {e}", path.display()))
+}
+
+/// Replaces the `units` and `doc` attributes of the dataset `name` of `f`.
+pub fn write_attributes(f: &hdf5::File, name: &str, units: &str, doc: &str) -> Result<()> {
+    let dataset = f.dataset(name)?;
+    write_text_attr(&dataset, "units", units)?;
+    write_text_attr(&dataset, "doc", doc)
+}
+
+/// Writes `text` as the attribute `attr` of `dataset`, replacing any attribute of that name,
+/// as the fixed-length string the datasets of the file have.
+fn write_text_attr(dataset: &Dataset, attr: &str, text: &str) -> Result<()> {
+    let value = ndarray::arr0(fixed_text(attr, text)?);
+    if dataset.attr_names()?.iter().any(|a| a == attr) {
+        dataset.delete_attr(attr)?;
+    }
+    dataset.new_attr_builder().with_data(&value).create(attr)?;
+    Ok(())
+}
+
+/// `text` as the fixed-length string of the attributes of the file, which must fit in
+/// [`ATTRIBUTE_BYTES`].
+fn fixed_text(attr: &str, text: &str) -> Result<FixedUnicode<ATTRIBUTE_BYTES>> {
+    if text.len() > ATTRIBUTE_BYTES {
+        return Err(eyre!(
+            "The {attr} is {} bytes long, over the {ATTRIBUTE_BYTES} an attribute holds",
+            text.len()
+        ));
+    }
+    Ok(text.parse()?)
+}
+
+/// The start of `text` that fits in an attribute, cut at a character boundary.
+pub fn attribute_prefix(text: &str) -> &str {
+    let mut end = text.len().min(ATTRIBUTE_BYTES);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
 
+/// Names of every dataset in the file, as `group/name`.
+pub fn variables(path: impl AsRef<Path>) -> Result<Vec<String>> {
+    let f = open_file(path)?;
+    let mut names = vec![];
+    for group in f.member_names()? {
+        for dataset in f.group(&group)?.member_names()? {
+            names.push(format!("{group}/{dataset}"));
+        }
+    }
+    Ok(names)
+}
+
+/// The window `rows` x `cols` of `data`, which holds `read_rows` x `read_cols` indexed as
+/// `[[col, row]]`.
+fn within(
+    data: ArrayView2<f64>,
+    read_rows: &Range<usize>,
+    read_cols: &Range<usize>,
+    rows: &Range<usize>,
+    cols: &Range<usize>,
+) -> Array2<f64> {
+    data.slice(s![
+        cols.start - read_cols.start..cols.end - read_cols.start,
+        rows.start - read_rows.start..rows.end - read_rows.start
+    ])
+    .to_owned()
+}
+
+/// Labels of the sets of a file by path, e.g. `routput/Area`, read once for every dataset of the
+/// group that uses the set.
+#[derive(Debug, Default, Clone)]
+pub struct LabelCache(Arc<Mutex<HashMap<String, Vec<String>>>>);
+
+lazy_static! {
+    /// Label caches by file, with the modification time of the file when it was created
+    static ref LABEL_CACHES: Mutex<HashMap<PathBuf, (Option<SystemTime>, LabelCache)>> =
+        Mutex::default();
+}
+
+impl LabelCache {
+    /// The cache shared by every [`Data`] of the file at `path`, replaced by an empty one when
+    /// the file was modified since.
+    pub fn of(path: &Path) -> Self {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut caches = LABEL_CACHES.lock().unwrap();
+        match caches.get(path) {
+            Some((time, cache)) if *time == modified => cache.clone(),
+            _ => {
+                let cache = Self::default();
+                caches.insert(path.into(), (modified, cache.clone()));
+                cache
+            }
+        }
+    }
+
+    /// Labels of the set at `path` in `f`, read from the file the first time only.
+    pub fn get(&self, f: &hdf5::File, path: &str) -> Result<Vec<String>> {
+        if let Some(labels) = self.0.lock().unwrap().get(path) {
+            return Ok(labels.clone());
+        }
+        let labels: Vec<String> = f
+            .dataset(path)?
+            .read_1d::<VarLenUnicode>()?
+            .into_iter()
+            .map(|label| label.to_string())
+            .collect();
+        self.0.lock().unwrap().insert(path.into(), labels.clone());
+        Ok(labels)
+    }
+}
+
+/// How a dimension that is not displayed is reduced to a single value.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    /// At one element
+    #[default]
+    Fix,
+    /// Summed over every element
+    Sum,
+    /// Averaged over every element
+    Mean,
+    /// Averaged over every element, weighted by the values of another dataset
+    Weighted,
+}
+
+impl Aggregation {
+    pub fn next(self) -> Self {
+        match self {
+            Aggregation::Fix => Aggregation::Sum,
+            Aggregation::Sum => Aggregation::Mean,
+            Aggregation::Mean => Aggregation::Weighted,
+            Aggregation::Weighted => Aggregation::Fix,
+        }
+    }
+}
+
+impl std::fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Aggregation::Fix => write!(f, "fixed"),
+            Aggregation::Sum => write!(f, "sum"),
+            Aggregation::Mean => write!(f, "mean"),
+            Aggregation::Weighted => write!(f, "weighted mean"),
+        }
+    }
+}
+
+/// Axes a dataset opens with, as (columns, rows): the first set of `column_sets` it has as
+/// columns, matched regardless of case, and its largest other set as rows. Without any of
+/// `column_sets`, or if `column_sets` is empty, its last and first dimensions.
+pub fn default_axes(
+    set_names: &[String],
+    shape: &[usize],
+    column_sets: &[String],
+) -> (usize, usize) {
+    let ndims = set_names.len();
+    if ndims < 2 {
+        return (0, 0);
+    }
+    let Some(columns) = column_sets.iter().find_map(|preferred| {
+        set_names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(preferred))
+    }) else {
+        return (ndims - 1, 0);
+    };
+    // The first of the largest sets, for files listing them in a meaningful order
+    let rows = (0..ndims)
+        .filter(|&dim| dim != columns)
+        .rev()
+        .max_by_key(|&dim| shape[dim])
+        .unwrap_or_default();
+    (columns, rows)
+}
+
+/// A variable of the database along with its metadata and the labels of its sets.
 #[derive(Debug, Clone)]
 pub struct Data {
+    /// Full path of the variable, e.g. `/routput/Dmd`
     pub name: String,
     pub doc: String,
     pub units: String,
+    /// Names of the sets indexing each dimension
     pub set_names: Vec<String>,
     pub ndims: usize,
     pub typ: String,
+    /// Length of each dimension, in the order of `set_names`
     pub shape: Vec<usize>,
+    /// Value of the `_FillValue` attribute, which marks missing values like NaN
+    pub fill_value: Option<f64>,
     pub dataset: Dataset,
+    /// Labels of each set, in the order of `set_names`
     pub set_data: Vec<Vec<String>>,
+    /// Expression the values are computed with instead of read from `dataset`, which is then
+    /// that of its first operand
+    pub expression: Option<Arc<Expression>>,
+    /// Datasets read side by side instead of `dataset`, which is then that of the first one
+    pub combination: Option<Arc<Combination>>,
+    /// Chunk size along each dimension, in the order of `set_names`, if the dataset is chunked
+    pub chunk: Option<Vec<usize>>,
+    /// Groups the members of each set are summed into, in the order of `set_names`, for the
+    /// sets that are rolled up
+    pub rollups: Vec<Option<Rollup>>,
 }
 
 impl Data {
     pub fn new(file: PathBuf, name: String) -> Result<Self> {
-        let f = hdf5::File::open(file)?;
-        let dataset = f.dataset(&name)?;
+        let f = open_file(&file)?;
+        Self::open(&f, &name, &LabelCache::of(&file))
+    }
+
+    /// Reads the dataset `name` of the open file `f`, with the labels of its sets from
+    /// `labels` if another dataset already read them.
+    pub fn open(f: &hdf5::File, name: &str, labels: &LabelCache) -> Result<Self> {
+        let dataset = f.dataset(name)?;
         let name = dataset.name();
         let units = dataset
             .attr("units")?
@@ -49,19 +305,22 @@ impl Data {
             .collect::<Vec<_>>();
         let mut shape = dataset.shape();
         shape.reverse();
+        let fill_value = dataset
+            .attr("_FillValue")
+            .and_then(|a| a.read_scalar::<f64>())
+            .ok()
+            .filter(|v| !v.is_nan());
+        let chunk = dataset.chunk().map(|mut c| {
+            c.reverse();
+            c
+        });
         let mut set_data = vec![];
         let g_name = name
             .split('/')
             .filter(|s| !(s.is_empty()))
             .collect::<Vec<&str>>()[0];
         for dim in set_names.iter() {
-            let ds = f.dataset(format!("{}/{}", g_name, dim).as_str())?;
-            let set = ds
-                .read_1d::<VarLenUnicode>()?
-                .into_iter()
-                .map(|dim| dim.to_string())
-                .collect::<Vec<_>>();
-            set_data.push(set);
+            set_data.push(labels.get(f, &format!("{g_name}/{dim}"))?);
         }
         Ok(Self {
             name,
@@ -71,11 +330,467 @@ impl Data {
             set_names,
             ndims,
             shape,
+            fill_value,
             dataset,
             set_data,
+            expression: None,
+            combination: None,
+            chunk,
+            rollups: vec![],
+        })
+    }
+
+    /// Rolls up the members of the sets named in `mappings` into their groups, e.g. provinces
+    /// into regions: the labels of those sets become those of the groups, and
+    /// [`Data::read_aggregated_slice`] sums the members of each group.
+    pub fn roll_up(&mut self, mappings: &HashMap<String, HashMap<String, String>>) {
+        self.rollups = self
+            .set_names
+            .iter()
+            .zip(self.set_data.iter_mut())
+            .map(|(name, labels)| {
+                let rollup = Rollup::new(labels, mappings.get(name)?)?;
+                labels.clone_from(&rollup.groups);
+                Some(rollup)
+            })
+            .collect();
+    }
+
+    /// Labels of each set, those of the members for the sets that are rolled up.
+    pub fn member_labels(&self) -> Vec<Vec<String>> {
+        self.set_data
+            .iter()
+            .enumerate()
+            .map(|(i, labels)| match self.rollups.get(i) {
+                Some(Some(r)) => r.labels.clone(),
+                _ => labels.clone(),
+            })
+            .collect()
+    }
+
+    /// `range` of dimension `i` widened to whole chunks, so reads do not split chunks that
+    /// HDF5 would then read and decompress more than once.
+    fn chunk_aligned(&self, i: usize, range: &Range<usize>) -> Range<usize> {
+        match self.chunk.as_ref().and_then(|c| c.get(i)) {
+            Some(&size) if size > 1 && !range.is_empty() => {
+                let start = range.start / size * size;
+                let end = (range.end.div_ceil(size) * size).min(self.shape[i]);
+                start..end
+            }
+            _ => range.clone(),
+        }
+    }
+
+    /// Reads the selection `s` of the file's dimensions, or computes it for an expression or a
+    /// combination.
+    ///
+    /// Fill values are read as NaN.
+    pub fn read_selection(
+        &self,
+        s: &SliceInfo<Vec<SliceInfoElem>, IxDyn, IxDyn>,
+    ) -> Result<ArrayD<f64>> {
+        match (&self.expression, &self.combination) {
+            (Some(e), _) => e.evaluate(&|d| d.read_selection(s)),
+            (_, Some(c)) => c.read_selection(s),
+            _ => Ok(self.missing_as_nan(self.dataset.read_slice(s.clone())?)),
+        }
+    }
+
+    /// Writes `values` to the file, each at its index in the order of the sets. Missing values
+    /// are written as the `_FillValue`, if the dataset has one.
+    ///
+    /// Only datasets read as they are stored can be written: not expressions, datasets side by
+    /// side or rolled up sets.
+    pub fn write_values(&self, values: &HashMap<Vec<usize>, f64>) -> Result<()> {
+        if self.expression.is_some() || self.combination.is_some() {
+            return Err(eyre!("{} is computed, not read from the file", self.name));
+        }
+        if self.rollups.iter().any(Option::is_some) {
+            return Err(eyre!("{} has rolled up sets", self.name));
+        }
+        let dataset = open_file_rw(self.dataset.filename())?.dataset(&self.name)?;
+        for (index, &value) in values {
+            // The file stores dimensions in the reverse order of the sets
+            let slices: Vec<SliceInfoElem> = index
+                .iter()
+                .rev()
+                .map(|&i| SliceInfoElem::Slice {
+                    start: i as isize,
+                    end: Some(i as isize + 1),
+                    step: 1,
+                })
+                .collect();
+            let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
+            let value = match self.fill_value {
+                Some(fill) if value.is_nan() => fill,
+                _ => value,
+            };
+            let cell = ArrayD::from_elem(IxDyn(&vec![1; index.len()]), value);
+            dataset.write_slice(cell.view(), s)?;
+        }
+        Ok(())
+    }
+
+    /// Every value, with the members of rolled up sets summed into their groups, in the order
+    /// of the file's dimensions.
+    pub fn read_rolled_up(&self) -> Result<ArrayD<f64>> {
+        let mut values = self.read_all()?;
+        for (i, rollup) in self.rollups.iter().enumerate() {
+            if let Some(r) = rollup {
+                // The file stores dimensions in the reverse order of the sets
+                values = r.sum_all(values.view(), Axis(self.ndims - 1 - i))?;
+            }
+        }
+        Ok(values)
+    }
+
+    /// Writes every value, rolled up, to `f` as the new dataset `name`, e.g.
+    /// `routput/DmdRegions`, with the `dims`, `units`, `doc` and `type` attributes the datasets
+    /// of the file have, so that it reads like any other.
+    ///
+    /// `set_names` are the sets of the new dataset, in order. Those missing from its group are
+    /// written with the labels of this dataset, and those already there must have them.
+    pub fn write_as(
+        &self,
+        f: &hdf5::File,
+        name: &str,
+        set_names: &[String],
+        doc: &str,
+    ) -> Result<()> {
+        let name = name.trim().trim_matches('/');
+        let Some((group, _)) = name.split_once('/') else {
+            return Err(eyre!("Expected a name like group/dataset, not {name}"));
+        };
+        if set_names.len() != self.ndims {
+            return Err(eyre!("{name} needs {} sets", self.ndims));
+        }
+        if f.link_exists(name) {
+            return Err(eyre!("{name} already exists"));
+        }
+        // Everything is read and checked before anything is written, so that a failure leaves
+        // the file as it was
+        let g = f.group(group)?;
+        let mut new_sets = vec![];
+        for (set, labels) in set_names.iter().zip(&self.set_data) {
+            if g.link_exists(set) {
+                let existing: Vec<String> = g
+                    .dataset(set)?
+                    .read_1d::<VarLenUnicode>()?
+                    .iter()
+                    .map(|l| l.to_string())
+                    .collect();
+                if !existing
+                    .iter()
+                    .map(|l| l.trim())
+                    .eq(labels.iter().map(|l| l.trim()))
+                {
+                    return Err(eyre!("{group}/{set} exists with other labels"));
+                }
+            } else {
+                let labels = labels
+                    .iter()
+                    .map(|l| l.parse::<VarLenUnicode>())
+                    .collect::<Result<Array1<_>, _>>()?;
+                new_sets.push((set.as_str(), labels));
+            }
+        }
+        let values = self.read_rolled_up()?;
+        let dims = set_names
+            .iter()
+            .map(|s| s.parse::<VarLenUnicode>())
+            .collect::<Result<Array1<_>, _>>()?;
+        let attributes = [
+            ("units", self.units.as_str()),
+            ("doc", doc),
+            ("type", self.typ.as_str()),
+        ]
+        .into_iter()
+        .map(|(attr, text)| Ok((attr, fixed_text(attr, text)?)))
+        .collect::<Result<Vec<_>>>()?;
+        for (set, labels) in new_sets {
+            g.new_dataset_builder().with_data(&labels).create(set)?;
+        }
+        let dataset = f.new_dataset_builder().with_data(&values).create(name)?;
+        for (attr, text) in attributes {
+            dataset
+                .new_attr_builder()
+                .with_data(&ndarray::arr0(text))
+                .create(attr)?;
+        }
+        dataset.new_attr_builder().with_data(&dims).create("dims")?;
+        Ok(())
+    }
+
+    /// Reads every value, in the order of the file's dimensions.
+    pub fn read_all(&self) -> Result<ArrayD<f64>> {
+        match (&self.expression, &self.combination) {
+            (Some(e), _) => e.evaluate(&|d| d.read_all()),
+            (_, Some(c)) => c.read_all(),
+            _ => Ok(self.missing_as_nan(self.dataset.read_dyn::<f64>()?)),
+        }
+    }
+
+    fn missing_as_nan(&self, mut values: ArrayD<f64>) -> ArrayD<f64> {
+        if let Some(fill) = self.fill_value {
+            values.mapv_inplace(|v| if v == fill { f64::NAN } else { v });
+        }
+        values
+    }
+
+    /// Reads the window `rows` x `cols` of the 2-D slice with dimension `axis0` as columns and
+    /// `axis1` as rows, indexed as `[[col, row]]`.
+    ///
+    /// Every other dimension is fixed at its entry of `index`; entries for the displayed
+    /// dimensions are ignored.
+    pub fn read_slice(
+        &self,
+        axis0: usize,
+        axis1: usize,
+        index: &[usize],
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<Array2<f64>> {
+        let (read_rows, read_cols) = (
+            self.chunk_aligned(axis1, &rows),
+            self.chunk_aligned(axis0, &cols),
+        );
+        let mut slices = Vec::new();
+        // The file stores dimensions in the reverse order of the sets
+        for i in (0..self.ndims).rev() {
+            if i == axis0 || i == axis1 {
+                let range = if i == axis0 { &read_cols } else { &read_rows };
+                slices.push(SliceInfoElem::Slice {
+                    start: range.start as isize,
+                    end: Some(range.end as isize),
+                    step: 1,
+                });
+            } else {
+                slices.push(SliceInfoElem::Index(index[i] as isize));
+            }
+        }
+        log::debug!("{:?} {:?} = {:?}", axis0, axis1, &slices);
+        let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
+        log::debug!("Start reading slice");
+        let data = self.read_selection(&s)?.into_dimensionality::<Ix2>()?;
+        log::debug!("End reading slice");
+        let data = if axis1 > axis0 { data.t() } else { data.view() };
+        Ok(within(data, &read_rows, &read_cols, &rows, &cols))
+    }
+
+    /// Like [`Data::read_slice`], but every dimension that is not displayed and whose entry of
+    /// `aggregations` is not [`Aggregation::Fix`] is summed or averaged over instead of fixed.
+    /// Missing values are left out.
+    ///
+    /// Weighted means use the values of `weights`, which must have the same sets. They are
+    /// taken over every weighted dimension at once, before any sum or mean.
+    ///
+    /// Rows, columns and fixed indices of rolled up sets are groups, whose members are summed.
+    /// Means over a rolled up set are over its members.
+    pub fn read_aggregated_slice(
+        &self,
+        axis0: usize,
+        axis1: usize,
+        index: &[usize],
+        aggregations: &[Aggregation],
+        weights: Option<&Data>,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<Array2<f64>> {
+        let rollup = |i: usize| self.rollups.get(i).and_then(Option::as_ref);
+        if (0..self.ndims).all(|i| rollup(i).is_none()) {
+            return self.read_members(axis0, axis1, index, aggregations, weights, rows, cols);
+        }
+        // Every combination of the members of the groups fixed dimensions are at
+        let mut indices = vec![index.to_vec()];
+        for i in (0..self.ndims).filter(|&i| i != axis0 && i != axis1) {
+            let Some(r) = rollup(i) else {
+                continue;
+            };
+            if aggregations.get(i).copied().unwrap_or_default() != Aggregation::Fix {
+                continue;
+            }
+            indices = indices
+                .iter()
+                .flat_map(|index| {
+                    r.members[index[i]].iter().map(move |&m| {
+                        let mut index = index.clone();
+                        index[i] = m;
+                        index
+                    })
+                })
+                .collect();
+        }
+        let read_rows = rollup(axis1).map_or(rows.clone(), |r| r.span(&rows));
+        let read_cols = rollup(axis0).map_or(cols.clone(), |r| r.span(&cols));
+        let mut data = Array2::from_elem((read_cols.len(), read_rows.len()), f64::NAN);
+        for index in indices {
+            let members = self.read_members(
+                axis0,
+                axis1,
+                &index,
+                aggregations,
+                weights,
+                read_rows.clone(),
+                read_cols.clone(),
+            )?;
+            Zip::from(&mut data).and(&members).for_each(|sum, &v| {
+                *sum = nansum([&*sum, &v]);
+            });
+        }
+        if let Some(r) = rollup(axis0) {
+            data = r.sum(data.view(), Axis(0), &read_cols, cols);
+        }
+        if let Some(r) = rollup(axis1) {
+            data = r.sum(data.view(), Axis(1), &read_rows, rows);
+        }
+        Ok(data)
+    }
+
+    /// [`Data::read_aggregated_slice`] of the members of the sets, whether rolled up or not.
+    fn read_members(
+        &self,
+        axis0: usize,
+        axis1: usize,
+        index: &[usize],
+        aggregations: &[Aggregation],
+        weights: Option<&Data>,
+        rows: Range<usize>,
+        cols: Range<usize>,
+    ) -> Result<Array2<f64>> {
+        let aggregation = |i: usize| match aggregations.get(i) {
+            Some(&a) if i != axis0 && i != axis1 => a,
+            _ => Aggregation::Fix,
+        };
+        if (0..self.ndims).all(|i| aggregation(i) == Aggregation::Fix) {
+            return self.read_slice(axis0, axis1, index, rows, cols);
+        }
+        let mut slices = Vec::new();
+        // Sets of the axes of the array read, which are in the reverse order of the sets
+        let mut read = Vec::new();
+        for i in (0..self.ndims).rev() {
+            let range = if i == axis0 {
+                cols.clone()
+            } else if i == axis1 {
+                rows.clone()
+            } else if aggregation(i) != Aggregation::Fix {
+                0..self.shape[i]
+            } else {
+                slices.push(SliceInfoElem::Index(index[i] as isize));
+                continue;
+            };
+            slices.push(SliceInfoElem::Slice {
+                start: range.start as isize,
+                end: Some(range.end as isize),
+                step: 1,
+            });
+            read.push(i);
+        }
+        let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
+        let mut data = self.read_selection(&s)?;
+        if read
+            .iter()
+            .any(|&i| aggregation(i) == Aggregation::Weighted)
+        {
+            let weights =
+                weights.ok_or_else(|| eyre!("No weights chosen for the weighted mean"))?;
+            if weights.set_data != self.set_data {
+                return Err(eyre!(
+                    "{} does not have the sets of {}",
+                    weights.name,
+                    self.name
+                ));
+            }
+            let mut w = weights.read_selection(&s)?;
+            // Missing values have no weight
+            Zip::from(&mut w).and(&data).for_each(|w, &v| {
+                if v.is_nan() {
+                    *w = f64::NAN;
+                }
+            });
+            let mut products = &data * &w;
+            // Last axis first, so the positions of the others are unchanged
+            for (axis, &i) in read.iter().enumerate().rev() {
+                if aggregation(i) == Aggregation::Weighted {
+                    products = products.map_axis(Axis(axis), nansum);
+                    w = w.map_axis(Axis(axis), nansum);
+                }
+            }
+            data = products / w;
+            read.retain(|&i| aggregation(i) != Aggregation::Weighted);
+        }
+        for (axis, &i) in read.iter().enumerate().rev() {
+            data = match aggregation(i) {
+                Aggregation::Fix => continue,
+                Aggregation::Mean => data.map_axis(Axis(axis), nanmean),
+                _ => data.map_axis(Axis(axis), nansum),
+            };
+        }
+        let data = data.into_dimensionality::<Ix2>()?;
+        Ok(if axis1 > axis0 {
+            data.t().to_owned()
+        } else {
+            data
         })
     }
 
+    /// Writes every value to `out` as CSV, one line per element with the label of each set
+    /// followed by the value, after a header of the set names and `Value`. Missing values are
+    /// left empty. At most [`BLOCK_CELLS`] are read at a time along the first dimension of the
+    /// file.
+    ///
+    /// `progress` is called with the number of cells of each block once written, and returns
+    /// false to stop there. Returns whether every value was written.
+    pub fn stream_csv(
+        &self,
+        out: &mut impl Write,
+        mut progress: impl FnMut(usize) -> bool,
+    ) -> Result<bool> {
+        let header: Vec<String> = self
+            .set_names
+            .iter()
+            .map(|s| csv_field(s))
+            .chain(std::iter::once("Value".to_string()))
+            .collect();
+        writeln!(out, "{}", header.join(","))?;
+        let Some(&outer) = self.shape.last() else {
+            return Ok(true);
+        };
+        let inner: usize = self.shape[..self.ndims - 1].iter().product();
+        let step = (BLOCK_CELLS / inner.max(1)).max(1);
+        for start in (0..outer).step_by(step) {
+            let end = (start + step).min(outer);
+            // The file stores dimensions in the reverse order of the sets
+            let slices: Vec<SliceInfoElem> = std::iter::once(SliceInfoElem::Slice {
+                start: start as isize,
+                end: Some(end as isize),
+                step: 1,
+            })
+            .chain((1..self.ndims).map(|_| SliceInfoElem::from(..)))
+            .collect();
+            let s = SliceInfo::<Vec<SliceInfoElem>, IxDyn, IxDyn>::try_from(slices)?;
+            let values = self.read_selection(&s)?;
+            for (index, value) in values.indexed_iter() {
+                let labels: Vec<String> = (0..self.ndims)
+                    .map(|i| {
+                        let k =
+                            index[self.ndims - 1 - i] + if i == self.ndims - 1 { start } else { 0 };
+                        csv_field(&self.set_data[i][k])
+                    })
+                    .collect();
+                let value = if value.is_nan() {
+                    String::default()
+                } else {
+                    value.to_string()
+                };
+                writeln!(out, "{},{value}", labels.join(","))?;
+            }
+            if !progress(values.len()) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     pub fn selection(&self, range_x: Range<usize>, range_y: Range<usize>) -> Selection {
         let mut points = Vec::new();
 
@@ -104,4 +819,21 @@ mod tests {
         Data::new(file, name)?;
         Ok(())
     }
+
+    #[test]
+    fn test_attribute_prefix() {
+        assert_eq!(attribute_prefix("PJ"), "PJ");
+        let long = "é".repeat(60);
+        assert_eq!(attribute_prefix(&long), "é".repeat(50));
+    }
+
+    #[test]
+    fn test_default_axes() {
+        let sets = ["Area", "Fuel", "year"].map(String::from);
+        let years = vec!["Year".to_string()];
+        assert_eq!(default_axes(&sets, &[13, 20, 30], &years), (2, 1));
+        assert_eq!(default_axes(&sets, &[20, 20, 30], &years), (2, 0));
+        assert_eq!(default_axes(&sets, &[13, 20, 30], &[]), (2, 0));
+        assert_eq!(default_axes(&sets[..1], &[13], &years), (0, 0));
+    }
 }
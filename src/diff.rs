@@ -0,0 +1,192 @@
+use std::{collections::BTreeSet, path::Path};
+
+use color_eyre::eyre::Result;
+use hdf5::{
+    types::{TypeDescriptor, VarLenUnicode},
+    Dataset,
+};
+
+use crate::data;
+
+/// When two values are considered equal: `|a - b| <= atol + rtol * |b|`, as in NumPy's
+/// `isclose`. Two NaN values are equal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub rtol: f64,
+    pub atol: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            rtol: 1e-9,
+            atol: 0.0,
+        }
+    }
+}
+
+impl Tolerance {
+    pub fn close(&self, a: f64, b: f64) -> bool {
+        // Equal infinities differ by NaN, and any value is within a relative tolerance of one
+        if a.is_infinite() || b.is_infinite() {
+            return a == b;
+        }
+        (a.is_nan() && b.is_nan()) || (a - b).abs() <= self.atol + self.rtol * b.abs()
+    }
+}
+
+/// How a dataset differs between the two files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    OnlyInFirst,
+    OnlyInSecond,
+    Shape(Vec<usize>, Vec<usize>),
+    /// Values outside the tolerance, with the largest absolute and relative differences
+    Values {
+        count: usize,
+        max_abs: f64,
+        max_rel: f64,
+    },
+    /// Labels or other text that differ
+    Text(usize),
+    Unreadable(String),
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::OnlyInFirst => write!(f, "only in the first file"),
+            Change::OnlyInSecond => write!(f, "only in the second file"),
+            Change::Shape(a, b) => write!(f, "shape {a:?} → {b:?}"),
+            Change::Values {
+                count,
+                max_abs,
+                max_rel,
+            } => write!(
+                f,
+                "{count} values differ, max abs diff {max_abs:.6e}, max rel diff {max_rel:.3e}"
+            ),
+            Change::Text(count) => write!(f, "{count} labels differ"),
+            Change::Unreadable(e) => write!(f, "unreadable: {e}"),
+        }
+    }
+}
+
+/// Result of comparing two files.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Datasets present in both files
+    pub compared: usize,
+    /// Datasets that differ, in name order
+    pub changes: Vec<(String, Change)>,
+}
+
+impl Report {
+    pub fn to_text(&self) -> String {
+        let mut text: String = self
+            .changes
+            .iter()
+            .map(|(name, change)| format!("{name}: {change}\n"))
+            .collect();
+        text.push_str(&format!(
+            "{} of {} common datasets differ, {} only in one file\n",
+            self.changes
+                .iter()
+                .filter(|(_, c)| !matches!(c, Change::OnlyInFirst | Change::OnlyInSecond))
+                .count(),
+            self.compared,
+            self.changes
+                .iter()
+                .filter(|(_, c)| matches!(c, Change::OnlyInFirst | Change::OnlyInSecond))
+                .count(),
+        ));
+        text
+    }
+}
+
+/// Lists every dataset that differs between `first` and `second`.
+pub fn diff(first: &Path, second: &Path, tolerance: Tolerance) -> Result<Report> {
+    let (a, b) = (data::open_file(first)?, data::open_file(second)?);
+    let names_a: BTreeSet<String> = data::variables(first)?.into_iter().collect();
+    let names_b: BTreeSet<String> = data::variables(second)?.into_iter().collect();
+    let mut report = Report::default();
+    for name in names_a.union(&names_b) {
+        let change = match (names_a.contains(name), names_b.contains(name)) {
+            (true, false) => Some(Change::OnlyInFirst),
+            (false, true) => Some(Change::OnlyInSecond),
+            _ => {
+                report.compared += 1;
+                match (a.dataset(name), b.dataset(name)) {
+                    (Ok(da), Ok(db)) => compare(&da, &db, tolerance)
+                        .unwrap_or_else(|e| Some(Change::Unreadable(e.to_string()))),
+                    (Err(e), _) | (_, Err(e)) => Some(Change::Unreadable(e.to_string())),
+                }
+            }
+        };
+        if let Some(change) = change {
+            report.changes.push((name.clone(), change));
+        }
+    }
+    Ok(report)
+}
+
+fn compare(a: &Dataset, b: &Dataset, tolerance: Tolerance) -> Result<Option<Change>> {
+    if a.shape() != b.shape() {
+        return Ok(Some(Change::Shape(a.shape(), b.shape())));
+    }
+    Ok(match a.dtype()?.to_descriptor()? {
+        TypeDescriptor::Integer(_) | TypeDescriptor::Unsigned(_) | TypeDescriptor::Float(_) => {
+            let (va, vb) = (a.read_dyn::<f64>()?, b.read_dyn::<f64>()?);
+            let (mut count, mut max_abs, mut max_rel) = (0, 0.0_f64, 0.0_f64);
+            for (&x, &y) in va.iter().zip(vb.iter()) {
+                if !tolerance.close(x, y) {
+                    count += 1;
+                    let abs = (x - y).abs();
+                    max_abs = max_abs.max(abs);
+                    if y != 0.0 {
+                        max_rel = max_rel.max(abs / y.abs());
+                    }
+                }
+            }
+            (count > 0).then_some(Change::Values {
+                count,
+                max_abs,
+                max_rel,
+            })
+        }
+        TypeDescriptor::VarLenUnicode => {
+            let (va, vb) = (
+                a.read_dyn::<VarLenUnicode>()?,
+                b.read_dyn::<VarLenUnicode>()?,
+            );
+            let count = va
+                .iter()
+                .zip(vb.iter())
+                .filter(|(x, y)| x.as_str() != y.as_str())
+                .count();
+            (count > 0).then_some(Change::Text(count))
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close() {
+        let t = Tolerance {
+            rtol: 1e-3,
+            atol: 1e-6,
+        };
+        assert!(t.close(1000.0, 1000.5));
+        assert!(!t.close(1000.0, 1002.0));
+        assert!(t.close(0.0, 1e-7));
+        assert!(t.close(f64::NAN, f64::NAN));
+        assert!(!t.close(f64::NAN, 0.0));
+        assert!(t.close(f64::INFINITY, f64::INFINITY));
+        assert!(!t.close(f64::INFINITY, f64::NEG_INFINITY));
+        assert!(!t.close(1.0, f64::INFINITY));
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use color_eyre::eyre::Result;
+use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle};
+
+use crate::{action::Action, data::Data, tasks::Task};
+
+/// File `d` is exported to in `dir`, named after its path, e.g. `routput_Dmd.csv`.
+pub fn csv_path(dir: &Path, d: &Data) -> PathBuf {
+    dir.join(format!(
+        "{}.csv",
+        d.name.trim_matches('/').replace('/', "_")
+    ))
+}
+
+/// Writes `d` to `path` as CSV, counting the cells written in `task`. Stops and removes the
+/// file if `task` is cancelled; returns whether it was written in full.
+pub fn write_csv(d: &Data, path: &Path, task: &Task) -> Result<bool> {
+    let mut out = BufWriter::new(File::create(path)?);
+    let complete = d.stream_csv(&mut out, |cells| {
+        task.advance_by(cells);
+        !task.is_cancelled()
+    })?;
+    out.flush()?;
+    if !complete {
+        drop(out);
+        std::fs::remove_file(path)?;
+    }
+    Ok(complete)
+}
+
+/// Exports each of `datasets` to its own CSV file in `dir`, one after the other in the
+/// background, so that large datasets neither freeze the interface nor fill the memory.
+///
+/// The total of `task` is the number of cells of every dataset, and it counts those written.
+/// Each dataset that cannot be exported is reported as an [`Action::Error`] and the next one is
+/// written. Once every dataset is written, or `task` is cancelled, a summary is sent as
+/// [`Action::ExportedMarked`].
+pub fn spawn(
+    datasets: Vec<Data>,
+    dir: PathBuf,
+    task: Task,
+    action_tx: Option<UnboundedSender<Action>>,
+) -> JoinHandle<()> {
+    task.set_total(
+        datasets
+            .iter()
+            .map(|d| d.shape.iter().product::<usize>())
+            .sum(),
+    );
+    let task = Arc::new(task);
+    tokio::spawn(async move {
+        let send = |action| {
+            if let Some(ref tx) = action_tx {
+                tx.send(action).unwrap_or_default();
+            }
+        };
+        let mut exported = 0;
+        for d in datasets {
+            if task.is_cancelled() {
+                break;
+            }
+            let (name, path, t) = (d.name.clone(), csv_path(&dir, &d), task.clone());
+            match tokio::task::spawn_blocking(move || write_csv(&d, &path, &t)).await {
+                Ok(Ok(true)) => exported += 1,
+                Ok(Ok(false)) => break,
+                Ok(Err(e)) => send(Action::Error(format!("Unable to export {name}: {e}"))),
+                Err(e) => send(Action::Error(format!("Unable to export {name}: {e}"))),
+            }
+        }
+        let message = if task.is_cancelled() {
+            format!(
+                "Cancelled the export after {exported} datasets, in {}",
+                dir.display()
+            )
+        } else {
+            format!("Exported {exported} datasets to {}", dir.display())
+        };
+        log::info!("{message}");
+        drop(task);
+        send(Action::ExportedMarked(message));
+    })
+}
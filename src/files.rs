@@ -0,0 +1,155 @@
+use std::path::{Component, Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result};
+
+/// Whether `name` matches `pattern`, where `*` stands for any characters and `?` for one.
+pub fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) =
+        (pattern.chars().collect(), name.chars().collect());
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was, and the character of `name` it is matched up to
+    let mut star = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((sp, sn)) => {
+                    star = Some((sp, sn + 1));
+                    (p, n) = (sp + 1, sn + 1);
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn has_wildcards(s: &str) -> bool {
+    s.contains(['*', '?'])
+}
+
+/// Paths matching `pattern`, e.g. `results/*.hdf5` or `runs/*/database.hdf5`, sorted. Hidden
+/// files only match a pattern starting with `.`.
+pub fn expand_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![PathBuf::new()];
+    for component in Path::new(pattern).components() {
+        let part = component.as_os_str().to_string_lossy();
+        if !matches!(component, Component::Normal(_)) || !has_wildcards(&part) {
+            paths.iter_mut().for_each(|p| p.push(component));
+            continue;
+        }
+        let mut matches = vec![];
+        for dir in paths.iter() {
+            let read = if dir.as_os_str().is_empty() {
+                std::fs::read_dir(".")
+            } else {
+                std::fs::read_dir(dir)
+            };
+            let Ok(entries) = read else {
+                continue;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if (part.starts_with('.') || !name.starts_with('.')) && wildcard_match(&part, &name)
+                {
+                    matches.push(dir.join(name));
+                }
+            }
+        }
+        paths = matches;
+    }
+    paths.retain(|p| p.exists());
+    paths.sort();
+    Ok(paths)
+}
+
+/// File in the temporary directory that what is piped on stdin is copied to, removed on exit.
+pub fn stdin_path() -> PathBuf {
+    std::env::temp_dir().join(format!("e2020-stdin-{}.hdf5", std::process::id()))
+}
+
+/// Removes the file buffered from stdin once dropped.
+pub struct StdinGuard;
+
+impl Drop for StdinGuard {
+    fn drop(&mut self) {
+        std::fs::remove_file(stdin_path()).unwrap_or_default();
+    }
+}
+
+/// Copies what is piped on stdin to [`stdin_path`], so an HDF5 file produced by another
+/// command can be opened.
+pub fn buffer_stdin() -> Result<PathBuf> {
+    let path = stdin_path();
+    let buffered = std::fs::File::create(&path)
+        .and_then(|mut file| std::io::copy(&mut std::io::stdin().lock(), &mut file))
+        .map_err(|e| eyre!("Unable to buffer stdin to {}: {e}", path.display()))?;
+    if buffered == 0 {
+        return Err(eyre!("Nothing was piped on stdin for --file -"));
+    }
+    Ok(path)
+}
+
+/// The files to open from those given with `--file`: `-` is stdin, buffered to a temporary
+/// file, and patterns are expanded, each to at least one file.
+pub fn resolve(args: &[PathBuf]) -> Result<Vec<String>> {
+    let mut files = vec![];
+    for arg in args {
+        let text = arg.to_string_lossy().to_string();
+        if text == "-" {
+            files.push(buffer_stdin()?.to_string_lossy().to_string());
+        } else if has_wildcards(&text) && !arg.exists() {
+            let matches = expand_glob(&text)?;
+            if matches.is_empty() {
+                return Err(eyre!("No file matches {text}"));
+            }
+            files.extend(matches.iter().map(|p| p.to_string_lossy().to_string()));
+        } else {
+            files.push(text);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.hdf5", "database.hdf5"));
+        assert!(wildcard_match("run?.h5", "run1.h5"));
+        assert!(wildcard_match("*a*b", "xaybab"));
+        assert!(!wildcard_match("*.hdf5", "database.hdf5.bak"));
+        assert!(!wildcard_match("run?.h5", "run10.h5"));
+        assert!(wildcard_match("*", ""));
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let dir = std::env::temp_dir().join(format!("e2020-glob-{}", std::process::id()));
+        for name in [
+            "b/run2.hdf5",
+            "a/run1.hdf5",
+            "a/notes.txt",
+            "a/.hidden.hdf5",
+        ] {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let pattern = format!("{}/*/*.hdf5", dir.display());
+        assert_eq!(
+            expand_glob(&pattern).unwrap(),
+            vec![dir.join("a/run1.hdf5"), dir.join("b/run2.hdf5")]
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
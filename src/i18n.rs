@@ -0,0 +1,612 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use lazy_static::lazy_static;
+use serde_derive::{Deserialize, Serialize};
+
+/// Language of the interface.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "fr")]
+    French,
+}
+
+impl Language {
+    /// Language of the locale, from `LC_ALL`, `LC_MESSAGES` or `LANG` as in gettext, e.g.
+    /// French for `fr_CA.UTF-8`. Any other locale is English.
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|v| std::env::var(v).ok())
+            .find(|v| !v.is_empty())
+            .unwrap_or_default();
+        if locale.starts_with("fr") {
+            Language::French
+        } else {
+            Language::English
+        }
+    }
+}
+
+static FRENCH: AtomicBool = AtomicBool::new(false);
+
+/// Shows the interface in `language` from now on.
+pub fn set_language(language: Language) {
+    FRENCH.store(language == Language::French, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    if FRENCH.load(Ordering::Relaxed) {
+        Language::French
+    } else {
+        Language::English
+    }
+}
+
+/// `text` in the language of the interface.
+///
+/// Strings are written in English in the code, which makes the English catalogue; the French
+/// one maps them to their translation. Text it does not have stays in English.
+pub fn tr(text: &'static str) -> &'static str {
+    match language() {
+        Language::English => text,
+        Language::French => FRENCH_CATALOGUE.get(text).copied().unwrap_or(text),
+    }
+}
+
+lazy_static! {
+    static ref FRENCH_CATALOGUE: HashMap<&'static str, &'static str> =
+        FRENCH_TEXT.iter().copied().collect();
+}
+
+/// English text of the interface and its French translation.
+const FRENCH_TEXT: &[(&str, &str)] = &[
+    // Key bindings
+    ("Apply", "Appliquer"),
+    ("Apply and close", "Appliquer et fermer"),
+    ("Back to the previous view", "Revenir à la vue précédente"),
+    ("Back to the table", "Revenir au tableau"),
+    ("Bookmark the slice", "Ajouter la tranche aux signets"),
+    ("Cancel", "Annuler"),
+    ("Cancel scan", "Annuler le balayage"),
+    (
+        "Chart the selected rows as SVG",
+        "Tracer les lignes sélectionnées en SVG",
+    ),
+    ("Choose Current Selection", "Choisir la sélection"),
+    ("Choose a file", "Choisir un fichier"),
+    ("Choose another file", "Choisir un autre fichier"),
+    ("Choose bookmark", "Choisir le signet"),
+    ("Choose file", "Choisir le fichier"),
+    ("Choose match", "Choisir le résultat"),
+    ("Choose task", "Choisir la tâche"),
+    ("Show the log", "Afficher le journal des messages"),
+    (
+        "Move the totals to the first or last column, bottom or top row",
+        "Placer les totaux en première ou dernière colonne, en bas ou en haut",
+    ),
+    (
+        "Show more or fewer levels",
+        "Afficher plus ou moins de niveaux",
+    ),
+    (
+        "Save the log to a file",
+        "Enregistrer les messages dans un fichier",
+    ),
+    ("Cancel task", "Annuler la tâche"),
+    ("Show background tasks", "Afficher les tâches de fond"),
+    ("Choose dimension", "Choisir la dimension"),
+    (
+        "Choose row and column dimensions",
+        "Choisir les dimensions des lignes et des colonnes",
+    ),
+    (
+        "Choose the weights of weighted means",
+        "Choisir les poids des moyennes pondérées",
+    ),
+    ("Choose weights", "Choisir les poids"),
+    ("Close", "Fermer"),
+    ("Close Help", "Fermer l'aide"),
+    ("Close Viewer", "Fermer la visionneuse"),
+    (
+        "Compare with another file",
+        "Comparer avec un autre fichier",
+    ),
+    (
+        "Compare with the dataset in another file",
+        "Comparer avec le jeu de données d'un autre fichier",
+    ),
+    ("Confirm", "Confirmer"),
+    ("Copy selected cells", "Copier les cellules sélectionnées"),
+    ("Cycle 1st Axis", "Parcourir le 1er axe"),
+    ("Cycle 2nd Axis", "Parcourir le 2e axe"),
+    ("Cycle 1st dimension", "Parcourir la 1re dimension"),
+    ("Cycle 2nd dimension", "Parcourir la 2e dimension"),
+    ("Cycle 3rd dimension", "Parcourir la 3e dimension"),
+    ("Cycle 4th dimension", "Parcourir la 4e dimension"),
+    ("Cycle 5th dimension", "Parcourir la 5e dimension"),
+    ("Cycle 6th dimension", "Parcourir la 6e dimension"),
+    ("Cycle 7th dimension", "Parcourir la 7e dimension"),
+    ("Cycle 8th dimension", "Parcourir la 8e dimension"),
+    ("Cycle 9th dimension", "Parcourir la 9e dimension"),
+    ("Cycle unit scaling", "Changer d'unités"),
+    ("Delete", "Supprimer"),
+    (
+        "Double the refresh rate",
+        "Doubler la fréquence de rafraîchissement",
+    ),
+    (
+        "Drill into overview bin / collapse group",
+        "Détailler la classe de l'aperçu / replier le groupe",
+    ),
+    ("Edit", "Modifier"),
+    (
+        "Edit the selected cell, with --writable",
+        "Modifier la cellule sélectionnée, avec --writable",
+    ),
+    (
+        "Edit the units / documentation",
+        "Modifier les unités / la documentation",
+    ),
+    (
+        "Edit the units and doc of the dataset",
+        "Modifier les unités et la documentation du jeu de données",
+    ),
+    ("Enter Fuzzy Find Mode", "Lancer la recherche floue"),
+    ("Exit Fuzzy Find Mode", "Quitter la recherche floue"),
+    ("Export", "Exporter"),
+    (
+        "Export frames along a dimension",
+        "Exporter des images le long d'une dimension",
+    ),
+    (
+        "Export marked datasets to CSV",
+        "Exporter les jeux de données marqués en CSV",
+    ),
+    (
+        "Export selected cells as CSV",
+        "Exporter les cellules sélectionnées en CSV",
+    ),
+    (
+        "Export slice as JSON records",
+        "Exporter la tranche en enregistrements JSON",
+    ),
+    (
+        "Export slice as Markdown",
+        "Exporter la tranche en Markdown",
+    ),
+    (
+        "Export slice as text, one cell per line",
+        "Exporter la tranche en texte, une cellule par ligne",
+    ),
+    ("Export view definition", "Exporter la définition de la vue"),
+    ("Filter datasets", "Filtrer les jeux de données"),
+    (
+        "Fix / sum / average over dimension",
+        "Fixer / sommer / moyenner la dimension",
+    ),
+    ("Forward to the next view", "Avancer à la vue suivante"),
+    ("Go to bottom", "Aller à la fin"),
+    ("Go to the parent directory", "Aller au répertoire parent"),
+    ("Go to first columns", "Aller aux premières colonnes"),
+    ("Go to last columns", "Aller aux dernières colonnes"),
+    ("Go to top", "Aller au début"),
+    (
+        "Group small rows into Other",
+        "Regrouper les petites lignes dans Autre",
+    ),
+    (
+        "Halve the refresh rate",
+        "Diviser par deux la fréquence de rafraîchissement",
+    ),
+    ("Jump", "Aller"),
+    ("Jump to a bookmark", "Aller à un signet"),
+    ("Keep the value", "Garder la valeur"),
+    ("Mark/unmark dataset", "Marquer/démarquer le jeu de données"),
+    ("More / fewer bins", "Plus / moins de classes"),
+    ("Move across columns", "Passer d'une colonne à l'autre"),
+    ("Move down", "Descendre"),
+    (
+        "Next / previous dataset in the group",
+        "Jeu de données suivant / précédent du groupe",
+    ),
+    ("Move left", "Aller à gauche"),
+    ("Move right", "Aller à droite"),
+    ("Move up", "Monter"),
+    ("Open Help", "Ouvrir l'aide"),
+    ("Open in the Viewer", "Ouvrir dans la visionneuse"),
+    (
+        "Open a computed dataset",
+        "Ouvrir un jeu de données calculé",
+    ),
+    ("Open a read-only copy", "Ouvrir une copie en lecture seule"),
+    ("Open the next file", "Ouvrir le fichier suivant"),
+    ("Open the previous file", "Ouvrir le fichier précédent"),
+    (
+        "Open the directory or file",
+        "Ouvrir le répertoire ou le fichier",
+    ),
+    (
+        "Open marked datasets side by side",
+        "Ouvrir les jeux de données marqués côte à côte",
+    ),
+    ("Page down", "Page suivante"),
+    ("Page left", "Page à gauche"),
+    ("Page right", "Page à droite"),
+    ("Page up", "Page précédente"),
+    (
+        "Pin/unpin dimension as Δ reference",
+        "Épingler/désépingler la dimension comme référence du Δ",
+    ),
+    (
+        "Pin/unpin row above the others",
+        "Épingler/désépingler la ligne au-dessus des autres",
+    ),
+    ("Quit", "Quitter"),
+    ("Reload Data", "Recharger les données"),
+    ("Retry", "Réessayer"),
+    (
+        "Run the configured hook on the slice",
+        "Lancer la commande configurée sur la tranche",
+    ),
+    (
+        "Run the configured plugin on the marked datasets",
+        "Lancer le greffon configuré sur les jeux de données marqués",
+    ),
+    (
+        "Save edited cells to the file",
+        "Enregistrer les cellules modifiées dans le fichier",
+    ),
+    (
+        "Save the dataset into the file as a new one",
+        "Enregistrer le jeu de données dans le fichier sous un nouveau nom",
+    ),
+    ("Save the screen as text", "Enregistrer l'écran en texte"),
+    (
+        "Save the screen with ANSI colours",
+        "Enregistrer l'écran avec les couleurs ANSI",
+    ),
+    ("Save to the file", "Enregistrer dans le fichier"),
+    (
+        "Scale, set or zero out the selected cells",
+        "Multiplier, fixer ou mettre à zéro les cellules sélectionnées",
+    ),
+    ("Scroll", "Défiler"),
+    (
+        "Search every dataset for a label or values",
+        "Chercher un libellé ou des valeurs dans tous les jeux de données",
+    ),
+    ("Select mode", "Mode sélection"),
+    (
+        "Show the last search results",
+        "Afficher les derniers résultats de recherche",
+    ),
+    (
+        "Show datasets that could not be read",
+        "Afficher les jeux de données illisibles",
+    ),
+    (
+        "Report the zero and missing cells of the dataset",
+        "Rapport des cellules nulles et manquantes du jeu de données",
+    ),
+    (
+        "Show more / fewer decimals",
+        "Afficher plus / moins de décimales",
+    ),
+    (
+        "Show only the top N rows",
+        "N'afficher que les N premières lignes",
+    ),
+    (
+        "Limit exports and statistics to a range of columns",
+        "Limiter les exports et statistiques à une plage de colonnes",
+    ),
+    (
+        "Go to a column by its label",
+        "Aller à une colonne par son libellé",
+    ),
+    (
+        "Go to a row by its label",
+        "Aller à une ligne par son libellé",
+    ),
+    (
+        "Show the summary in full, on one line or not at all",
+        "Afficher le résumé en entier, sur une ligne ou pas du tout",
+    ),
+    (
+        "Show how long opening, reading and drawing took",
+        "Afficher la durée de l'ouverture, de la lecture et de l'affichage",
+    ),
+    (
+        "Scroll the dimensions of the summary",
+        "Faire défiler les dimensions du résumé",
+    ),
+    (
+        "Step the dimension last stepped through",
+        "Parcourir la dernière dimension parcourue",
+    ),
+    (
+        "Show statistics of the selected column",
+        "Afficher les statistiques de la colonne sélectionnée",
+    ),
+    (
+        "Show the distribution of the values",
+        "Afficher la distribution des valeurs",
+    ),
+    (
+        "Show the next / previous group",
+        "Afficher le groupe suivant / précédent",
+    ),
+    (
+        "Show the whole slice as an image",
+        "Afficher toute la tranche en image",
+    ),
+    (
+        "Start/stop selecting a block of cells",
+        "Commencer/arrêter la sélection d'un bloc de cellules",
+    ),
+    ("Swap rows and columns", "Permuter lignes et colonnes"),
+    (
+        "Switch between Δ and % change",
+        "Basculer entre Δ et variation en %",
+    ),
+    (
+        "Switch between Δ and % change vs reference",
+        "Basculer entre Δ et variation en % par rapport à la référence",
+    ),
+    (
+        "Switch rows / columns / groups",
+        "Passer aux lignes / colonnes / groupes",
+    ),
+    ("Sort by the next column", "Trier selon la colonne suivante"),
+    ("Reverse the sort order", "Inverser l'ordre de tri"),
+    ("Switch set", "Changer d'ensemble"),
+    ("Toggle all values", "Inverser toutes les valeurs"),
+    (
+        "Toggle cumulative sum",
+        "Activer/désactiver la somme cumulée",
+    ),
+    (
+        "Toggle cumulative sum across columns",
+        "Activer/désactiver la somme cumulée sur les colonnes",
+    ),
+    (
+        "Choose how values are formatted",
+        "Choisir la mise en forme des valeurs",
+    ),
+    ("Choose an option", "Choisir une option"),
+    ("Switch on / off", "Activer / désactiver"),
+    ("Formatting", "Mise en forme"),
+    ("Zeros as dashes", "Zéros en tirets"),
+    (
+        "Whole numbers without decimals",
+        "Nombres entiers sans décimales",
+    ),
+    (
+        "Missing values left blank",
+        "Valeurs manquantes laissées vides",
+    ),
+    (
+        "Toggle long labels and set codes",
+        "Basculer entre libellés longs et codes des ensembles",
+    ),
+    (
+        "Toggle rows sorted by total",
+        "Trier ou non les lignes par total",
+    ),
+    (
+        "Toggle rows sorted by total / file order",
+        "Trier les lignes par total / dans l'ordre du fichier",
+    ),
+    (
+        "Toggle the roll-up of sets",
+        "Activer/désactiver le regroupement des ensembles",
+    ),
+    ("Toggle value", "Inverser la valeur"),
+    ("Type the new value", "Saisir la nouvelle valeur"),
+    ("Unmark all datasets", "Démarquer tous les jeux de données"),
+    ("Use", "Utiliser"),
+    // Titles
+    ("Help - Key Bindings", "Aide - Raccourcis clavier"),
+    ("Press ESC to close.", "Appuyez sur ESC pour fermer."),
+    ("Key", "Touche"),
+    ("Action", "Action"),
+    ("Picker", "Sélecteur"),
+    ("Dataset", "Jeu de données"),
+    ("Bookmarks", "Signets"),
+    ("Weights", "Poids"),
+    ("Pivot", "Pivot"),
+    ("Rows", "Lignes"),
+    ("Columns", "Colonnes"),
+    ("Rows grouped by", "Lignes groupées par"),
+    ("Export frames along", "Exporter des images le long de"),
+    ("Are you sure?", "Êtes-vous sûr ?"),
+    ("Unable to open file", "Impossible d'ouvrir le fichier"),
+    (
+        "Open file (Enter to open, ESC to cancel)",
+        "Ouvrir un fichier (Entrée pour ouvrir, ESC pour annuler)",
+    ),
+    ("Attributes of", "Attributs de"),
+    (
+        "Enter to save, ESC to cancel",
+        "Entrée pour enregistrer, ESC pour annuler",
+    ),
+    ("Units", "Unités"),
+    ("Documentation", "Documentation"),
+    ("Fuzzy Find (Press ", "Recherche floue (appuyez sur "),
+    (" to start, ", " pour commencer, "),
+    (" to finish)", " pour terminer)"),
+    ("Press ", "Appuyez sur "),
+    (" to switch axis, ", " pour changer d'axe, "),
+    (
+        " to move across columns, ",
+        " pour passer d'une colonne à l'autre, ",
+    ),
+    (" to toggle values, ", " pour inverser les valeurs, "),
+    (" to close.", " pour fermer."),
+    // Prompts
+    (
+        "Enter to confirm, ESC to cancel",
+        "Entrée pour confirmer, ESC pour annuler",
+    ),
+    ("Compare with file", "Comparer avec le fichier"),
+    (
+        "Show the dataset side by side with file, empty to stop",
+        "Afficher le jeu de données à côté de celui du fichier, vide pour arrêter",
+    ),
+    (
+        "Change the selected cells: *factor, =value or 0",
+        "Modifier les cellules sélectionnées : *facteur, =valeur ou 0",
+    ),
+    (
+        "Change every cell of the slice: *factor, =value or 0",
+        "Modifier toutes les cellules de la tranche : *facteur, =valeur ou 0",
+    ),
+    (
+        "Save as the new dataset",
+        "Enregistrer sous le nouveau jeu de données",
+    ),
+    (
+        "Number of rows to show, 0 for all",
+        "Nombre de lignes à afficher, 0 pour toutes",
+    ),
+    (
+        "Columns to export, e.g. 2025-2035, empty for all",
+        "Colonnes à exporter, p. ex. 2025-2035, vide pour toutes",
+    ),
+    ("Go to column", "Aller à la colonne"),
+    ("Enter to open", "Entrée pour ouvrir"),
+    (
+        "Select a dataset by its number, then Enter to open it",
+        "Choisir un jeu de données par son numéro, puis Entrée pour l'ouvrir",
+    ),
+    (
+        "This dataset has no sets to select from.",
+        "Ce jeu de données n'a aucun ensemble où choisir.",
+    ),
+    (
+        "This set is empty: the dataset has no values along it.",
+        "Cet ensemble est vide : le jeu de données n'a aucune valeur le long de celui-ci.",
+    ),
+    ("Go to row", "Aller à la ligne"),
+    ("Tab to complete", "Tab pour compléter"),
+    ("no match", "aucune correspondance"),
+    (
+        "Expression, e.g. ooutput/Emissions / ooutput/Demand",
+        "Expression, p. ex. ooutput/Emissions / ooutput/Demand",
+    ),
+    ("Bookmark name", "Nom du signet"),
+    (
+        "Search for a label, >value or <value, in the marked datasets if any",
+        "Chercher un libellé, >valeur ou <valeur, dans les jeux de données marqués s'il y en a",
+    ),
+    ("Search for", "Recherche de"),
+    (
+        "Searching every dataset…",
+        "Recherche dans tous les jeux de données…",
+    ),
+    (
+        "No directory or HDF5 file (.hdf5 or .h5) here.",
+        "Aucun répertoire ni fichier HDF5 (.hdf5 ou .h5) ici.",
+    ),
+    ("No match.", "Aucun résultat."),
+    ("Background tasks", "Tâches de fond"),
+    ("Log", "Messages"),
+    ("and above", "et plus graves"),
+    ("(no log lines)", "(aucun message)"),
+    ("Scanning", "Lecture"),
+    ("Exporting", "Exportation"),
+    ("Searching", "Recherche"),
+    ("Comparing files", "Comparaison des fichiers"),
+    ("Running hook", "Exécution du crochet"),
+    ("Running plugin", "Exécution du greffon"),
+    ("Measuring sparsity", "Mesure des cellules vides"),
+    ("ESC to cancel", "ESC pour annuler"),
+    ("cancelling…", "annulation…"),
+    ("No task running.", "Aucune tâche en cours."),
+    (
+        "Press c to cancel the selected task, ESC to close.",
+        "c pour annuler la tâche choisie, ESC pour fermer.",
+    ),
+    (
+        "matches, press Enter to open one in the Viewer, ESC to close.",
+        "résultats, Entrée pour en ouvrir un dans la visionneuse, ESC pour fermer.",
+    ),
+    ("datasets", "jeux de données"),
+    ("marked", "marqués"),
+    ("Timings", "Durées"),
+    ("open", "ouvrir"),
+    ("read", "lire"),
+    ("format", "formater"),
+    ("draw", "afficher"),
+    ("on disk", "sur le disque"),
+    (
+        "e export, o side by side, Space f search, Space P plugin, V unmark all",
+        "e exporter, o côte à côte, Espace f rechercher, Espace P plugin, V tout démarquer",
+    ),
+    ("file", "fichier"),
+    (
+        "Start with --writable to edit values",
+        "Lancez avec --writable pour modifier les valeurs",
+    ),
+    (
+        "Only the file opened with --writable can be edited",
+        "Seul le fichier ouvert avec --writable peut être modifié",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keymap::{self, Context};
+
+    #[test]
+    fn test_french_catalogue() {
+        assert_eq!(FRENCH_CATALOGUE.len(), FRENCH_TEXT.len());
+        let contexts = [
+            Context::Picker,
+            Context::PickerEditing,
+            Context::Viewer,
+            Context::ViewerEditing,
+            Context::ViewerSelection,
+            Context::ViewerPivot,
+            Context::ViewerFrames,
+            Context::ViewerBookmarks,
+            Context::ViewerWeights,
+            Context::ViewerFormatting,
+            Context::ViewerHeatmap,
+            Context::ViewerHistogram,
+            Context::FileError,
+            Context::Confirm,
+            Context::Prompt,
+            Context::Attributes,
+            Context::Search,
+            Context::Tasks,
+            Context::Log,
+            Context::Output,
+            Context::Help,
+            Context::Browser,
+        ];
+        for context in contexts {
+            let descriptions = keymap::bindings(context)
+                .into_iter()
+                .map(|b| b.description)
+                .chain(
+                    keymap::leader_bindings(context)
+                        .into_iter()
+                        .map(|b| b.description),
+                );
+            for description in descriptions {
+                assert!(
+                    FRENCH_CATALOGUE.contains_key(description),
+                    "no French for {description:?}"
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Local};
+use color_eyre::eyre::Result;
+
+/// A dataset view visited during the session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub time: DateTime<Local>,
+    pub dataset: String,
+    pub view: String,
+}
+
+/// Log of every dataset and slice visited, exported as Markdown when the session ends.
+#[derive(Debug, Clone)]
+pub struct Journal {
+    pub enabled: bool,
+    pub file: String,
+    pub started: DateTime<Local>,
+    pub entries: Vec<Entry>,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            file: String::default(),
+            started: Local::now(),
+            entries: Vec::default(),
+        }
+    }
+}
+
+/// Formats a duration as `HH:MM:SS`.
+pub fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.num_seconds().max(0);
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+impl Journal {
+    pub fn new(file: &str) -> Self {
+        Self {
+            enabled: true,
+            file: file.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Time since the session started, as `HH:MM:SS`.
+    pub fn elapsed(&self) -> String {
+        format_elapsed(Local::now() - self.started)
+    }
+
+    /// Records a visit, unless it is the view already on display.
+    pub fn record(&mut self, dataset: &str, view: String) {
+        if !self.enabled {
+            return;
+        }
+        if self
+            .entries
+            .last()
+            .is_some_and(|e| e.dataset == dataset && e.view == view)
+        {
+            return;
+        }
+        self.entries.push(Entry {
+            time: Local::now(),
+            dataset: dataset.into(),
+            view,
+        });
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let ended = Local::now();
+        let mut md = format!(
+            "# Analysis journal\n\n\
+             - File: `{}`\n\
+             - Started: {}\n\
+             - Ended: {}\n\
+             - Duration: {}\n\n\
+             | Time | Elapsed | Dataset | View |\n\
+             |------|---------|---------|------|\n",
+            self.file,
+            self.started.format("%Y-%m-%d %H:%M:%S"),
+            ended.format("%Y-%m-%d %H:%M:%S"),
+            format_elapsed(ended - self.started),
+        );
+        for e in self.entries.iter() {
+            md.push_str(&format!(
+                "| {} | {} | `{}` | {} |\n",
+                e.time.format("%H:%M:%S"),
+                format_elapsed(e.time - self.started),
+                e.dataset,
+                e.view.replace('|', "\\|"),
+            ));
+        }
+        md
+    }
+
+    /// Writes the journal to the current directory, if anything was visited.
+    pub fn save(&self) -> Result<Option<PathBuf>> {
+        if !self.enabled || self.entries.is_empty() {
+            return Ok(None);
+        }
+        let path = PathBuf::from(format!(
+            "journal-{}.md",
+            self.started.format("%Y%m%d-%H%M%S")
+        ));
+        std::fs::write(&path, self.to_markdown())?;
+        Ok(Some(path))
+    }
+}
@@ -0,0 +1,828 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{action::Action, components::viewer::SliceFormat};
+
+/// The set of key bindings that are active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Picker,
+    PickerEditing,
+    Viewer,
+    ViewerEditing,
+    ViewerSelection,
+    ViewerPivot,
+    ViewerFrames,
+    ViewerBookmarks,
+    ViewerWeights,
+    ViewerFormatting,
+    ViewerHeatmap,
+    ViewerHistogram,
+    FileError,
+    Confirm,
+    Prompt,
+    Attributes,
+    Search,
+    Tasks,
+    Log,
+    Output,
+    Help,
+    Browser,
+}
+
+/// A documented key binding: one or more keys, each triggering its own action.
+///
+/// Components look their keys up here, so the Help and the footer hints always match what the
+/// keys do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub triggers: Vec<(KeyModifiers, KeyCode, Action)>,
+    pub description: &'static str,
+    /// Whether the binding is shown in the footer hint bar
+    pub hint: bool,
+    /// Shown instead of the keys, for bindings handled outside of the registry
+    pub label: Option<&'static str>,
+}
+
+fn bind(description: &'static str) -> Binding {
+    Binding {
+        triggers: vec![],
+        description,
+        hint: false,
+        label: None,
+    }
+}
+
+impl Binding {
+    fn on(self, code: KeyCode, action: Action) -> Self {
+        self.on_mod(KeyModifiers::NONE, code, action)
+    }
+
+    fn on_mod(mut self, modifiers: KeyModifiers, code: KeyCode, action: Action) -> Self {
+        self.triggers.push((modifiers, code, action));
+        self
+    }
+
+    fn hint(mut self) -> Self {
+        self.hint = true;
+        self
+    }
+
+    fn label(mut self, label: &'static str) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// The keys of the binding, as shown in the Help and the footer.
+    pub fn keys(&self) -> String {
+        if let Some(label) = self.label {
+            return label.into();
+        }
+        let keys: Vec<String> = self
+            .triggers
+            .iter()
+            .map(|(modifiers, code, _)| key_label(*modifiers, *code))
+            .collect();
+        match keys.as_slice() {
+            [first, .., last] if keys.len() > 3 => format!("{first} … {last}"),
+            _ => keys.join(" / "),
+        }
+    }
+
+    /// Action triggered by `key`, if it is one of the binding's keys.
+    pub fn action(&self, key: KeyEvent) -> Option<Action> {
+        // Shift is part of the character itself, e.g. `G` or `%`
+        let modifiers = match key.code {
+            KeyCode::Char(_) => key.modifiers.difference(KeyModifiers::SHIFT),
+            _ => key.modifiers,
+        };
+        self.triggers
+            .iter()
+            .find(|(m, c, _)| *m == modifiers && *c == key.code)
+            .map(|(_, _, a)| a.clone())
+    }
+}
+
+fn key_label(modifiers: KeyModifiers, code: KeyCode) -> String {
+    let key = match code {
+        KeyCode::Char(' ') => "Space".into(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Down => "↓".into(),
+        KeyCode::Up => "↑".into(),
+        KeyCode::Left => "←".into(),
+        KeyCode::Right => "→".into(),
+        KeyCode::Esc => "ESC".into(),
+        KeyCode::BackTab => "Shift+Tab".into(),
+        KeyCode::F(n) => format!("F{n}"),
+        code => format!("{code:?}"),
+    };
+    let mut label = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        label.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        label.push_str("Alt+");
+    }
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        label.push_str("Shift+");
+    }
+    label + &key
+}
+
+/// Number of dimensions with their own keys.
+const DIMENSIONS: usize = 9;
+
+/// All documented bindings of `context`, in the order they appear in the Help.
+pub fn bindings(context: Context) -> Vec<Binding> {
+    use Action::*;
+    use KeyCode::*;
+    match context {
+        Context::Picker => vec![
+            bind("Move down")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext),
+            bind("Move up")
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious),
+            bind("Show the next / previous group")
+                .on(Tab, MoveSelectionRight)
+                .on(BackTab, MoveSelectionLeft)
+                .on(Char('l'), MoveSelectionRight)
+                .on(Right, MoveSelectionRight)
+                .on(Char('h'), MoveSelectionLeft)
+                .on(Left, MoveSelectionLeft)
+                .label("Tab")
+                .hint(),
+            bind("Page up").on(PageUp, MoveSelectionPageUp),
+            bind("Page down").on(PageDown, MoveSelectionPageDown),
+            bind("Go to top").on(Char('g'), MoveSelectionTop),
+            bind("Go to bottom").on(Char('G'), MoveSelectionBottom),
+            bind("Enter Fuzzy Find Mode")
+                .on(Char('/'), EnterInsert)
+                .hint(),
+            bind("Choose Current Selection")
+                .on(Enter, SubmitSelection)
+                .hint(),
+            bind("Select a dataset by its number, then Enter to open it").label("0-9"),
+            bind("Sort by the next column").on(Char('s'), CycleSortColumn),
+            bind("Reverse the sort order").on(Char('S'), ReverseSort),
+            bind("Mark/unmark dataset").on(Char('v'), ToggleSelection),
+            bind("Unmark all datasets").on(Char('V'), UnmarkAll),
+            bind("Export marked datasets to CSV").on(Char('e'), ExportMarked),
+            bind("Open marked datasets side by side").on(Char('o'), OpenMarked),
+            bind("Open a computed dataset").on(Char('='), EnterExpression),
+            bind("Cancel scan").on(Esc, CancelScan),
+            bind("Reload Data").on(Char('r'), ReloadData).hint(),
+            bind("Quit").on(Char('q'), Quit).hint(),
+            bind("Open Help").on(Char('?'), SwitchModeToHelp).hint(),
+        ],
+        Context::PickerEditing => vec![
+            bind("Filter datasets").label("type").hint(),
+            bind("Exit Fuzzy Find Mode")
+                .on(Enter, EnterNormal)
+                .on(Esc, EnterNormal)
+                .hint(),
+        ],
+        Context::Viewer => {
+            let mut bindings = vec![
+                bind("Move left")
+                    .on(Char('h'), MoveSelectionLeft)
+                    .on(Left, MoveSelectionLeft),
+                bind("Move down")
+                    .on(Char('j'), MoveSelectionNext)
+                    .on(Down, MoveSelectionNext),
+                bind("Move up")
+                    .on(Char('k'), MoveSelectionPrevious)
+                    .on(Up, MoveSelectionPrevious),
+                bind("Move right")
+                    .on(Char('l'), MoveSelectionRight)
+                    .on(Right, MoveSelectionRight),
+                bind("Page left")
+                    .on(Char('H'), MoveSelectionPageLeft)
+                    .on_mod(KeyModifiers::SHIFT, Left, MoveSelectionPageLeft),
+                bind("Page right")
+                    .on(Char('L'), MoveSelectionPageRight)
+                    .on_mod(KeyModifiers::SHIFT, Right, MoveSelectionPageRight),
+                bind("Go to first columns")
+                    .on(Home, MoveSelectionHome)
+                    .on_mod(KeyModifiers::CONTROL, Home, MoveSelectionHome),
+                bind("Go to last columns").on(End, MoveSelectionEnd).on_mod(
+                    KeyModifiers::CONTROL,
+                    End,
+                    MoveSelectionEnd,
+                ),
+                bind("Page up").on(PageUp, MoveSelectionPageUp),
+                bind("Page down").on(PageDown, MoveSelectionPageDown),
+                bind("Scroll the dimensions of the summary")
+                    .on_mod(KeyModifiers::CONTROL, Up, ScrollSummary(-1))
+                    .on_mod(KeyModifiers::CONTROL, Down, ScrollSummary(1)),
+                bind("Step the dimension last stepped through")
+                    .on_mod(KeyModifiers::ALT, Right, StepScrubbed(true))
+                    .on_mod(KeyModifiers::ALT, Left, StepScrubbed(false)),
+            ];
+            for i in 0..DIMENSIONS {
+                let binding = bind(ordinal_description(i))
+                    .on(F(i as u8 + 1), NextAxis(i))
+                    .on_mod(KeyModifiers::SHIFT, F(i as u8 + 1), PreviousAxis(i));
+                bindings.push(if i == 0 { binding.hint() } else { binding });
+            }
+            for i in 0..DIMENSIONS {
+                let c = char::from(b'1' + i as u8);
+                bindings.push(
+                    bind(ordinal_description(i))
+                        .on(Char(c), NextAxis(i))
+                        .on_mod(KeyModifiers::CONTROL, Char(c), PreviousAxis(i)),
+                );
+            }
+            let mut aggregate = bind("Fix / sum / average over dimension");
+            for i in 0..DIMENSIONS {
+                aggregate =
+                    aggregate.on_mod(KeyModifiers::ALT, F(i as u8 + 1), CycleAggregation(i));
+            }
+            bindings.push(aggregate);
+            let mut reference = bind("Pin/unpin dimension as Δ reference");
+            for i in 0..DIMENSIONS {
+                let c = char::from(b'1' + i as u8);
+                reference = reference.on_mod(KeyModifiers::ALT, Char(c), ToggleReference(i));
+            }
+            bindings.push(reference);
+            bindings.extend([
+                bind("Switch between Δ and % change vs reference").on(Char('%'), CycleComparison),
+                bind("Cycle 1st Axis")
+                    .on(Char('['), DecrementAxis(0))
+                    .on(Char(']'), IncrementAxis(0)),
+                bind("Cycle 2nd Axis")
+                    .on(Char('{'), DecrementAxis(1))
+                    .on(Char('}'), IncrementAxis(1)),
+                bind("Swap rows and columns").on(Char('x'), SwapAxes).hint(),
+                bind("Next / previous dataset in the group")
+                    .on(Char('n'), NextInGroup)
+                    .on(Char('N'), PreviousInGroup),
+                bind("Back to the previous view")
+                    .on(Backspace, NavigateBack)
+                    .on_mod(KeyModifiers::CONTROL, Char('o'), NavigateBack),
+                bind("Forward to the next view").on(Tab, NavigateForward),
+                bind("Start/stop selecting a block of cells").on(Char('v'), ToggleBlockSelection),
+                bind("Copy selected cells").on(Char('y'), CopyBlock),
+                bind("Pin/unpin row above the others").on(Char('P'), TogglePinRow),
+                bind("Select mode").on(Char('s'), EnterSubset),
+                bind("Choose row and column dimensions")
+                    .on(Char('p'), EnterPivot)
+                    .hint(),
+                bind("Toggle rows sorted by total / file order").on(Char('o'), ToggleSortByTotal),
+                bind("Move the totals to the first or last column, bottom or top row")
+                    .on(Char('t'), CycleTotalPlacement),
+                bind("Choose how values are formatted").on(Char('.'), EnterFormatting),
+                bind("Show more / fewer decimals")
+                    .on(Char('+'), IncreaseDecimals)
+                    .on(Char('-'), DecreaseDecimals),
+                bind("Cycle unit scaling").on(Char('u'), CycleUnitScale),
+                bind("Toggle cumulative sum across columns").on(Char('c'), ToggleCumulative),
+                bind("Drill into overview bin / collapse group").on(Enter, SubmitSelection),
+                bind("Edit the selected cell, with --writable").on(Char('e'), EnterInsert),
+                bind("Close Viewer").on(Esc, Close).hint(),
+                bind("Quit").on(Char('q'), Quit),
+                bind("Open Help").on(Char('?'), SwitchModeToHelp).hint(),
+            ]);
+            bindings
+        }
+        Context::ViewerEditing => vec![
+            bind("Type the new value").label("type").hint(),
+            bind("Keep the value").on(Enter, SubmitSelection).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerSelection => vec![
+            bind("Switch set")
+                .on(Tab, NextSet)
+                .on(BackTab, PreviousSet)
+                .hint(),
+            bind("Move down")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext),
+            bind("Move up")
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious),
+            bind("Move across columns")
+                .on(Char('h'), MoveSelectionLeft)
+                .on(Left, MoveSelectionLeft)
+                .on(Char('l'), MoveSelectionRight)
+                .on(Right, MoveSelectionRight)
+                .label("◄ ►")
+                .hint(),
+            bind("Toggle value").on(Char('v'), ToggleSelection).hint(),
+            bind("Toggle all values")
+                .on(Char('V'), ToggleAllSelection)
+                .hint(),
+            bind("Apply and close").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerPivot => vec![
+            bind("Choose dimension")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Switch rows / columns / groups")
+                .on(Tab, MoveSelectionRight)
+                .on(BackTab, MoveSelectionLeft)
+                .on(Char('h'), MoveSelectionLeft)
+                .on(Left, MoveSelectionLeft)
+                .on(Char('l'), MoveSelectionRight)
+                .on(Right, MoveSelectionRight)
+                .label("Tab")
+                .hint(),
+            bind("Apply").on(Enter, SubmitSelection).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerFrames => vec![
+            bind("Choose dimension")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Export").on(Enter, SubmitSelection).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerBookmarks => vec![
+            bind("Choose bookmark")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Jump").on(Enter, SubmitSelection).hint(),
+            bind("Delete").on(Char('d'), DeleteBookmark).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerWeights => vec![
+            bind("Choose weights")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Use").on(Enter, SubmitSelection).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::ViewerFormatting => vec![
+            bind("Choose an option")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Switch on / off")
+                .on(Enter, SubmitSelection)
+                .on(Char(' '), SubmitSelection)
+                .on(Char('.'), SubmitSelection)
+                .label("Enter")
+                .hint(),
+            bind("Close")
+                .on(Esc, EnterNormal)
+                .on(Char('q'), EnterNormal)
+                .hint(),
+        ],
+        Context::ViewerHeatmap => vec![bind("Back to the table")
+            .on(Esc, EnterNormal)
+            .on(Char('q'), EnterNormal)
+            .hint()],
+        Context::ViewerHistogram => vec![
+            bind("More / fewer bins")
+                .on(Char('+'), MoveSelectionRight)
+                .on(Char('-'), MoveSelectionLeft)
+                .label("+ / -")
+                .hint(),
+            bind("Back to the table")
+                .on(Esc, EnterNormal)
+                .on(Char('q'), EnterNormal)
+                .hint(),
+        ],
+        Context::FileError => vec![
+            bind("Retry").on(Char('r'), RetryOpen).hint(),
+            bind("Open a read-only copy").on(Char('c'), OpenCopy).hint(),
+            bind("Choose another file")
+                .on(Char('o'), EnterInsert)
+                .hint(),
+            bind("Quit").on(Char('q'), Quit).hint(),
+        ],
+        Context::Confirm => vec![
+            bind("Confirm")
+                .on(Char('y'), SubmitSelection)
+                .on(Enter, SubmitSelection)
+                .hint(),
+            bind("Cancel")
+                .on(Char('n'), EnterNormal)
+                .on(Esc, EnterNormal)
+                .hint(),
+        ],
+        Context::Prompt => vec![
+            bind("Edit").label("type").hint(),
+            bind("Confirm").label("Enter").hint(),
+            bind("Cancel").label("ESC").hint(),
+        ],
+        Context::Attributes => vec![
+            bind("Edit the units / documentation")
+                .on(Tab, MoveSelectionNext)
+                .on(BackTab, MoveSelectionNext)
+                .on(Up, MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .label("Tab")
+                .hint(),
+            bind("Save to the file").on(Enter, SubmitSelection).hint(),
+            bind("Cancel").on(Esc, EnterNormal).hint(),
+        ],
+        Context::Search => vec![
+            bind("Choose match")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Open in the Viewer").on(Enter, SubmitSelection).hint(),
+            bind("Close").on(Esc, EnterNormal).hint(),
+        ],
+        Context::Tasks => vec![
+            bind("Choose task")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Cancel task")
+                .on(Char('c'), SubmitSelection)
+                .on(Delete, SubmitSelection)
+                .hint(),
+            bind("Close").on(Esc, EnterNormal).hint(),
+        ],
+        Context::Log => vec![
+            bind("Scroll")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Show more or fewer levels")
+                .on(Char('l'), CycleLogLevel)
+                .hint(),
+            bind("Save the log to a file").on(Char('d'), DumpLog).hint(),
+            bind("Close").on(Esc, EnterNormal).hint(),
+        ],
+        Context::Output => vec![
+            bind("Scroll")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Close").on(Esc, EnterNormal).hint(),
+        ],
+        Context::Browser => vec![
+            bind("Choose file")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Open the directory or file")
+                .on(Enter, SubmitSelection)
+                .on(Char('l'), SubmitSelection)
+                .on(Right, SubmitSelection)
+                .hint(),
+            bind("Go to the parent directory")
+                .on(Char('h'), MoveSelectionLeft)
+                .on(Left, MoveSelectionLeft)
+                .on(Backspace, MoveSelectionLeft)
+                .hint(),
+            bind("Quit").on(Char('q'), Quit).on(Esc, Quit).hint(),
+        ],
+        Context::Help => vec![
+            bind("Scroll")
+                .on(Char('j'), MoveSelectionNext)
+                .on(Down, MoveSelectionNext)
+                .on(Char('k'), MoveSelectionPrevious)
+                .on(Up, MoveSelectionPrevious)
+                .label("j / k")
+                .hint(),
+            bind("Close Help").on(Esc, SwitchModeToPreviousMode).hint(),
+        ],
+    }
+}
+
+fn ordinal_description(i: usize) -> &'static str {
+    [
+        "Cycle 1st dimension",
+        "Cycle 2nd dimension",
+        "Cycle 3rd dimension",
+        "Cycle 4th dimension",
+        "Cycle 5th dimension",
+        "Cycle 6th dimension",
+        "Cycle 7th dimension",
+        "Cycle 8th dimension",
+        "Cycle 9th dimension",
+    ][i]
+}
+
+/// Action bound to `key` in `context`.
+pub fn action(context: Context, key: KeyEvent) -> Option<Action> {
+    bindings(context).iter().find_map(|b| b.action(key))
+}
+
+/// Key that starts a multi-key sequence.
+pub const LEADER: KeyCode = KeyCode::Char(' ');
+
+/// A key pressed after the leader key, and the action it triggers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderBinding {
+    pub key: char,
+    pub description: &'static str,
+    pub action: Action,
+}
+
+const fn leader(key: char, description: &'static str, action: Action) -> LeaderBinding {
+    LeaderBinding {
+        key,
+        description,
+        action,
+    }
+}
+
+/// Sequences starting with the leader key in `context`.
+pub fn leader_bindings(context: Context) -> Vec<LeaderBinding> {
+    match context {
+        Context::Picker => vec![
+            leader('r', "Reload Data", Action::ReloadData),
+            leader('d', "Compare with another file", Action::EnterDiff),
+            leader('n', "Open the next file", Action::NextFile),
+            leader('N', "Open the previous file", Action::PreviousFile),
+            leader(
+                'f',
+                "Search every dataset for a label or values",
+                Action::EnterSearch,
+            ),
+            leader('F', "Show the last search results", Action::ShowSearch),
+            leader(
+                'p',
+                "Show datasets that could not be read",
+                Action::ShowProblems,
+            ),
+            leader(
+                's',
+                "Report the zero and missing cells of the dataset",
+                Action::ShowSparsity,
+            ),
+            leader('=', "Open a computed dataset", Action::EnterExpression),
+            leader(
+                'P',
+                "Run the configured plugin on the marked datasets",
+                Action::RunPlugin,
+            ),
+            leader(
+                'A',
+                "Edit the units and doc of the dataset",
+                Action::EnterAttributes,
+            ),
+            leader('K', "Show background tasks", Action::ShowTasks),
+            leader('M', "Show the log", Action::ShowLog),
+            leader('z', "Save the screen as text", Action::ExportScreen(false)),
+            leader(
+                'Z',
+                "Save the screen with ANSI colours",
+                Action::ExportScreen(true),
+            ),
+            leader('>', "Double the refresh rate", Action::IncreaseRates),
+            leader('<', "Halve the refresh rate", Action::DecreaseRates),
+            leader('?', "Open Help", Action::SwitchModeToHelp),
+        ],
+        Context::Viewer => vec![
+            leader('p', "Choose row and column dimensions", Action::EnterPivot),
+            leader('x', "Swap rows and columns", Action::SwapAxes),
+            leader(
+                'o',
+                "Toggle rows sorted by total",
+                Action::ToggleSortByTotal,
+            ),
+            leader('u', "Cycle unit scaling", Action::CycleUnitScale),
+            leader('c', "Toggle cumulative sum", Action::ToggleCumulative),
+            leader(
+                '%',
+                "Switch between Δ and % change",
+                Action::CycleComparison,
+            ),
+            leader(
+                '.',
+                "Choose how values are formatted",
+                Action::EnterFormatting,
+            ),
+            leader(
+                's',
+                "Show the summary in full, on one line or not at all",
+                Action::ToggleSummary,
+            ),
+            leader(
+                'O',
+                "Move the totals to the first or last column, bottom or top row",
+                Action::CycleTotalPlacement,
+            ),
+            leader('a', "Export frames along a dimension", Action::EnterFrames),
+            leader(
+                'm',
+                "Export slice as Markdown",
+                Action::ExportSlice(SliceFormat::Markdown, false),
+            ),
+            leader(
+                'j',
+                "Export slice as JSON records",
+                Action::ExportSlice(SliceFormat::Json, false),
+            ),
+            leader(
+                'l',
+                "Export slice as text, one cell per line",
+                Action::ExportSlice(SliceFormat::Text, false),
+            ),
+            leader(
+                'k',
+                "Chart the selected rows as SVG",
+                Action::ExportChart(false),
+            ),
+            leader('v', "Export view definition", Action::ExportView(false)),
+            leader('h', "Run the configured hook on the slice", Action::RunHook),
+            leader(
+                't',
+                "Show statistics of the selected column",
+                Action::ColumnStats,
+            ),
+            leader(
+                'g',
+                "Group small rows into Other",
+                Action::ToggleGroupSmallRows,
+            ),
+            leader('n', "Show only the top N rows", Action::EnterTopRows),
+            leader(
+                'r',
+                "Limit exports and statistics to a range of columns",
+                Action::EnterColumnRange,
+            ),
+            leader('G', "Go to a column by its label", Action::EnterGoToColumn),
+            leader('J', "Go to a row by its label", Action::EnterGoToRow),
+            leader(
+                'e',
+                "Export selected cells as CSV",
+                Action::ExportBlock(false),
+            ),
+            leader('b', "Bookmark the slice", Action::EnterBookmark),
+            leader('B', "Jump to a bookmark", Action::EnterBookmarks),
+            leader('=', "Open a computed dataset", Action::EnterExpression),
+            leader(
+                'w',
+                "Choose the weights of weighted means",
+                Action::EnterWeights,
+            ),
+            leader(
+                'i',
+                "Show the whole slice as an image",
+                Action::EnterHeatmap,
+            ),
+            leader(
+                'H',
+                "Show the distribution of the values",
+                Action::EnterHistogram,
+            ),
+            leader('R', "Toggle the roll-up of sets", Action::ToggleRollups),
+            leader(
+                'd',
+                "Show how long opening, reading and drawing took",
+                Action::ToggleTimings,
+            ),
+            leader(
+                'L',
+                "Toggle long labels and set codes",
+                Action::ToggleLongLabels,
+            ),
+            leader(
+                'f',
+                "Compare with the dataset in another file",
+                Action::EnterCompareFile,
+            ),
+            leader('S', "Save edited cells to the file", Action::SaveEdits),
+            leader(
+                'T',
+                "Scale, set or zero out the selected cells",
+                Action::EnterTransform,
+            ),
+            leader(
+                'D',
+                "Save the dataset into the file as a new one",
+                Action::EnterSaveDataset,
+            ),
+            leader(
+                'A',
+                "Edit the units and doc of the dataset",
+                Action::EnterAttributes,
+            ),
+            leader('K', "Show background tasks", Action::ShowTasks),
+            leader('M', "Show the log", Action::ShowLog),
+            leader('z', "Save the screen as text", Action::ExportScreen(false)),
+            leader(
+                'Z',
+                "Save the screen with ANSI colours",
+                Action::ExportScreen(true),
+            ),
+            leader('>', "Double the refresh rate", Action::IncreaseRates),
+            leader('<', "Halve the refresh rate", Action::DecreaseRates),
+            leader('?', "Open Help", Action::SwitchModeToHelp),
+        ],
+        _ => vec![],
+    }
+}
+
+/// Result of feeding a key to a [`Leader`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sequence {
+    /// The key is not part of a sequence and should be handled as usual
+    Unhandled,
+    /// The key started, completed or cancelled a sequence
+    Consumed(Option<Action>),
+}
+
+/// Tracks multi-key sequences started with the [`LEADER`] key.
+///
+/// Shared by every component: keys are fed here before the focused component sees them.
+#[derive(Debug, Default)]
+pub struct Leader {
+    pub pending: bool,
+}
+
+impl Leader {
+    pub fn handle_key_event(&mut self, context: Context, key: KeyEvent) -> Sequence {
+        let bindings = leader_bindings(context);
+        if bindings.is_empty() {
+            self.pending = false;
+            return Sequence::Unhandled;
+        }
+        if !self.pending {
+            if key.code == LEADER && key.modifiers.difference(KeyModifiers::SHIFT).is_empty() {
+                self.pending = true;
+                return Sequence::Consumed(None);
+            }
+            return Sequence::Unhandled;
+        }
+        self.pending = false;
+        let action = match key.code {
+            KeyCode::Char(c) => bindings.into_iter().find(|b| b.key == c).map(|b| b.action),
+            _ => None,
+        };
+        if action.is_none() {
+            log::debug!("No leader binding for {key:?}");
+        }
+        Sequence::Consumed(action)
+    }
+}
+
+/// Number of bindings shown in the footer hint bar.
+pub const HINTS: usize = 5;
+
+/// The most relevant bindings of `context`, for the footer hint bar.
+pub fn hints(context: Context) -> Vec<Binding> {
+    bindings(context)
+        .into_iter()
+        .filter(|b| b.hint)
+        .take(HINTS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action() {
+        let shifted = KeyEvent::new(KeyCode::Char('%'), KeyModifiers::SHIFT);
+        assert_eq!(
+            action(Context::Viewer, shifted),
+            Some(Action::CycleComparison)
+        );
+        let alt = KeyEvent::new(KeyCode::Char('3'), KeyModifiers::ALT);
+        assert_eq!(
+            action(Context::Viewer, alt),
+            Some(Action::ToggleReference(2))
+        );
+        let page = KeyEvent::new(KeyCode::Left, KeyModifiers::SHIFT);
+        assert_eq!(
+            action(Context::Viewer, page),
+            Some(Action::MoveSelectionPageLeft)
+        );
+        let keys: Vec<String> = bindings(Context::Viewer).iter().map(|b| b.keys()).collect();
+        assert!(keys.contains(&"F1 / Shift+F1".to_string()));
+        assert!(keys.contains(&"Alt+1 … Alt+9".to_string()));
+    }
+}
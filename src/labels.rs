@@ -0,0 +1,73 @@
+use std::{collections::HashMap, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::rollup::csv_record;
+
+/// Long labels of terse set codes, e.g. `ON` for `Ontario` or `ResSH` for `Residential space
+/// heating`, from a dictionary file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dictionary(HashMap<String, String>);
+
+impl Dictionary {
+    /// Reads a dictionary file, a CSV file with one `code,label` line per code, e.g.
+    /// `NG,Natural gas`.
+    ///
+    /// Blank lines and lines starting with `#` are skipped, as is a `code,label` header.
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| eyre!("Unable to read the dictionary {}: {e}", path.display()))?;
+        let mut labels = HashMap::new();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match &csv_record(line)[..] {
+                [code, label] if i == 0 && code == "code" && label == "label" => (),
+                [code, label] => {
+                    labels.insert(code.trim().to_string(), label.trim().to_string());
+                }
+                _ => {
+                    return Err(eyre!(
+                        "{} line {}: expected code,label",
+                        path.display(),
+                        i + 1
+                    ))
+                }
+            }
+        }
+        Ok(Self(labels))
+    }
+
+    /// Long label of `code`, or `code` itself if the dictionary does not have it.
+    pub fn label<'a>(&'a self, code: &'a str) -> &'a str {
+        self.0.get(code.trim()).map_or(code, String::as_str)
+    }
+
+    /// Long labels of `codes`, in the same order.
+    pub fn labels(&self, codes: &[String]) -> Vec<String> {
+        codes.iter().map(|c| self.label(c).to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dictionary() {
+        let path = std::env::temp_dir().join("e2020-data-viewer-test-dictionary.csv");
+        std::fs::write(
+            &path,
+            "code,label\n# Areas\nON,Ontario\n\n\"ResSH\",\"Residential, space heating\"\n",
+        )
+        .unwrap();
+        let dictionary = Dictionary::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            dictionary.labels(&["ON  ".into(), "QC".into(), "ResSH".into()]),
+            vec!["Ontario", "QC", "Residential, space heating"]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Reading, slicing and exporting ENERGY2020 HDF5 databases, and the terminal user interface
+//! built on top of them.
+//!
+//! The `e2020-data-viewer` binary is a thin wrapper around [`runner::Runner`]. Other tools can
+//! use the data model directly without starting the TUI:
+//!
+//! ```no_run
+//! use e2020_data_viewer::data::{self, Data};
+//!
+//! # fn main() -> color_eyre::eyre::Result<()> {
+//! // Every variable of the file, as `group/name`
+//! let names = data::variables("database.hdf5")?;
+//!
+//! // Rows of the first dimension by columns of the last one, the others at their first element
+//! let d = Data::new("database.hdf5".into(), names[0].clone())?;
+//! let index = vec![0; d.ndims];
+//! let rows = 0..d.set_data[0].len();
+//! let cols = 0..d.set_data[d.ndims - 1].len();
+//! let slice = d.read_slice(d.ndims - 1, 0, &index, rows, cols)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! - [`data`]: opening files and variables, reading 2-D slices
+//! - [`chart`]: line charts of slices as SVG
+//! - [`check`]: integrity check of a whole file
+//! - [`validate`]: conventions of E2020 databases, e.g. every variable naming its sets
+//! - [`compute`]: datasets computed from others, e.g. `ooutput/Emissions / ooutput/Demand`
+//! - [`diff`]: datasets that differ between two files
+//! - [`export`]: whole datasets written to CSV files in the background
+//! - [`files`]: the files given on the command line, from patterns or stdin
+//! - [`search`]: set labels and values across the datasets of a file
+//! - [`list`]: the datasets of a file, as text, CSV or JSON
+//! - [`stats`]: summary statistics of a dataset
+//! - [`sparsity`]: zero and missing cells of a dataset, and the members of its sets with no data
+//! - [`serve`]: HTTP JSON API over datasets and slices
+//! - [`units`]: unit conversions
+//! - [`rollup`]: sums of the members of a set into groups, from mapping files
+//! - [`labels`]: long labels of set codes, from dictionary files
+//! - [`i18n`]: English and French text of the interface
+//! - [`tasks`]: operations running in the background, e.g. scanning a file
+//! - [`logs`]: the last log lines, kept in memory
+//! - [`journal`]: log of visited views, exported as Markdown
+//! - [`view`]: shareable definitions of a Viewer table
+//! - [`render`]: the screen of a dataset as text, without a terminal, for snapshot tests
+//! - [`components`]: the TUI
+#![allow(dead_code)]
+#![allow(unused_imports)]
+#![allow(unused_variables)]
+#![allow(clippy::too_many_arguments)]
+
+pub mod action;
+pub mod chart;
+pub mod check;
+pub mod components;
+pub mod compute;
+pub mod config;
+pub mod data;
+pub mod diff;
+pub mod export;
+pub mod files;
+pub mod i18n;
+pub mod journal;
+pub mod keymap;
+pub mod labels;
+pub mod list;
+pub mod logs;
+pub mod palette;
+pub mod render;
+pub mod rollup;
+pub mod runner;
+pub mod search;
+pub mod serve;
+pub mod sparsity;
+pub mod stats;
+pub mod tasks;
+pub mod tui;
+pub mod units;
+pub mod utils;
+pub mod validate;
+pub mod view;
@@ -0,0 +1,146 @@
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    data::{self, Data},
+    utils::csv_field,
+};
+
+/// Output format of `--list-datasets`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    #[default]
+    Text,
+    Csv,
+    Json,
+}
+
+/// A dataset as listed in the Picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub dims: Vec<String>,
+    pub shape: Vec<usize>,
+    pub units: String,
+    pub doc: String,
+}
+
+impl From<&Data> for Entry {
+    fn from(d: &Data) -> Self {
+        Self {
+            name: d.name.clone(),
+            dims: d.set_names.clone(),
+            shape: d.shape.clone(),
+            units: d.units.clone(),
+            doc: d.doc.clone(),
+        }
+    }
+}
+
+impl Entry {
+    fn fields(&self) -> [String; 5] {
+        [
+            self.name.clone(),
+            self.dims.join(", "),
+            self.shape
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            self.units.clone(),
+            self.doc.clone(),
+        ]
+    }
+}
+
+const HEADER: [&str; 5] = ["Name", "Dims", "Shape", "Units", "Documentation"];
+
+/// Every dataset of `file` that can be read, in file order. Unreadable ones are skipped, as in
+/// the Picker.
+pub fn datasets(file: &Path) -> Result<Vec<Entry>> {
+    let mut entries = vec![];
+    for name in data::variables(file)? {
+        match Data::new(file.into(), name.clone()) {
+            Ok(d) => entries.push(Entry::from(&d)),
+            Err(e) => log::warn!("Skipping {name}: {e}"),
+        }
+    }
+    Ok(entries)
+}
+
+pub fn format(entries: &[Entry], format: Format) -> String {
+    match format {
+        Format::Text => to_text(entries),
+        Format::Csv => to_csv(entries),
+        Format::Json => to_json(entries),
+    }
+}
+
+/// Aligned columns, one dataset per line.
+pub fn to_text(entries: &[Entry]) -> String {
+    let rows: Vec<[String; 5]> = std::iter::once(HEADER.map(String::from))
+        .chain(entries.iter().map(Entry::fields))
+        .collect();
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (w, field) in widths.iter_mut().zip(row) {
+            *w = (*w).max(field.width());
+        }
+    }
+    rows.iter()
+        .map(|row| {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(field, w)| format!("{field}{}", " ".repeat(w - field.width())))
+                .collect();
+            line.join("  ").trim_end().to_string() + "\n"
+        })
+        .collect()
+}
+
+pub fn to_csv(entries: &[Entry]) -> String {
+    std::iter::once(HEADER.map(String::from))
+        .chain(entries.iter().map(Entry::fields))
+        .map(|row| row.map(|f| csv_field(&f)).join(",") + "\n")
+        .collect()
+}
+
+pub fn to_json(entries: &[Entry]) -> String {
+    let entries: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|e| {
+            serde_json::json!({
+                "name": e.name,
+                "dims": e.dims,
+                "shape": e.shape,
+                "units": e.units,
+                "doc": e.doc,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_default() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_csv() {
+        let entries = vec![Entry {
+            name: "/routput/Dmd".into(),
+            dims: vec!["Area".into(), "Year".into()],
+            shape: vec![2, 3],
+            units: "TBtu".into(),
+            doc: "Demand \"total\"".into(),
+        }];
+        assert_eq!(
+            to_csv(&entries),
+            "Name,Dims,Shape,Units,Documentation\n\
+             /routput/Dmd,\"Area, Year\",\"2, 3\",TBtu,\"Demand \"\"total\"\"\"\n"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    collections::VecDeque,
+    io::Write,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use color_eyre::eyre::Result;
+use lazy_static::lazy_static;
+use tracing::{Level, Metadata};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Most log lines kept in memory, the oldest dropped first.
+pub const LOG_LINES: usize = 2000;
+
+lazy_static! {
+    /// The last log lines of the session, for the log popup.
+    pub static ref LOGS: LogBuffer = LogBuffer::new(LOG_LINES);
+}
+
+/// A formatted log event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub level: Level,
+    pub text: String,
+}
+
+/// Bounded buffer of the last log lines, which `tracing_subscriber` formats events into.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    lines: Arc<Mutex<VecDeque<LogLine>>>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, level: Level, text: &str) {
+        let mut lines = self.lines.lock().unwrap();
+        if lines.len() == self.capacity {
+            lines.pop_front();
+        }
+        lines.push_back(LogLine {
+            level,
+            text: text.trim_end().to_string(),
+        });
+    }
+
+    /// Lines at `level` or more severe, oldest first.
+    pub fn lines(&self, level: Level) -> Vec<LogLine> {
+        self.lines
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.level <= level)
+            .cloned()
+            .collect()
+    }
+
+    /// Writes every line kept to `path`.
+    pub fn dump(&self, path: &Path) -> Result<()> {
+        let mut out = std::fs::File::create(path)?;
+        for line in self.lines.lock().unwrap().iter() {
+            writeln!(out, "{}", line.text)?;
+        }
+        Ok(())
+    }
+
+    fn make_writer_for_level(&self, level: Level) -> LineWriter {
+        LineWriter {
+            buffer: self.clone(),
+            level,
+            bytes: vec![],
+        }
+    }
+}
+
+/// Collects the bytes of one event, added to its buffer once formatted.
+pub struct LineWriter {
+    buffer: LogBuffer,
+    level: Level,
+    bytes: Vec<u8>,
+}
+
+impl Write for LineWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.bytes.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Drop for LineWriter {
+    fn drop(&mut self) {
+        if !self.bytes.is_empty() {
+            self.buffer
+                .push(self.level, &String::from_utf8_lossy(&self.bytes));
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for LogBuffer {
+    type Writer = LineWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.make_writer_for_level(Level::INFO)
+    }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        self.make_writer_for_level(*meta.level())
+    }
+}
+
+/// Level shown after `level` when cycling through them, from the most severe to the most
+/// verbose and back.
+pub fn next_level(level: Level) -> Level {
+    match level {
+        Level::ERROR => Level::WARN,
+        Level::WARN => Level::INFO,
+        Level::INFO => Level::DEBUG,
+        Level::DEBUG => Level::TRACE,
+        _ => Level::ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_buffer() {
+        let logs = LogBuffer::new(2);
+        logs.push(Level::INFO, "first\n");
+        {
+            let mut w = logs.make_writer_for_level(Level::WARN);
+            write!(w, "second").unwrap();
+            writeln!(w, " line").unwrap();
+        }
+        logs.push(Level::DEBUG, "third");
+        let texts = |level| {
+            logs.lines(level)
+                .into_iter()
+                .map(|l| l.text)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(texts(Level::TRACE), vec!["second line", "third"]);
+        assert_eq!(texts(Level::INFO), vec!["second line"]);
+        assert_eq!(next_level(Level::TRACE), Level::ERROR);
+    }
+}
@@ -3,39 +3,263 @@
 #![allow(unused_variables)]
 #![allow(clippy::too_many_arguments)]
 
-pub mod action;
-pub mod components;
-pub mod data;
-pub mod runner;
-pub mod tui;
-pub mod utils;
-
 use std::path::PathBuf;
 
-use clap::Parser;
-use color_eyre::eyre::Result;
-
-use crate::{
-    runner::Runner,
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{eyre, Result};
+use e2020_data_viewer::{
+    check,
+    components::{
+        app::App,
+        viewer::{text_table, SliceFormat},
+    },
+    config::{self, Config, DefaultIndex, Session, Startup},
+    data,
+    diff::{self, Tolerance},
+    files,
+    i18n::{self, Language},
+    list,
+    palette::Palette,
+    render,
+    runner::{self, Runner},
+    serve, stats,
     utils::{initialize_logging, initialize_panic_handler, version},
+    validate,
+    view::ViewDefinition,
 };
 
 /// E2020 Data Viewer
 #[derive(Parser, Debug)]
-#[command(version=version(), about)]
+#[command(version=version(), about, subcommand_negates_reqs = true)]
 struct Args {
-    /// The input file to use
-    #[arg(short, long)]
-    file: PathBuf,
-    /// Tick rate (ticks per second)
-    #[arg(long, default_value_t = 4.0)]
-    tick_rate: f64,
-    /// Frame rate (frames per second)
-    #[arg(long, default_value_t = 4.0)]
-    frame_rate: f64,
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// The input file to use, `-` for stdin, or files or patterns to switch between; chosen in a
+    /// file browser if not given
+    #[arg(short, long, num_args = 1..)]
+    file: Vec<PathBuf>,
+    /// Tick rate (ticks per second) [default: from the config, 4]
+    #[arg(long, value_parser = config::parse_rate)]
+    tick_rate: Option<f64>,
+    /// Frame rate (frames per second) [default: from the config, 4]
+    #[arg(long, value_parser = config::parse_rate)]
+    frame_rate: Option<f64>,
     /// The dataset to read on load (optional)
     #[arg(short, long)]
     dataset: Option<String>,
+    /// Open the dataset and table described in a view file
+    #[arg(long, value_name = "FILE", conflicts_with = "dataset")]
+    view: Option<PathBuf>,
+    /// Disable animations and transient highlights
+    #[arg(long)]
+    reduced_motion: bool,
+    /// Log every view visited and write it as Markdown on exit
+    #[arg(long)]
+    journal: bool,
+    /// Allow editing values in the Viewer and saving them to the file
+    #[arg(long)]
+    writable: bool,
+    /// Element a set starts at, e.g. Year=last, Area=ON or Year=-2, over the config; repeatable
+    #[arg(long, value_name = "SET=ELEMENT", value_parser = DefaultIndex::parse_assignment)]
+    index: Vec<(String, DefaultIndex)>,
+    /// Print the table the dataset or view opens with as plain text, one cell per line, and exit
+    #[arg(long)]
+    plain: bool,
+    /// With --plain, print the table again every time the file is modified, until interrupted
+    #[arg(long, requires = "plain")]
+    follow: bool,
+    /// Print the screen the dataset or view opens with as text, at COLUMNSxROWS, and exit
+    #[arg(
+        long,
+        value_name = "SIZE",
+        num_args = 0..=1,
+        default_missing_value = "120x40",
+        value_parser = render::parse_size
+    )]
+    screenshot: Option<(u16, u16)>,
+    /// Print the datasets of the file and exit
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "text"
+    )]
+    list_datasets: Option<list::Format>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Read every object of a file and report the ones that are unreadable or corrupt
+    Check {
+        /// The file to check
+        file: PathBuf,
+    },
+    /// Report the datasets that break the conventions of E2020 databases
+    Validate {
+        /// The file to validate
+        file: PathBuf,
+    },
+    /// Print the minimum, maximum, mean, standard deviation and sum of a dataset
+    Stats {
+        /// The file to read
+        file: PathBuf,
+        /// The dataset, e.g. "routput/Dmd"
+        dataset: String,
+        /// Compute them for each element of this dimension, e.g. "Year"
+        #[arg(long)]
+        by: Option<String>,
+    },
+    /// List the datasets that differ between two files
+    Diff {
+        /// The reference file
+        first: PathBuf,
+        /// The file compared against it
+        second: PathBuf,
+        /// Relative tolerance on values
+        #[arg(long, default_value_t = Tolerance::default().rtol)]
+        rtol: f64,
+        /// Absolute tolerance on values
+        #[arg(long, default_value_t = Tolerance::default().atol)]
+        atol: f64,
+    },
+    /// Serve the datasets of a file as a read-only HTTP JSON API
+    Serve {
+        /// The file to serve
+        file: PathBuf,
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+}
+
+/// Runs the `check` subcommand, failing if any object could not be read.
+fn run_check(file: PathBuf) -> Result<()> {
+    let report = check::check(&file)?;
+    for problem in report.problems.iter() {
+        println!("{}: {}", problem.path, problem.error);
+    }
+    println!(
+        "Checked {} datasets in {}: {} unreadable",
+        report.datasets,
+        file.display(),
+        report.problems.len()
+    );
+    if report.problems.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} unreadable objects in {}",
+            report.problems.len(),
+            file.display()
+        ))
+    }
+}
+
+/// Runs the `validate` subcommand, failing if any dataset breaks a convention.
+fn run_validate(file: PathBuf) -> Result<()> {
+    let report = validate::validate(&file)?;
+    for violation in report.violations.iter() {
+        println!("{}: {}", violation.path, violation.message);
+    }
+    println!(
+        "Validated {} variables in {}: {} violations",
+        report.variables,
+        file.display(),
+        report.violations.len()
+    );
+    if report.violations.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} violations of the E2020 conventions in {}",
+            report.violations.len(),
+            file.display()
+        ))
+    }
+}
+
+/// Runs the `diff` subcommand, failing if any dataset differs.
+fn run_diff(first: PathBuf, second: PathBuf, tolerance: Tolerance) -> Result<()> {
+    let report = diff::diff(&first, &second, tolerance)?;
+    print!("{}", report.to_text());
+    if report.changes.is_empty() {
+        Ok(())
+    } else {
+        Err(eyre!(
+            "{} datasets differ between {} and {}",
+            report.changes.len(),
+            first.display(),
+            second.display()
+        ))
+    }
+}
+
+/// Runs the `stats` subcommand.
+fn run_stats(file: PathBuf, dataset: String, by: Option<String>) -> Result<()> {
+    let groups = stats::stats(&file, &dataset, by.as_deref())?;
+    let columns: Vec<String> = [
+        by.as_deref().unwrap_or(""),
+        "Count",
+        "Min",
+        "Max",
+        "Mean",
+        "Std",
+        "Sum",
+    ]
+    .map(String::from)
+    .to_vec();
+    let rows: Vec<String> = groups.iter().map(|(label, _)| label.clone()).collect();
+    let cells: Vec<Vec<String>> = groups
+        .iter()
+        .map(|(_, s)| {
+            let mut row = vec![s.count.to_string()];
+            row.extend([s.min, s.max, s.mean, s.std, s.sum].map(|v| format!("{v:.4}")));
+            row
+        })
+        .collect();
+    print!("{}", text_table(&columns, &rows, &cells));
+    Ok(())
+}
+
+/// How often `--follow` looks for changes to the file.
+const FOLLOW_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Prints the table of `--plain` each time `file` is modified, e.g. by a model run writing its
+/// outputs, like `tail -f`, until interrupted.
+async fn run_follow(
+    file: String,
+    dataset: Option<String>,
+    view: Option<ViewDefinition>,
+    config: Config,
+) -> Result<()> {
+    let modified = || std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+    let mut printed = None;
+    loop {
+        let seen = modified();
+        if seen != printed {
+            // Wait for the writer to be done before reading
+            tokio::time::sleep(FOLLOW_INTERVAL).await;
+            if modified() != seen {
+                continue;
+            }
+            printed = seen;
+            let table = App::new(file.clone(), dataset.clone(), view.clone(), config.clone())
+                .and_then(|mut app| app.viewer.slice_text(SliceFormat::Text));
+            match table {
+                Ok(table) => {
+                    println!(
+                        "==> {file} at {} <==",
+                        chrono::Local::now().format("%H:%M:%S")
+                    );
+                    print!("{table}");
+                }
+                // The file may be rewritten as a whole, so try again on its next change
+                Err(e) => eprintln!("Unable to read {file}: {e}"),
+            }
+        }
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+    }
 }
 
 #[tokio::main]
@@ -44,13 +268,107 @@ async fn main() -> Result<()> {
     initialize_panic_handler()?;
     log::debug!("Starting in main");
     let args = Args::parse();
-    let (tick_rate, frame_rate, file) = (
-        args.tick_rate,
-        args.frame_rate,
-        args.file.as_os_str().to_string_lossy().to_string(),
+    match args.command {
+        Some(Command::Check { file }) => return run_check(file),
+        Some(Command::Validate { file }) => return run_validate(file),
+        Some(Command::Stats { file, dataset, by }) => return run_stats(file, dataset, by),
+        Some(Command::Diff {
+            first,
+            second,
+            rtol,
+            atol,
+        }) => return run_diff(first, second, Tolerance { rtol, atol }),
+        Some(Command::Serve { file, addr }) => {
+            return serve::serve(file, &addr, Config::new()?.unit_conversions).await
+        }
+        None => (),
+    }
+    let mut config = Config::new()?;
+    // https://no-color.org
+    if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        config.palette = Palette::Monochrome;
+    }
+    let _stdin = args
+        .file
+        .iter()
+        .any(|f| f.as_os_str() == "-")
+        .then_some(files::StdinGuard);
+    let mut files = files::resolve(&args.file)?;
+    let file = match files.first() {
+        Some(file) => file.clone(),
+        None if args.list_datasets.is_some() || args.plain || args.screenshot.is_some() => {
+            return Err(eyre!(
+                "--file is needed to print datasets, tables or screens"
+            ));
+        }
+        None => match runner::choose_file(std::env::current_dir()?, config.palette).await? {
+            Some(file) => {
+                files.push(file.clone());
+                file
+            }
+            None => return Ok(()),
+        },
+    };
+    if let Some(format) = args.list_datasets {
+        print!("{}", list::format(&list::datasets(file.as_ref())?, format));
+        return Ok(());
+    }
+    let (tick_rate, frame_rate) = (
+        args.tick_rate.unwrap_or(config.tick_rate),
+        args.frame_rate.unwrap_or(config.frame_rate),
     );
+    config.reduced_motion |= args.reduced_motion;
+    config.journal |= args.journal;
+    config.writable = args.writable.then(|| file.clone());
+    for (set, index) in args.index {
+        config
+            .default_indices
+            .retain(|k, _| !k.eq_ignore_ascii_case(&set));
+        config.default_indices.insert(set, index);
+    }
+    // Every other file is opened read-only; see data::open_file_rw
+    let _edited = config
+        .writable
+        .as_ref()
+        .map(data::open_file_rw)
+        .transpose()?;
+    i18n::set_language(config.language.unwrap_or_else(Language::from_env));
     log::debug!("Reading file: {file}");
-    let mut app = Runner::new(tick_rate, frame_rate, file, args.dataset)?;
+    let view = args
+        .view
+        .map(|path| ViewDefinition::load(&path))
+        .transpose()?;
+    let dataset = view.as_ref().map(|v| v.dataset.clone());
+    let dataset = dataset.or(args.dataset).or_else(|| match config.startup {
+        Startup::Picker => None,
+        Startup::LastDataset => Session::load(&file)
+            .map(|s| s.dataset)
+            .filter(|name| hdf5::File::open(&file).is_ok_and(|f| f.dataset(name).is_ok())),
+    });
+    if args.plain {
+        if dataset.is_none() {
+            return Err(eyre!("--plain needs a dataset, from --dataset or --view"));
+        }
+        if args.follow {
+            return run_follow(file, dataset, view, config).await;
+        }
+        let mut app = App::new(file, dataset, view, config)?;
+        print!("{}", app.viewer.slice_text(SliceFormat::Text)?);
+        return Ok(());
+    }
+    if let Some((width, height)) = args.screenshot {
+        let Some(dataset) = dataset else {
+            return Err(eyre!(
+                "--screenshot needs a dataset, from --dataset or --view"
+            ));
+        };
+        print!(
+            "{}",
+            render::render_to_string(&file, &dataset, view, config, width, height)?
+        );
+        return Ok(());
+    }
+    let mut app = Runner::new(tick_rate, frame_rate, files, dataset, view, config)?;
     app.run().await?;
     Ok(())
 }
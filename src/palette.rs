@@ -0,0 +1,192 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use ratatui::style::{Color, Modifier, Style};
+use serde_derive::{Deserialize, Serialize};
+
+/// Stops of the viridis colour map, which stays readable with every common colour vision
+/// deficiency and in greyscale.
+const VIRIDIS: [(u8, u8, u8); 5] = [
+    (68, 1, 84),
+    (59, 82, 139),
+    (33, 145, 140),
+    (94, 201, 98),
+    (253, 231, 37),
+];
+
+/// Diverging blue-yellow-red stops.
+const DIVERGING: [(u8, u8, u8); 5] = [
+    (49, 54, 149),
+    (116, 173, 209),
+    (255, 255, 191),
+    (244, 109, 67),
+    (165, 0, 38),
+];
+
+/// Colours used for deltas, heatmaps and conditional formatting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Palette {
+    /// Green and red deltas, diverging blue to red gradient
+    #[default]
+    Default,
+    /// Blue and orange deltas, viridis gradient
+    ColorBlind,
+    /// Bright colours only, and reversed video instead of background colours
+    HighContrast,
+    /// No colours at all, as asked for by `NO_COLOR`
+    Monochrome,
+}
+
+impl Palette {
+    /// Colour of values that went up.
+    pub fn increase(&self) -> Color {
+        match self {
+            Palette::Default => Color::Green,
+            Palette::ColorBlind => Color::Rgb(86, 180, 233),
+            Palette::HighContrast => Color::LightGreen,
+            Palette::Monochrome => Color::Reset,
+        }
+    }
+
+    /// Colour of values that went down.
+    pub fn decrease(&self) -> Color {
+        match self {
+            Palette::Default => Color::Red,
+            Palette::ColorBlind => Color::Rgb(230, 159, 0),
+            Palette::HighContrast => Color::LightRed,
+            Palette::Monochrome => Color::Reset,
+        }
+    }
+
+    /// Colour at `t` between 0 (lowest) and 1 (highest) along the palette's gradient.
+    pub fn gradient(&self, t: f64) -> Color {
+        let stops = match self {
+            Palette::Default | Palette::HighContrast => &DIVERGING,
+            Palette::ColorBlind => &VIRIDIS,
+            Palette::Monochrome => return Color::Reset,
+        };
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let x = t * (stops.len() - 1) as f64;
+        let i = (x.floor() as usize).min(stops.len() - 2);
+        let f = x - i as f64;
+        let lerp = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        let (a, b) = (stops[i], stops[i + 1]);
+        Color::Rgb(lerp(a.0, b.0), lerp(a.1, b.1), lerp(a.2, b.2))
+    }
+
+    /// `c` as text is shown in it: brighter in high contrast, and not at all in monochrome.
+    pub fn color(&self, c: Color) -> Color {
+        match self {
+            Palette::Default | Palette::ColorBlind => c,
+            Palette::HighContrast => bright(c),
+            Palette::Monochrome => Color::Reset,
+        }
+    }
+
+    /// Style of text in the colour `c`.
+    pub fn fg(&self, c: Color) -> Style {
+        Style::default().fg(self.color(c))
+    }
+
+    /// Style of dim text, e.g. missing values, which is plain in high contrast and monochrome.
+    pub fn dim(&self) -> Style {
+        match self {
+            Palette::Default | Palette::ColorBlind => Style::default().add_modifier(Modifier::DIM),
+            Palette::HighContrast | Palette::Monochrome => Style::default(),
+        }
+    }
+
+    /// `style` marked as selected with the background `bg`, or in reversed video, which shows
+    /// on any terminal, in high contrast and monochrome.
+    pub fn selected(&self, style: Style, bg: Color) -> Style {
+        match self {
+            Palette::Default | Palette::ColorBlind => style.bg(bg),
+            Palette::HighContrast | Palette::Monochrome => style.add_modifier(Modifier::REVERSED),
+        }
+    }
+}
+
+/// Palette of the interface, set once from the config.
+static PALETTE: AtomicU8 = AtomicU8::new(Palette::Default as u8);
+
+/// Shows the interface in `palette` from now on.
+pub fn set_palette(palette: Palette) {
+    PALETTE.store(palette as u8, Ordering::Relaxed);
+}
+
+pub fn palette() -> Palette {
+    match PALETTE.load(Ordering::Relaxed) {
+        p if p == Palette::ColorBlind as u8 => Palette::ColorBlind,
+        p if p == Palette::HighContrast as u8 => Palette::HighContrast,
+        p if p == Palette::Monochrome as u8 => Palette::Monochrome,
+        _ => Palette::Default,
+    }
+}
+
+/// [`Palette::color`] of the interface.
+pub fn color(c: Color) -> Color {
+    palette().color(c)
+}
+
+/// [`Palette::fg`] of the interface.
+pub fn fg(c: Color) -> Style {
+    palette().fg(c)
+}
+
+/// [`Palette::dim`] of the interface.
+pub fn dim() -> Style {
+    palette().dim()
+}
+
+/// [`Palette::selected`] of the interface.
+pub fn selected(style: Style, bg: Color) -> Style {
+    palette().selected(style, bg)
+}
+
+/// The bright variant of the basic colour `c`, white for greys and blue, which is hard to read on
+/// a black background.
+fn bright(c: Color) -> Color {
+    match c {
+        Color::Red => Color::LightRed,
+        Color::Green => Color::LightGreen,
+        Color::Yellow => Color::LightYellow,
+        Color::Blue | Color::LightBlue => Color::LightCyan,
+        Color::Magenta => Color::LightMagenta,
+        Color::Cyan => Color::LightCyan,
+        Color::Gray | Color::DarkGray => Color::White,
+        c => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_styles() {
+        assert_eq!(
+            Palette::Default.fg(Color::DarkGray).fg,
+            Some(Color::DarkGray)
+        );
+        assert_eq!(
+            Palette::HighContrast.fg(Color::DarkGray).fg,
+            Some(Color::White)
+        );
+        assert_eq!(Palette::HighContrast.color(Color::Red), Color::LightRed);
+        assert_eq!(Palette::Monochrome.color(Color::Red), Color::Reset);
+        assert!(Palette::Default.dim().add_modifier.contains(Modifier::DIM));
+        assert!(!Palette::HighContrast
+            .dim()
+            .add_modifier
+            .contains(Modifier::DIM));
+        let selected = Palette::Monochrome.selected(Style::default(), Color::DarkGray);
+        assert_eq!(selected.bg, None);
+        assert!(selected.add_modifier.contains(Modifier::REVERSED));
+        assert_eq!(
+            Palette::Default
+                .selected(Style::default(), Color::DarkGray)
+                .bg,
+            Some(Color::DarkGray)
+        );
+    }
+}
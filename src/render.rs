@@ -0,0 +1,104 @@
+use color_eyre::eyre::Result;
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use crate::{
+    components::{app::App, Component},
+    config::Config,
+    palette, tui,
+    view::ViewDefinition,
+};
+
+/// Most draws before the screen is taken as it is. The Viewer reads the columns that fit
+/// judging by the previous draw, so it needs two when they change.
+const MAX_DRAWS: usize = 3;
+
+/// Parses a screen size given as `COLUMNSxROWS`, e.g. `120x40`.
+pub fn parse_size(s: &str) -> Result<(u16, u16), String> {
+    let (width, height) = s
+        .split_once(['x', 'X'])
+        .ok_or_else(|| format!("{s:?} is not a size such as 120x40"))?;
+    let parse = |n: &str| {
+        n.trim()
+            .parse::<u16>()
+            .ok()
+            .filter(|&n| n > 0)
+            .ok_or_else(|| format!("{n:?} is not a number of cells"))
+    };
+    Ok((parse(width)?, parse(height)?))
+}
+
+/// Draws `app` on a screen of `width` columns by `height` rows, without a terminal, and
+/// returns the cells it shows, with the colours of its palette.
+pub fn render(app: &mut App, width: u16, height: u16) -> Result<Buffer> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    palette::set_palette(app.config.palette);
+    for _ in 0..MAX_DRAWS {
+        terminal.draw(|f| app.draw(f, f.size()))?;
+        if !app.needs_redraw() {
+            break;
+        }
+    }
+    Ok(terminal.backend().buffer().clone())
+}
+
+/// The screen `dataset` of `file` opens with, laid out as in `view` if given, as plain text
+/// one line per row.
+///
+/// The result depends only on its arguments and the version of the viewer, shown in the
+/// status line, so it can be compared against a snapshot. The journal is left out, since it
+/// shows the time spent.
+pub fn render_to_string(
+    file: &str,
+    dataset: &str,
+    view: Option<ViewDefinition>,
+    mut config: Config,
+    width: u16,
+    height: u16,
+) -> Result<String> {
+    config.journal = false;
+    config.reduced_motion = true;
+    let mut app = App::new(file.into(), Some(dataset.into()), view, config)?;
+    Ok(tui::buffer_text(&render(&mut app, width, height)?, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("120x40"), Ok((120, 40)));
+        assert_eq!(parse_size("80X24"), Ok((80, 24)));
+        assert!(parse_size("120").is_err());
+        assert!(parse_size("0x40").is_err());
+        assert!(parse_size("wide x 40").is_err());
+    }
+
+    #[test]
+    fn test_render() -> Result<()> {
+        let config = Config {
+            journal: false,
+            reduced_motion: true,
+            ..Config::default()
+        };
+        let screen = || -> Result<String> {
+            // Never read: the Picker lists the datasets once the App runs
+            let file = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+            let mut app = App::new(file.into(), None, None, config.clone())?;
+            Ok(tui::buffer_text(&render(&mut app, 80, 24)?, false))
+        };
+        let first = screen()?;
+        assert_eq!(first, screen()?);
+        assert_eq!(first.lines().count(), 24);
+        assert!(first.contains("┌Picker"));
+        let footer = first
+            .lines()
+            .find(|line| line.starts_with("Tab show the next"))
+            .unwrap_or_default();
+        assert!(
+            footer.ends_with(concat!("#v", env!("CARGO_PKG_VERSION"))),
+            "{footer}"
+        );
+        Ok(())
+    }
+}
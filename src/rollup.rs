@@ -0,0 +1,168 @@
+use std::{collections::HashMap, ops::Range, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::{stack, Array2, ArrayD, ArrayView2, ArrayViewD, Axis};
+
+use crate::stats::nansum;
+
+/// Members of a set summed into groups, e.g. provinces into regions, from a mapping file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rollup {
+    /// Label of each group, in the order of their first member
+    pub groups: Vec<String>,
+    /// Indices of the members of each group, in file order
+    pub members: Vec<Vec<usize>>,
+    /// Labels of the members
+    pub labels: Vec<String>,
+}
+
+/// Reads a mapping file, a CSV file with one `member,group` line per member, e.g. `ON,Central`.
+///
+/// Blank lines and lines starting with `#` are skipped, as is a `member,group` header.
+pub fn read_mapping(path: &Path) -> Result<HashMap<String, String>> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| eyre!("Unable to read the mapping file {}: {e}", path.display()))?;
+    let mut mapping = HashMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match &csv_record(line)[..] {
+            [member, group] if i == 0 && member == "member" && group == "group" => (),
+            [member, group] => {
+                mapping.insert(member.trim().to_string(), group.trim().to_string());
+            }
+            _ => {
+                return Err(eyre!(
+                    "{} line {}: expected member,group",
+                    path.display(),
+                    i + 1
+                ))
+            }
+        }
+    }
+    Ok(mapping)
+}
+
+/// Fields of a CSV line, quoted as by [`crate::utils::csv_field`].
+pub(crate) fn csv_record(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+impl Rollup {
+    /// Groups `labels` by `mapping`. Labels it does not map stay on their own, as groups of one.
+    ///
+    /// Returns `None` if `mapping` maps none of the labels.
+    pub fn new(labels: &[String], mapping: &HashMap<String, String>) -> Option<Self> {
+        if !labels.iter().any(|l| mapping.contains_key(l.trim())) {
+            return None;
+        }
+        let mut rollup = Rollup {
+            labels: labels.to_vec(),
+            ..Default::default()
+        };
+        for (i, label) in labels.iter().enumerate() {
+            let group = mapping.get(label.trim()).unwrap_or(label);
+            match rollup.groups.iter().position(|l| l == group) {
+                Some(g) => rollup.members[g].push(i),
+                None => {
+                    rollup.groups.push(group.clone());
+                    rollup.members.push(vec![i]);
+                }
+            }
+        }
+        Some(rollup)
+    }
+
+    /// Members to read for the groups `groups`, from the first to the last.
+    pub fn span(&self, groups: &Range<usize>) -> Range<usize> {
+        let members = self.members[groups.clone()].iter().flatten();
+        match (members.clone().min(), members.max()) {
+            (Some(&first), Some(&last)) => first..last + 1,
+            _ => 0..0,
+        }
+    }
+
+    /// Sums over the members of `groups` of `data`, whose `axis` holds the members from
+    /// `span.start`. Missing values are left out.
+    pub fn sum(
+        &self,
+        data: ArrayView2<f64>,
+        axis: Axis,
+        span: &Range<usize>,
+        groups: Range<usize>,
+    ) -> Array2<f64> {
+        let mut shape = [data.nrows(), data.ncols()];
+        shape[axis.index()] = groups.len();
+        let mut sums = Array2::from_elem(shape, f64::NAN);
+        for (k, g) in groups.enumerate() {
+            let members: Vec<usize> = self.members[g].iter().map(|m| m - span.start).collect();
+            let lanes = data.select(axis, &members);
+            sums.index_axis_mut(axis, k)
+                .assign(&lanes.map_axis(axis, nansum));
+        }
+        sums
+    }
+
+    /// Sums over the members of every group of `data`, whose `axis` holds every member.
+    /// Missing values are left out.
+    pub fn sum_all(&self, data: ArrayViewD<f64>, axis: Axis) -> Result<ArrayD<f64>> {
+        let sums: Vec<ArrayD<f64>> = self
+            .members
+            .iter()
+            .map(|members| data.select(axis, members).map_axis(axis, nansum))
+            .collect();
+        let views: Vec<ArrayViewD<f64>> = sums.iter().map(|s| s.view()).collect();
+        Ok(stack(axis, &views)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_rollup() {
+        let labels: Vec<String> = ["ON", "QC", "AB", "BC"].map(String::from).to_vec();
+        let mapping = HashMap::from([
+            ("ON".to_string(), "Central".to_string()),
+            ("QC".to_string(), "Central".to_string()),
+            ("BC".to_string(), "West".to_string()),
+        ]);
+        let rollup = Rollup::new(&labels, &mapping).unwrap();
+        assert_eq!(rollup.groups, vec!["Central", "AB", "West"]);
+        assert_eq!(rollup.members, vec![vec![0, 1], vec![2], vec![3]]);
+        assert_eq!(rollup.span(&(1..3)), 2..4);
+        let data = array![[1.0, 2.0, f64::NAN, 4.0], [5.0, 6.0, 7.0, 8.0]];
+        let sums = rollup.sum(data.view(), Axis(1), &(0..4), 0..2);
+        assert_eq!(sums.row(1).to_vec(), vec![11.0, 7.0]);
+        assert_eq!(sums[[0, 0]], 3.0);
+        // Every member missing
+        assert!(sums[[0, 1]].is_nan());
+        let all = rollup.sum_all(data.view().into_dyn(), Axis(1)).unwrap();
+        assert_eq!(all.shape(), &[2, 3]);
+        assert_eq!(all[[1, 2]], 8.0);
+        assert!(Rollup::new(&labels, &HashMap::new()).is_none());
+        assert_eq!(
+            csv_record("\"Nova Scotia, NS\",Atlantic"),
+            vec!["Nova Scotia, NS", "Atlantic"]
+        );
+    }
+}
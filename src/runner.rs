@@ -1,7 +1,7 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 use color_eyre::eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
@@ -11,12 +11,47 @@ use ratatui::{
 
 use crate::{
     action::Action,
-    components::{app::App, Component},
+    components::{app::App, browser::FileBrowser, Component},
+    config::{Config, MAX_RATE, MIN_RATE},
     data::Data,
+    palette::{self, Palette},
     trace_dbg, tui,
     tui::Event,
+    view::ViewDefinition,
 };
 
+/// Lets the user find the file to open from `dir` in the terminal, before the App starts, e.g.
+/// when launched from a desktop shortcut. Returns the path chosen, or `None` if they quit.
+pub async fn choose_file(dir: PathBuf, palette: Palette) -> Result<Option<String>> {
+    palette::set_palette(palette);
+    let mut browser = FileBrowser::new(dir);
+    let mut tui = tui::Tui::new()?;
+    tui.enter()?;
+    let chosen = loop {
+        let Some(e) = tui.next().await else {
+            break None;
+        };
+        let action = match e {
+            tui::Event::Quit => Some(Action::Quit),
+            tui::Event::Init | tui::Event::Render | tui::Event::Resize(_, _) => {
+                tui.draw(|f| browser.draw(f, f.size()))?;
+                None
+            }
+            e => browser.handle_events(e),
+        };
+        match action {
+            Some(Action::Quit) => break None,
+            Some(Action::OpenFile(path)) => break Some(path),
+            Some(action) => {
+                browser.update(action)?;
+            }
+            None => (),
+        }
+    };
+    tui.exit()?;
+    Ok(chosen)
+}
+
 #[derive(Default)]
 pub struct Runner {
     pub tick_rate: f64,
@@ -24,22 +59,38 @@ pub struct Runner {
     pub components: Vec<Box<dyn Component>>,
     pub should_quit: bool,
     pub should_suspend: bool,
+    /// Whether anything changed since the last frame was drawn
+    pub needs_redraw: bool,
+    /// Whether to capture the mouse, from the config
+    pub mouse: bool,
+    /// Whether to write the screen to a file once drawn, with ANSI colours if true
+    pub snapshot: Option<bool>,
 }
 
 impl Runner {
+    /// Opens the first of `files`, the others being switched to from the Picker.
     pub fn new(
         tick_rate: f64,
         frame_rate: f64,
-        file: String,
+        files: Vec<String>,
         dataset: Option<String>,
+        view: Option<ViewDefinition>,
+        config: Config,
     ) -> Result<Self> {
-        let app = App::new(file, dataset)?;
+        palette::set_palette(config.palette);
+        let mouse = config.mouse;
+        let file = files.first().cloned().unwrap_or_default();
+        let mut app = App::new(file, dataset, view, config)?;
+        app.files = files;
         Ok(Self {
             tick_rate,
             frame_rate,
             components: vec![Box::new(app)],
             should_quit: false,
             should_suspend: false,
+            needs_redraw: true,
+            mouse,
+            snapshot: None,
         })
     }
 
@@ -47,12 +98,41 @@ impl Runner {
         self.should_quit = true
     }
 
+    /// Draws every component, then writes the screen to a file if asked, returning the message
+    /// to show about it.
+    fn draw(&mut self, tui: &mut tui::Tui) -> Result<Option<Action>> {
+        self.needs_redraw = false;
+        let frame = tui.draw(|f| {
+            for component in self.components.iter_mut() {
+                component.draw(f, f.size());
+            }
+        })?;
+        let Some(ansi) = self.snapshot.take() else {
+            return Ok(None);
+        };
+        let path = PathBuf::from(format!(
+            "screen-{}.{}",
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            if ansi { "ans" } else { "txt" }
+        ));
+        Ok(Some(
+            match std::fs::write(&path, tui::buffer_text(frame.buffer, ansi)) {
+                Ok(()) => Action::Notify(format!("Saved the screen to {}", path.display())),
+                Err(e) => Action::Error(format!(
+                    "Unable to save the screen to {}: {e}",
+                    path.display()
+                )),
+            },
+        ))
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let (action_tx, mut action_rx) = tokio::sync::mpsc::unbounded_channel();
 
         let mut tui = tui::Tui::new()?;
         tui.tick_rate(self.tick_rate);
         tui.frame_rate(self.frame_rate);
+        tui.mouse(self.mouse);
         tui.enter()?;
 
         for component in self.components.iter_mut() {
@@ -61,6 +141,18 @@ impl Runner {
 
         loop {
             if let Some(e) = tui.next().await {
+                // The mouse only moving changes nothing on screen
+                if !matches!(
+                    e,
+                    tui::Event::Tick
+                        | tui::Event::Render
+                        | tui::Event::Mouse(MouseEvent {
+                            kind: MouseEventKind::Moved,
+                            ..
+                        })
+                ) {
+                    self.needs_redraw = true;
+                }
                 match e {
                     tui::Event::Init => action_tx.send(Action::Init)?,
                     tui::Event::Quit => action_tx.send(Action::Quit)?,
@@ -80,18 +172,50 @@ impl Runner {
             while let Ok(action) = action_rx.try_recv() {
                 if action != Action::Tick && action != Action::Render {
                     log::debug!("{action:?}");
+                    self.needs_redraw = true;
                 }
                 match action {
                     Action::Quit => self.should_quit = true,
                     Action::Suspend => self.should_suspend = true,
                     Action::Resume => self.should_suspend = false,
-                    Action::Render => {
-                        tui.draw(|f| {
-                            for component in self.components.iter_mut() {
-                                component.draw(f, f.size());
+                    Action::IncreaseRates | Action::DecreaseRates => {
+                        let factor = if action == Action::IncreaseRates {
+                            2.0
+                        } else {
+                            0.5
+                        };
+                        self.tick_rate = (self.tick_rate * factor).clamp(MIN_RATE, MAX_RATE);
+                        self.frame_rate = (self.frame_rate * factor).clamp(MIN_RATE, MAX_RATE);
+                        log::info!(
+                            "Ticks at {} and renders at {} per second",
+                            self.tick_rate,
+                            self.frame_rate
+                        );
+                        tui.set_rates(self.tick_rate, self.frame_rate)?;
+                    }
+                    // Skipped when nothing changed, since drawing the Viewer reads the slice
+                    Action::Render
+                        if self.needs_redraw
+                            || self.components.iter().any(|c| c.needs_redraw()) =>
+                    {
+                        if let Some(action) = self.draw(&mut tui)? {
+                            action_tx.send(action)?;
+                        }
+                    }
+                    // Laid out again at once, and a second time if the first draw changed what
+                    // fits, e.g. the columns of the Viewer, rather than at the next frame
+                    Action::Resize { x, y } => {
+                        tui.resize(Rect::new(0, 0, x, y))?;
+                        for _ in 0..2 {
+                            if let Some(action) = self.draw(&mut tui)? {
+                                action_tx.send(action)?;
+                            }
+                            if !self.components.iter().any(|c| c.needs_redraw()) {
+                                break;
                             }
-                        })?;
+                        }
                     }
+                    Action::ExportScreen(ansi) => self.snapshot = Some(ansi),
                     _ => {}
                 }
                 for component in self.components.iter_mut() {
@@ -103,9 +227,11 @@ impl Runner {
             if self.should_suspend {
                 tui.suspend()?;
                 action_tx.send(Action::Resume)?;
+                self.needs_redraw = true;
                 tui = tui::Tui::new()?;
                 tui.tick_rate(self.tick_rate);
                 tui.frame_rate(self.frame_rate);
+                tui.mouse(self.mouse);
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
@@ -0,0 +1,161 @@
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Report, Result};
+use ndarray::{ArrayViewD, Dimension};
+
+use crate::data::Data;
+
+/// Most matches a search lists, so a loose threshold does not fill the memory.
+pub const MAX_MATCHES: usize = 1000;
+
+/// What to look for in the datasets of a file: a set label, e.g. `ON`, or cells above or below
+/// a value, e.g. `>1e6` or `<0`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    Label(String),
+    Above(f64),
+    Below(f64),
+}
+
+impl FromStr for Query {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let value = |v: &str| {
+            v.trim()
+                .parse::<f64>()
+                .map_err(|_| eyre!("{:?} is not a number", v.trim()))
+        };
+        match s.chars().next() {
+            None => Err(eyre!("Nothing to search for")),
+            Some('>') => Ok(Query::Above(value(&s[1..])?)),
+            Some('<') => Ok(Query::Below(value(&s[1..])?)),
+            Some(_) => Ok(Query::Label(s.into())),
+        }
+    }
+}
+
+/// A set element or a cell of a dataset that matches a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    pub dataset: String,
+    /// Index in each set, in the order of the sets, `None` for the sets the match leaves free
+    pub index: Vec<Option<usize>>,
+    /// Where it matched, e.g. `Area = ON` or `ON, Electric, 2030 = 1520.3`
+    pub description: String,
+}
+
+/// Elements of `set_data` equal to `label`, ignoring case and padding, as (dimension, index).
+pub fn matching_labels(set_data: &[Vec<String>], label: &str) -> Vec<(usize, usize)> {
+    set_data
+        .iter()
+        .enumerate()
+        .flat_map(|(dim, labels)| {
+            labels
+                .iter()
+                .enumerate()
+                .filter(|(_, l)| l.trim().eq_ignore_ascii_case(label.trim()))
+                .map(move |(i, _)| (dim, i))
+        })
+        .collect()
+}
+
+/// Indices of the cells of `values` strictly above or below the value of `query`, in the
+/// order of the dimensions of `values`, at most `limit` of them. Missing values never match.
+pub fn matching_cells(values: ArrayViewD<f64>, query: &Query, limit: usize) -> Vec<Vec<usize>> {
+    let matches = |v: f64| match *query {
+        Query::Above(x) => v > x,
+        Query::Below(x) => v < x,
+        Query::Label(_) => false,
+    };
+    values
+        .indexed_iter()
+        .filter(|(_, &v)| matches(v))
+        .take(limit)
+        .map(|(index, _)| index.slice().to_vec())
+        .collect()
+}
+
+/// Searches `datasets` for `query`, stopping after [`MAX_MATCHES`] matches.
+///
+/// Label searches only read the labels of the sets; value searches read every value of each
+/// dataset. Datasets that cannot be read are skipped.
+pub fn search(datasets: &[Data], query: &Query) -> Vec<Match> {
+    let mut matches = vec![];
+    for d in datasets {
+        let limit = MAX_MATCHES - matches.len();
+        if limit == 0 {
+            break;
+        }
+        match query {
+            Query::Label(label) => {
+                for (dim, i) in matching_labels(&d.set_data, label).into_iter().take(limit) {
+                    let mut index = vec![None; d.ndims];
+                    index[dim] = Some(i);
+                    matches.push(Match {
+                        dataset: d.name.clone(),
+                        index,
+                        description: format!("{} = {}", d.set_names[dim], d.set_data[dim][i]),
+                    });
+                }
+            }
+            _ => {
+                let values = match d.read_all() {
+                    Ok(values) => values,
+                    Err(e) => {
+                        log::warn!("Not searching {}: {e}", d.name);
+                        continue;
+                    }
+                };
+                for mut index in matching_cells(values.view(), query, limit) {
+                    let value = values[index.as_slice()];
+                    // The file stores the dimensions in the reverse order of the sets
+                    index.reverse();
+                    let labels: Vec<&str> = index
+                        .iter()
+                        .enumerate()
+                        .map(|(dim, &i)| d.set_data[dim][i].trim())
+                        .collect();
+                    matches.push(Match {
+                        dataset: d.name.clone(),
+                        index: index.into_iter().map(Some).collect(),
+                        description: format!("{} = {value}", labels.join(", ")),
+                    });
+                }
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+
+    use super::*;
+
+    #[test]
+    fn test_search() {
+        assert_eq!(" >1e6".parse::<Query>().unwrap(), Query::Above(1e6));
+        assert_eq!("<-2".parse::<Query>().unwrap(), Query::Below(-2.0));
+        assert_eq!("ON".parse::<Query>().unwrap(), Query::Label("ON".into()));
+        assert!(">a lot".parse::<Query>().is_err());
+        assert!("".parse::<Query>().is_err());
+        let sets = vec![
+            vec!["ON  ".to_string(), "QC".to_string()],
+            vec!["on".to_string()],
+        ];
+        assert_eq!(matching_labels(&sets, "ON"), vec![(0, 0), (1, 0)]);
+        let values = array![[1.0, 5.0], [f64::NAN, 7.0]].into_dyn();
+        assert_eq!(
+            matching_cells(values.view(), &Query::Above(2.0), 10),
+            vec![vec![0, 1], vec![1, 1]]
+        );
+        assert_eq!(
+            matching_cells(values.view(), &Query::Above(2.0), 1),
+            vec![vec![0, 1]]
+        );
+        assert!(matching_cells(values.view(), &Query::Below(0.0), 10).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{eyre, Result};
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{data::Data, list, units::Conversion, view::ViewDefinition};
+
+/// Largest request head read, in bytes.
+const MAX_REQUEST: usize = 8192;
+
+/// Serves the datasets of `file` as a read-only JSON API on `addr`, until interrupted.
+///
+/// - `GET /datasets`: every dataset, as in `--list-datasets json`
+/// - `GET /dataset?name=routput/Dmd`: metadata and the labels of every set
+/// - `GET /slice?name=routput/Dmd&rows=Area&columns=Year&Fuel=NaturalGas&units=PJ`: a 2-D
+///   slice; every other parameter fixes a set at a label, as in a view file, and sets left out
+///   are at their first element
+///
+/// `units` are converted to with `conversions` or the built-in ones.
+pub async fn serve(file: PathBuf, addr: &str, conversions: Vec<Conversion>) -> Result<()> {
+    let listener = TcpListener::bind(addr).await?;
+    println!(
+        "Serving {} on http://{}",
+        file.display(),
+        listener.local_addr()?
+    );
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let (file, conversions) = (file.clone(), conversions.clone());
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, file, conversions).await {
+                log::warn!("Request from {peer} failed: {e}");
+            }
+        });
+    }
+}
+
+async fn handle(mut stream: TcpStream, file: PathBuf, conversions: Vec<Conversion>) -> Result<()> {
+    let mut buffer = vec![0; MAX_REQUEST];
+    let mut len = 0;
+    while !buffer[..len].windows(4).any(|w| w == b"\r\n\r\n") {
+        if len == buffer.len() {
+            return respond(&mut stream, 431, &json!({"error": "Request too large"})).await;
+        }
+        let n = stream.read(&mut buffer[len..]).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        len += n;
+    }
+    let head = String::from_utf8_lossy(&buffer[..len]);
+    let mut parts = head.lines().next().unwrap_or_default().split_whitespace();
+    let (method, target) = (
+        parts.next().unwrap_or_default(),
+        parts.next().unwrap_or("/"),
+    );
+    log::info!("{method} {target}");
+    if method != "GET" {
+        return respond(&mut stream, 405, &json!({"error": "Only GET is supported"})).await;
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let (path, query) = (path.to_string(), parse_query(query));
+    // HDF5 reads block
+    let result =
+        tokio::task::spawn_blocking(move || route(&file, &path, query, &conversions)).await?;
+    match result {
+        Ok(Some(body)) => respond(&mut stream, 200, &body).await,
+        Ok(None) => respond(&mut stream, 404, &json!({"error": "Not found"})).await,
+        Err(e) => respond(&mut stream, 400, &json!({"error": e.to_string()})).await,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: u16, body: &Value) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Request Header Fields Too Large",
+    };
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    Ok(())
+}
+
+/// Body of the response to `path`, or `None` if there is no such endpoint.
+fn route(
+    file: &Path,
+    path: &str,
+    mut query: BTreeMap<String, String>,
+    conversions: &[Conversion],
+) -> Result<Option<Value>> {
+    let mut take = |key: &str| {
+        query
+            .remove(key)
+            .ok_or_else(|| eyre!("Missing query parameter {key:?}"))
+    };
+    Ok(Some(match path.trim_end_matches('/') {
+        "/datasets" => serde_json::from_str(&list::to_json(&list::datasets(file)?))?,
+        "/dataset" => {
+            let d = Data::new(file.into(), take("name")?)?;
+            let dims: Vec<Value> = d
+                .set_names
+                .iter()
+                .zip(d.set_data.iter())
+                .map(|(name, labels)| json!({"name": name, "labels": labels}))
+                .collect();
+            json!({"name": d.name, "units": d.units, "doc": d.doc, "dims": dims})
+        }
+        "/slice" => {
+            let view = ViewDefinition {
+                dataset: take("name")?,
+                rows: take("rows")?,
+                columns: take("columns")?,
+                units: query.remove("units"),
+                fixed: query,
+                ..ViewDefinition::default()
+            };
+            slice(file, &view, conversions)?
+        }
+        _ => return Ok(None),
+    }))
+}
+
+/// The whole 2-D slice described by `view`, as row-major values with their labels.
+fn slice(file: &Path, view: &ViewDefinition, conversions: &[Conversion]) -> Result<Value> {
+    let d = Data::new(file.into(), view.dataset.clone())?;
+    let resolved = view.resolve(&d, conversions)?;
+    let index: Vec<usize> = resolved
+        .active_index
+        .iter()
+        .map(|i| i.unwrap_or_default())
+        .collect();
+    let (rows, columns) = (&d.set_data[resolved.axis1], &d.set_data[resolved.axis0]);
+    let data = d.read_slice(
+        resolved.axis0,
+        resolved.axis1,
+        &index,
+        0..rows.len(),
+        0..columns.len(),
+    )?;
+    let (factor, units) = match resolved.unit_scale {
+        Some(ref c) => (c.factor, c.apply(&d.units).unwrap_or_default()),
+        None => (1.0, d.units.clone()),
+    };
+    let values: Vec<Vec<f64>> = (0..rows.len())
+        .map(|r| (0..columns.len()).map(|c| data[[c, r]] * factor).collect())
+        .collect();
+    let fixed: BTreeMap<&String, &String> = (0..d.ndims)
+        .filter(|&i| i != resolved.axis0 && i != resolved.axis1)
+        .map(|i| (&d.set_names[i], &d.set_data[i][index[i]]))
+        .collect();
+    Ok(json!({
+        "dataset": d.name,
+        "units": units,
+        "fixed": fixed,
+        "rows": rows,
+        "columns": columns,
+        "values": values,
+    }))
+}
+
+/// Decodes `a=1&b=x%2Fy` into its parameters.
+fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (key, value) = p.split_once('=').unwrap_or((p, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(b) => {
+                        decoded.push(b);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query = parse_query("name=routput%2FDmd&rows=Area&Fuel=Natural+Gas&x=100%");
+        assert_eq!(query["name"], "routput/Dmd");
+        assert_eq!(query["Fuel"], "Natural Gas");
+        assert_eq!(query["x"], "100%");
+    }
+}
@@ -0,0 +1,115 @@
+use color_eyre::eyre::Result;
+use ndarray::{ArrayViewD, Axis};
+
+use crate::data::Data;
+
+/// The members of a set whose cells are all zero or missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmptyMembers {
+    pub set: String,
+    /// Number of members of the set
+    pub members: usize,
+    pub empty: Vec<String>,
+}
+
+/// How much of a dataset holds no data: its zero and missing cells, and the members of its sets
+/// with nothing else, e.g. a fuel never used or an area never populated.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub cells: usize,
+    pub zeros: usize,
+    pub missing: usize,
+    /// One entry per set, in the order of the sets
+    pub sets: Vec<EmptyMembers>,
+}
+
+fn is_empty(v: f64) -> bool {
+    v == 0.0 || v.is_nan()
+}
+
+impl Report {
+    /// Counts the empty cells of `values`, stored in the order of the file's dimensions, the
+    /// reverse of `set_names` and `set_data`.
+    pub fn of(values: ArrayViewD<f64>, set_names: &[String], set_data: &[Vec<String>]) -> Self {
+        let ndims = set_names.len();
+        let sets = set_names
+            .iter()
+            .zip(set_data)
+            .enumerate()
+            .map(|(dim, (set, labels))| EmptyMembers {
+                set: set.clone(),
+                members: labels.len(),
+                empty: labels
+                    .iter()
+                    .zip(values.axis_iter(Axis(ndims - 1 - dim)))
+                    .filter(|(_, cells)| cells.iter().all(|&v| is_empty(v)))
+                    .map(|(label, _)| label.clone())
+                    .collect(),
+            })
+            .collect();
+        Self {
+            cells: values.len(),
+            zeros: values.iter().filter(|&&v| v == 0.0).count(),
+            missing: values.iter().filter(|v| v.is_nan()).count(),
+            sets,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let share = |n: usize| n as f64 / self.cells.max(1) as f64 * 100.0;
+        let mut text = format!(
+            "{} cells: {} zero ({:.1}%), {} missing ({:.1}%), {} with data ({:.1}%)\n",
+            self.cells,
+            self.zeros,
+            share(self.zeros),
+            self.missing,
+            share(self.missing),
+            self.cells - self.zeros - self.missing,
+            share(self.cells - self.zeros - self.missing),
+        );
+        text.push_str("\nMembers with every cell zero or missing:\n");
+        for s in &self.sets {
+            let empty = if s.empty.is_empty() {
+                "none".into()
+            } else {
+                s.empty.join(", ")
+            };
+            text.push_str(&format!(
+                "{} ({} of {}): {empty}\n",
+                s.set,
+                s.empty.len(),
+                s.members
+            ));
+        }
+        text
+    }
+}
+
+/// Reads the whole of `d` and reports its empty cells and members.
+pub fn sparsity(d: &Data) -> Result<Report> {
+    let values = d.read_all()?;
+    Ok(Report::of(values.view(), &d.set_names, &d.set_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::arr2;
+
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        // Years as the first dimension of the file, areas as the second
+        let values = arr2(&[[0.0, 1.0, f64::NAN], [0.0, 2.0, 0.0]]).into_dyn();
+        let sets = vec!["Area".to_string(), "Year".to_string()];
+        let labels = vec![
+            vec!["ON".to_string(), "QC".into(), "NU".into()],
+            vec!["2030".to_string(), "2050".into()],
+        ];
+        let report = Report::of(values.view(), &sets, &labels);
+        assert_eq!((report.cells, report.zeros, report.missing), (6, 3, 1));
+        assert_eq!(report.sets[0].empty, vec!["ON", "NU"]);
+        assert!(report.sets[1].empty.is_empty());
+        assert!(report.to_text().starts_with("6 cells: 3 zero (50.0%)"));
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result};
+use ndarray::{ArrayViewD, Axis};
+
+use crate::compute;
+
+/// Sum of the values that are not NaN, or NaN if every value is.
+pub fn nansum<'a>(values: impl IntoIterator<Item = &'a f64>) -> f64 {
+    values
+        .into_iter()
+        .filter(|v| !v.is_nan())
+        .fold(f64::NAN, |sum, &v| if sum.is_nan() { v } else { sum + v })
+}
+
+/// Mean of the values that are not NaN, or NaN if every value is.
+pub fn nanmean<'a>(values: impl IntoIterator<Item = &'a f64>) -> f64 {
+    let (count, sum) = values
+        .into_iter()
+        .filter(|v| !v.is_nan())
+        .fold((0, 0.0), |(count, sum), &v| (count + 1, sum + v));
+    sum / count as f64
+}
+
+/// Counts of the values that are not NaN in `bins` bins of equal width from their minimum to
+/// their maximum, as (start, end, count). The last bin includes its end.
+pub fn histogram<'a>(
+    values: impl IntoIterator<Item = &'a f64>,
+    bins: usize,
+) -> Vec<(f64, f64, usize)> {
+    let values: Vec<f64> = values
+        .into_iter()
+        .copied()
+        .filter(|v| !v.is_nan())
+        .collect();
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    if values.is_empty() || bins == 0 {
+        return vec![];
+    }
+    // Every value is the same
+    if max == min {
+        return vec![(min, max, values.len())];
+    }
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0; bins];
+    for v in values {
+        counts[(((v - min) / width) as usize).min(bins - 1)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (min + i as f64 * width, min + (i + 1) as f64 * width, count))
+        .collect()
+}
+
+/// Summary statistics of a set of values. NaN values are left out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub mean: f64,
+    /// Population standard deviation
+    pub std: f64,
+}
+
+impl Stats {
+    pub fn of(values: ArrayViewD<f64>) -> Self {
+        let (count, min, max, sum) = values.iter().filter(|v| !v.is_nan()).fold(
+            (0, f64::INFINITY, f64::NEG_INFINITY, 0.0),
+            |(count, min, max, sum), &v| (count + 1, min.min(v), max.max(v), sum + v),
+        );
+        if count == 0 {
+            return Self {
+                count,
+                min: f64::NAN,
+                max: f64::NAN,
+                sum: 0.0,
+                mean: f64::NAN,
+                std: f64::NAN,
+            };
+        }
+        let mean = sum / count as f64;
+        let squares: f64 = values
+            .iter()
+            .filter(|v| !v.is_nan())
+            .map(|v| (v - mean).powi(2))
+            .sum();
+        Self {
+            count,
+            min,
+            max,
+            sum,
+            mean,
+            std: (squares / count as f64).sqrt(),
+        }
+    }
+}
+
+/// Statistics of the whole dataset `name`, or of each element of the dimension `by`, labelled
+/// with the element.
+pub fn stats(file: &Path, name: &str, by: Option<&str>) -> Result<Vec<(String, Stats)>> {
+    let d = compute::open(file, name)?;
+    let values = d.read_all()?;
+    let Some(by) = by else {
+        return Ok(vec![("All".into(), Stats::of(values.view()))]);
+    };
+    let dim = d
+        .set_names
+        .iter()
+        .position(|s| s.eq_ignore_ascii_case(by))
+        .ok_or_else(|| {
+            eyre!(
+                "{name} has no dimension {by:?}, it has {}",
+                d.set_names.join(", ")
+            )
+        })?;
+    // The file stores dimensions in the reverse order of the sets
+    let axis = Axis(d.ndims - 1 - dim);
+    Ok(d.set_data[dim]
+        .iter()
+        .zip(values.axis_iter(axis))
+        .map(|(label, values)| (label.clone(), Stats::of(values)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use ndarray::{arr2, Axis};
+
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let values = arr2(&[[1.0, f64::NAN], [3.0, -2.0]]).into_dyn();
+        let s = Stats::of(values.view());
+        assert_eq!((s.count, s.min, s.max, s.sum), (3, -2.0, 3.0, 2.0));
+        let rows: Vec<Stats> = values.axis_iter(Axis(0)).map(Stats::of).collect();
+        assert_eq!(rows[0].mean, 1.0);
+        assert_eq!(rows[1].mean, 0.5);
+        assert_eq!(rows[1].std, 2.5);
+        assert_eq!(nansum(&values), 2.0);
+        assert_eq!(nanmean(&values), 2.0 / 3.0);
+        assert!(nansum(&[f64::NAN]).is_nan());
+        let counts: Vec<usize> = histogram(&[0.0, 1.0, 2.5, f64::NAN, 4.0], 2)
+            .into_iter()
+            .map(|(_, _, count)| count)
+            .collect();
+        assert_eq!(counts, vec![2, 2]);
+        assert_eq!(histogram(&[3.0, 3.0], 5), vec![(3.0, 3.0, 2)]);
+    }
+}
@@ -0,0 +1,197 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio_util::sync::CancellationToken;
+
+/// Frames of the spinner shown while tasks run, one every 100 ms.
+pub const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Frame of the spinner after running for `elapsed`.
+pub fn spinner(elapsed: Duration) -> &'static str {
+    SPINNER[(elapsed.as_millis() / 100) as usize % SPINNER.len()]
+}
+
+#[derive(Debug)]
+struct State {
+    id: usize,
+    label: String,
+    done: AtomicUsize,
+    total: AtomicUsize,
+    token: CancellationToken,
+    started: Instant,
+}
+
+/// Where a running task is, as shown to the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub id: usize,
+    pub label: String,
+    pub done: usize,
+    /// Number of steps, zero if unknown
+    pub total: usize,
+    /// Whether it was asked to stop but has not yet
+    pub cancelled: bool,
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Steps done out of the total, as a percentage when there are many, e.g. cells written.
+    pub fn text(&self) -> String {
+        match self.total {
+            0 => String::default(),
+            total if total > 10_000 => format!("{}%", self.done * 100 / total),
+            total => format!("{}/{total}", self.done),
+        }
+    }
+}
+
+/// Handle on a registered task, held by the code doing the work. The task is listed until the
+/// handle is dropped.
+#[derive(Debug)]
+pub struct Task {
+    state: Arc<State>,
+    tasks: Tasks,
+}
+
+impl Task {
+    pub fn id(&self) -> usize {
+        self.state.id
+    }
+
+    /// Counts one more step done.
+    pub fn advance(&self) {
+        self.advance_by(1);
+    }
+
+    pub fn advance_by(&self, steps: usize) {
+        self.state.done.fetch_add(steps, Ordering::SeqCst);
+    }
+
+    pub fn set_total(&self, total: usize) {
+        self.state.total.store(total, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.state.token.is_cancelled()
+    }
+
+    /// Token cancelled along with the task, for async code to select on.
+    pub fn token(&self) -> CancellationToken {
+        self.state.token.clone()
+    }
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.tasks
+            .running
+            .lock()
+            .unwrap()
+            .retain(|s| s.id != self.state.id);
+    }
+}
+
+/// Registry of the operations running in the background, e.g. scanning the file or exporting
+/// datasets, shared by the components that start them and the App that shows them.
+#[derive(Debug, Default, Clone)]
+pub struct Tasks {
+    running: Arc<Mutex<Vec<Arc<State>>>>,
+    next_id: Arc<AtomicUsize>,
+}
+
+impl Tasks {
+    /// Registers a task of `total` steps, zero if unknown.
+    pub fn start(&self, label: impl Into<String>, total: usize) -> Task {
+        let state = Arc::new(State {
+            id: self.next_id.fetch_add(1, Ordering::SeqCst),
+            label: label.into(),
+            done: AtomicUsize::new(0),
+            total: AtomicUsize::new(total),
+            token: CancellationToken::new(),
+            started: Instant::now(),
+        });
+        self.running.lock().unwrap().push(state.clone());
+        Task {
+            state,
+            tasks: self.clone(),
+        }
+    }
+
+    /// Running tasks, oldest first.
+    pub fn progress(&self) -> Vec<Progress> {
+        self.running
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| Progress {
+                id: s.id,
+                label: s.label.clone(),
+                done: s.done.load(Ordering::SeqCst),
+                total: s.total.load(Ordering::SeqCst),
+                cancelled: s.token.is_cancelled(),
+                elapsed: s.started.elapsed(),
+            })
+            .collect()
+    }
+
+    pub fn busy(&self) -> bool {
+        !self.running.lock().unwrap().is_empty()
+    }
+
+    /// Whether task `id` runs and was not cancelled.
+    pub fn is_running(&self, id: usize) -> bool {
+        self.running
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|s| s.id == id && !s.token.is_cancelled())
+    }
+
+    /// Asks task `id` to stop, returning whether it was running.
+    pub fn cancel(&self, id: usize) -> bool {
+        let running = self.is_running(id);
+        if let Some(s) = self.running.lock().unwrap().iter().find(|s| s.id == id) {
+            s.token.cancel();
+        }
+        running
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tasks() {
+        let tasks = Tasks::default();
+        let scan = tasks.start("Scanning", 0);
+        let export = tasks.start("Exporting", 3);
+        scan.set_total(10);
+        scan.advance();
+        export.advance_by(2);
+        let progress = tasks.progress();
+        assert_eq!(
+            progress
+                .iter()
+                .map(|p| (p.label.as_str(), p.done, p.total))
+                .collect::<Vec<_>>(),
+            vec![("Scanning", 1, 10), ("Exporting", 2, 3)]
+        );
+        assert_eq!(progress[1].text(), "2/3");
+        export.set_total(40_000);
+        assert_eq!(tasks.progress()[1].text(), "0%");
+        assert!(tasks.cancel(scan.id()));
+        assert!(scan.is_cancelled() && !export.is_cancelled());
+        assert!(!tasks.is_running(scan.id()));
+        assert!(!tasks.cancel(scan.id()));
+        drop(scan);
+        drop(export);
+        assert!(!tasks.busy());
+        assert_eq!(spinner(Duration::from_millis(1050)), SPINNER[0]);
+    }
+}
@@ -6,17 +6,24 @@ use std::{
 use color_eyre::eyre::Result;
 use crossterm::{
     cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
     event::{Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent},
+    style::{Attribute, Color as CColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{FutureExt, StreamExt};
-use ratatui::backend::CrosstermBackend as Backend;
+use ratatui::{
+    backend::CrosstermBackend as Backend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
 use serde_derive::{Deserialize, Serialize};
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
     task::JoinHandle,
 };
 use tokio_util::sync::CancellationToken;
+use unicode_width::UnicodeWidthStr;
 
 pub type Frame<'a> = ratatui::Frame<'a>;
 
@@ -99,6 +106,56 @@ pub fn key_event_to_string(key_event: &KeyEvent) -> String {
     key
 }
 
+/// The text of a drawn frame, one line per row without trailing spaces, with the colours and
+/// modifiers as ANSI escape codes if `ansi`.
+pub fn buffer_text(buffer: &Buffer, ansi: bool) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut line = String::new();
+        let mut style = None;
+        // Cells hidden by the wide character before them
+        let mut hidden = 0;
+        for x in area.left()..area.right() {
+            let cell = buffer.get(x, y);
+            if hidden > 0 {
+                hidden -= 1;
+                continue;
+            }
+            hidden = cell.symbol().width().saturating_sub(1);
+            if ansi && style != Some((cell.fg, cell.bg, cell.modifier)) {
+                style = Some((cell.fg, cell.bg, cell.modifier));
+                line.push_str(&format!(
+                    "{}{}{}",
+                    SetAttribute(Attribute::Reset),
+                    SetForegroundColor(CColor::from(cell.fg)),
+                    SetBackgroundColor(CColor::from(cell.bg))
+                ));
+                for (modifier, attribute) in [
+                    (Modifier::BOLD, Attribute::Bold),
+                    (Modifier::DIM, Attribute::Dim),
+                    (Modifier::ITALIC, Attribute::Italic),
+                    (Modifier::UNDERLINED, Attribute::Underlined),
+                    (Modifier::REVERSED, Attribute::Reverse),
+                ] {
+                    if cell.modifier.contains(modifier) {
+                        line.push_str(&SetAttribute(attribute).to_string());
+                    }
+                }
+            }
+            line.push_str(cell.symbol());
+        }
+        if ansi {
+            line.push_str(&SetAttribute(Attribute::Reset).to_string());
+        } else {
+            line.truncate(line.trim_end().len());
+        }
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
 pub struct Tui {
     pub terminal: ratatui::Terminal<Backend<std::io::Stderr>>,
     pub task: JoinHandle<()>,
@@ -107,6 +164,8 @@ pub struct Tui {
     pub event_tx: UnboundedSender<Event>,
     pub frame_rate: f64,
     pub tick_rate: f64,
+    /// Whether mouse events are captured on entering, instead of selecting text
+    pub mouse: bool,
 }
 
 impl Tui {
@@ -125,6 +184,7 @@ impl Tui {
             event_tx,
             frame_rate,
             tick_rate,
+            mouse: false,
         })
     }
 
@@ -136,7 +196,26 @@ impl Tui {
         self.frame_rate = frame_rate;
     }
 
+    pub fn mouse(&mut self, mouse: bool) {
+        self.mouse = mouse;
+    }
+
+    /// Changes the rates of a running event loop, which restarts without a new [`Event::Init`].
+    pub fn set_rates(&mut self, tick_rate: f64, frame_rate: f64) -> Result<()> {
+        self.tick_rate(tick_rate);
+        self.frame_rate(frame_rate);
+        if !self.task.is_finished() {
+            self.stop()?;
+            self.spawn(false);
+        }
+        Ok(())
+    }
+
     pub fn start(&mut self) {
+        self.spawn(true);
+    }
+
+    fn spawn(&mut self, init: bool) {
         let tick_delay = std::time::Duration::from_secs_f64(1.0 / self.tick_rate);
         let render_delay = std::time::Duration::from_secs_f64(1.0 / self.frame_rate);
         self.cancel();
@@ -147,7 +226,9 @@ impl Tui {
             let mut reader = crossterm::event::EventStream::new();
             let mut tick_interval = tokio::time::interval(tick_delay);
             let mut render_interval = tokio::time::interval(render_delay);
-            _event_tx.send(Event::Init).unwrap();
+            if init {
+                _event_tx.send(Event::Init).unwrap();
+            }
             loop {
                 let tick_delay = tick_interval.tick();
                 let render_delay = render_interval.tick();
@@ -219,6 +300,9 @@ impl Tui {
     pub fn enter(&mut self) -> Result<()> {
         crossterm::terminal::enable_raw_mode()?;
         crossterm::execute!(std::io::stderr(), EnterAlternateScreen, cursor::Hide)?;
+        if self.mouse {
+            crossterm::execute!(std::io::stderr(), EnableMouseCapture)?;
+        }
         self.start();
         Ok(())
     }
@@ -227,7 +311,12 @@ impl Tui {
         self.stop()?;
         if crossterm::terminal::is_raw_mode_enabled()? {
             self.flush()?;
-            crossterm::execute!(std::io::stderr(), LeaveAlternateScreen, cursor::Show)?;
+            crossterm::execute!(
+                std::io::stderr(),
+                DisableMouseCapture,
+                LeaveAlternateScreen,
+                cursor::Show
+            )?;
             crossterm::terminal::disable_raw_mode()?;
         }
         Ok(())
@@ -0,0 +1,95 @@
+use serde_derive::{Deserialize, Serialize};
+
+/// A multiplicative conversion from one unit to another, e.g.
+/// `{ "from": "PJ", "to": "TWh", "factor": 0.2778 }` in the config.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Conversion {
+    pub from: String,
+    pub to: String,
+    pub factor: f64,
+}
+
+impl Conversion {
+    pub fn new(from: &str, to: &str, factor: f64) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            factor,
+        }
+    }
+
+    /// Returns the converted unit label if this conversion applies to `units`.
+    ///
+    /// A conversion applies when `units` is exactly `from` or `from` followed by a
+    /// per-something suffix, e.g. `PJ/Yr` converts to `TWh/Yr`.
+    pub fn apply(&self, units: &str) -> Option<String> {
+        let units = units.trim();
+        if units == self.from {
+            Some(self.to.clone())
+        } else {
+            units
+                .strip_prefix(&self.from)
+                .filter(|rest| rest.starts_with('/'))
+                .map(|rest| format!("{}{rest}", self.to))
+        }
+    }
+}
+
+/// Common energy and emissions unit conversions.
+pub fn registry() -> Vec<Conversion> {
+    vec![
+        Conversion::new("GJ", "TJ", 1e-3),
+        Conversion::new("TJ", "PJ", 1e-3),
+        Conversion::new("PJ", "TJ", 1e3),
+        Conversion::new("TJ", "GWh", 1.0 / 3.6),
+        Conversion::new("PJ", "TWh", 1.0 / 3.6),
+        Conversion::new("TWh", "PJ", 3.6),
+        Conversion::new("TBtu", "PJ", 1.055_056),
+        Conversion::new("MWh", "GWh", 1e-3),
+        Conversion::new("GWh", "TWh", 1e-3),
+        Conversion::new("GWh", "TJ", 3.6),
+        Conversion::new("MW", "GW", 1e-3),
+        Conversion::new("Tonnes", "kt", 1e-3),
+        Conversion::new("kt", "Mt", 1e-3),
+        Conversion::new("Mt", "kt", 1e3),
+        Conversion::new("$", "k$", 1e-3),
+        Conversion::new("k$", "$", 1e3),
+        Conversion::new("k$", "M$", 1e-3),
+        Conversion::new("M$", "k$", 1e3),
+    ]
+}
+
+/// Conversions that apply to `units`: those of `custom` first, e.g. from the config, then those
+/// of the registry between other units.
+pub fn conversions_for(units: &str, custom: &[Conversion]) -> Vec<Conversion> {
+    let builtin = registry()
+        .into_iter()
+        .filter(|c| !custom.iter().any(|x| x.from == c.from && x.to == c.to));
+    custom
+        .iter()
+        .cloned()
+        .chain(builtin)
+        .filter(|c| c.apply(units).is_some())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let c = Conversion::new("PJ", "TWh", 1.0 / 3.6);
+        assert_eq!(c.apply("PJ"), Some("TWh".into()));
+        assert_eq!(c.apply("PJ/Yr"), Some("TWh/Yr".into()));
+        assert_eq!(c.apply("PJs"), None);
+        assert_eq!(conversions_for("TJ/Yr", &[]).len(), 2);
+        let custom = [
+            Conversion::new("TJ", "PJ", 0.001),
+            Conversion::new("TJ", "EJ", 1e-6),
+        ];
+        let conversions = conversions_for("TJ/Yr", &custom);
+        assert_eq!(conversions.len(), 3);
+        assert_eq!(conversions[0], custom[0]);
+    }
+}
@@ -8,8 +8,9 @@ use tracing_error::ErrorLayer;
 use tracing_subscriber::{
     self, prelude::__tracing_subscriber_SubscriberExt, util::SubscriberInitExt, Layer,
 };
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::tui::Tui;
+use crate::{logs::LOGS, tui::Tui};
 
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
@@ -111,8 +112,15 @@ pub fn initialize_logging() -> Result<()> {
         .with_target(false)
         .with_ansi(false)
         .with_filter(tracing_subscriber::filter::EnvFilter::from_default_env());
+    // Also kept in memory, for the log popup
+    let buffer_subscriber = tracing_subscriber::fmt::layer()
+        .with_writer(LOGS.clone())
+        .with_target(false)
+        .with_ansi(false)
+        .with_filter(tracing_subscriber::filter::EnvFilter::from_default_env());
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(buffer_subscriber)
         .with(ErrorLayer::default())
         .init();
     Ok(())
@@ -144,6 +152,193 @@ macro_rules! trace_dbg {
     };
 }
 
+/// Shortens `s` to at most `width` columns, replacing the cut tail with an ellipsis.
+pub fn ellipsize(s: &str, width: usize) -> String {
+    if s.width() <= width {
+        return s.to_string();
+    }
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = c.width().unwrap_or_default();
+        if used + w + 1 > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if width > 0 {
+        out.push('…');
+    }
+    out
+}
+
+/// Formats a number of bytes with binary units, e.g. `12.4 MiB`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Quotes `field` for a CSV file if it contains a separator, quote or line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.into()
+    }
+}
+
+/// Indices of the `candidates` that `query` matches, ignoring case, best first: equal, then
+/// starting with it, containing it, and holding its characters in order, e.g. `y40` for `Y2040`.
+pub fn fuzzy_matches(query: &str, candidates: &[String]) -> Vec<usize> {
+    let query = query.to_lowercase();
+    let rank = |candidate: &str| {
+        let candidate = candidate.to_lowercase();
+        if candidate == query {
+            Some(0)
+        } else if candidate.starts_with(&query) {
+            Some(1)
+        } else if candidate.contains(&query) {
+            Some(2)
+        } else {
+            let mut chars = candidate.chars();
+            query.chars().all(|q| chars.any(|c| c == q)).then_some(3)
+        }
+    };
+    let mut matches: Vec<(usize, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| Some((rank(c)?, i)))
+        .collect();
+    matches.sort();
+    matches.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Byte ranges of `text` holding any of `words`, ignoring case, merged where they overlap and in
+/// order. Empty if `text` changes length once lowercased, as a few non-ASCII characters do.
+pub fn match_ranges(text: &str, words: &[&str]) -> Vec<std::ops::Range<usize>> {
+    let lower = text.to_lowercase();
+    if lower.len() != text.len() {
+        return Vec::default();
+    }
+    let mut ranges: Vec<std::ops::Range<usize>> = words
+        .iter()
+        .filter(|w| !w.is_empty())
+        .flat_map(|w| {
+            let w = w.to_lowercase();
+            lower
+                .match_indices(&w)
+                .map(|(i, m)| i..i + m.len())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ranges.sort_by_key(|r| r.start);
+    let mut merged: Vec<std::ops::Range<usize>> = Vec::new();
+    for r in ranges {
+        match merged.last_mut() {
+            Some(last) if r.start <= last.end => last.end = last.end.max(r.end),
+            _ => merged.push(r),
+        }
+    }
+    merged
+}
+
+/// Copies `text` to the system clipboard through the terminal, with an OSC 52 sequence.
+///
+/// Works over SSH, in terminals that support it.
+pub fn copy_to_clipboard(text: &str) -> Result<()> {
+    use std::io::Write;
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(text.len().div_ceil(3) * 4);
+    for chunk in text.as_bytes().chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    let mut stderr = std::io::stderr();
+    write!(stderr, "\x1b]52;c;{encoded}\x07")?;
+    stderr.flush()?;
+    Ok(())
+}
+
+/// Runs `args` with `input` on stdin and waits for it to exit.
+pub fn run_command(
+    args: &[String],
+    env: &[(&str, String)],
+    input: String,
+) -> Result<std::process::Output> {
+    use std::{
+        io::Write,
+        process::{Command, Stdio},
+    };
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take();
+    // Written from another thread so a command that does not read all of it cannot block us
+    let writer = std::thread::spawn(move || {
+        if let Some(ref mut stdin) = stdin {
+            stdin.write_all(input.as_bytes()).unwrap_or_default();
+        }
+    });
+    let output = child.wait_with_output()?;
+    writer.join().unwrap_or_default();
+    Ok(output)
+}
+
+/// Runs `args` like [`run_command`], but without blocking a thread, for use with
+/// `tokio::time::timeout`: the command is killed if the future is dropped before it exits.
+pub async fn run_command_async(
+    args: &[String],
+    env: &[(&str, String)],
+    input: String,
+) -> Result<std::process::Output> {
+    use std::process::Stdio;
+    use tokio::{io::AsyncWriteExt, process::Command};
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut stdin = child.stdin.take();
+    // Written from another task so a command that does not read all of it cannot block us
+    let writer = tokio::spawn(async move {
+        if let Some(ref mut stdin) = stdin {
+            stdin.write_all(input.as_bytes()).await.unwrap_or_default();
+        }
+    });
+    let output = child.wait_with_output().await?;
+    writer.await.unwrap_or_default();
+    Ok(output)
+}
+
 pub fn version() -> String {
     let author = clap::crate_authors!();
 
@@ -163,3 +358,38 @@ Config directory: {config_dir_path}
 Data directory: {data_dir_path}"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_matches() {
+        let candidates: Vec<String> = ["2050", "Y2040", "2040", "1940", "2004"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(fuzzy_matches("2040", &candidates), vec![2, 1]);
+        assert_eq!(fuzzy_matches("y", &candidates), vec![1]);
+        assert_eq!(fuzzy_matches("40", &candidates), vec![1, 2, 3]);
+        assert_eq!(fuzzy_matches("204", &candidates), vec![2, 1, 4]);
+        assert!(fuzzy_matches("2060", &candidates).is_empty());
+    }
+
+    #[test]
+    fn test_human_size() {
+        assert_eq!(human_size(512), "512 B");
+        assert_eq!(human_size(1536), "1.5 KiB");
+        assert_eq!(human_size(13_002_342), "12.4 MiB");
+    }
+
+    #[test]
+    fn test_match_ranges() {
+        assert_eq!(
+            match_ranges("'/ooutput/Dmd'", &["dmd", "out"]),
+            vec![3..6, 10..13]
+        );
+        assert_eq!(match_ranges("abcabc", &["bc", "ca"]), vec![1..6]);
+        assert!(match_ranges("Demand", &["fuel"]).is_empty());
+    }
+}
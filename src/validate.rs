@@ -0,0 +1,177 @@
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+use hdf5::types::{FixedUnicode, TypeDescriptor, VarLenUnicode};
+
+use crate::data;
+
+/// A dataset that breaks a convention of E2020 databases.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub path: String,
+    pub message: String,
+}
+
+/// Summary of the validation of a whole file.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Number of variables validated, leaving out the sets
+    pub variables: usize,
+    pub violations: Vec<Violation>,
+}
+
+/// What a variable declares about itself, as far as it could be read.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Variable {
+    /// Shape in the order of the file, the reverse of that of the sets
+    pub shape: Vec<usize>,
+    /// Names of its sets, from the `dims` attribute
+    pub dims: Option<Vec<String>>,
+    pub units: Option<String>,
+    /// Number of labels of each set of `dims` in the group of the variable, if it exists
+    pub set_lengths: Vec<Option<usize>>,
+}
+
+/// Conventions `v` breaks: every variable names its sets in a `dims` attribute, each set
+/// exists in its group and has as many labels as the dimension it indexes, and its units are
+/// not empty.
+pub fn violations(v: &Variable) -> Vec<String> {
+    let mut violations = vec![];
+    match v.dims {
+        None => violations.push("no dims attribute".to_string()),
+        Some(ref dims) if dims.len() != v.shape.len() => violations.push(format!(
+            "dims names {} sets for {} dimensions",
+            dims.len(),
+            v.shape.len()
+        )),
+        Some(ref dims) => {
+            for ((set, length), &n) in dims.iter().zip(&v.set_lengths).zip(v.shape.iter().rev()) {
+                match length {
+                    None => violations.push(format!("set {set} is not in the group")),
+                    Some(length) if *length != n => violations.push(format!(
+                        "set {set} has {length} labels for a dimension of {n}"
+                    )),
+                    Some(_) => (),
+                }
+            }
+        }
+    }
+    if v.units.as_deref().is_none_or(|u| u.trim().is_empty()) {
+        violations.push("no units".to_string());
+    }
+    violations
+}
+
+/// Validates every variable of `file`, its numeric datasets, against the conventions of
+/// [`violations`]. Datasets of labels are the sets, and are not validated on their own.
+///
+/// Only failing to open the file itself is an error; unreadable objects are reported as
+/// violations.
+pub fn validate(file: &Path) -> Result<Report> {
+    let f = data::open_file(file)?;
+    let mut report = Report::default();
+    let violation = |path: &str, message: String| Violation {
+        path: path.into(),
+        message,
+    };
+    for group in f.member_names()? {
+        let g = match f.group(&group) {
+            Ok(g) => g,
+            Err(e) => {
+                report.violations.push(violation(&group, e.to_string()));
+                continue;
+            }
+        };
+        let names = match g.member_names() {
+            Ok(names) => names,
+            Err(e) => {
+                report.violations.push(violation(&group, e.to_string()));
+                continue;
+            }
+        };
+        for name in names {
+            let path = format!("{group}/{name}");
+            let dataset = match g.dataset(&name) {
+                Ok(d) => d,
+                Err(e) => {
+                    report.violations.push(violation(&path, e.to_string()));
+                    continue;
+                }
+            };
+            let numeric = dataset
+                .dtype()
+                .and_then(|t| t.to_descriptor())
+                .is_ok_and(|t| {
+                    matches!(
+                        t,
+                        TypeDescriptor::Integer(_)
+                            | TypeDescriptor::Unsigned(_)
+                            | TypeDescriptor::Float(_)
+                    )
+                });
+            if !numeric {
+                continue;
+            }
+            report.variables += 1;
+            let dims: Option<Vec<String>> = dataset
+                .attr("dims")
+                .and_then(|a| a.read_1d::<VarLenUnicode>())
+                .ok()
+                .map(|dims| dims.iter().map(|d| d.to_string()).collect());
+            let set_lengths = dims
+                .iter()
+                .flatten()
+                .map(|set| g.dataset(set).ok().and_then(|s| s.shape().first().copied()))
+                .collect();
+            let units = dataset
+                .attr("units")
+                .and_then(|a| a.as_reader().read_scalar::<FixedUnicode<100>>())
+                .ok()
+                .map(|u| u.to_string());
+            let variable = Variable {
+                shape: dataset.shape(),
+                dims,
+                units,
+                set_lengths,
+            };
+            for message in violations(&variable) {
+                report.violations.push(violation(&path, message));
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violations() {
+        let valid = Variable {
+            shape: vec![3, 2],
+            dims: Some(vec!["Area".into(), "Year".into()]),
+            units: Some("PJ".into()),
+            set_lengths: vec![Some(2), Some(3)],
+        };
+        assert!(violations(&valid).is_empty());
+        let broken = Variable {
+            set_lengths: vec![None, Some(4)],
+            units: Some(" ".into()),
+            ..valid.clone()
+        };
+        assert_eq!(
+            violations(&broken),
+            vec![
+                "set Area is not in the group",
+                "set Year has 4 labels for a dimension of 3",
+                "no units"
+            ]
+        );
+        let no_dims = Variable {
+            dims: None,
+            ..valid
+        };
+        assert_eq!(violations(&no_dims), vec!["no dims attribute"]);
+    }
+}
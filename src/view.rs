@@ -0,0 +1,144 @@
+use std::{collections::BTreeMap, path::Path};
+
+use color_eyre::eyre::{eyre, Result};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    config::Formatting,
+    data::{Aggregation, Data},
+    units::{self, Conversion},
+};
+
+/// A Viewer table described by set names and labels, so it can be shared and reopened on any
+/// file with the same dataset, e.g.
+///
+/// ```json
+/// {
+///   "dataset": "routput/Dmd",
+///   "rows": "Area",
+///   "columns": "Year",
+///   "fixed": { "Fuel": "NaturalGas" },
+///   "units": "PJ"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewDefinition {
+    pub dataset: String,
+    /// Set shown as rows
+    pub rows: String,
+    /// Set shown as columns
+    pub columns: String,
+    /// Label of every other set; sets left out start where the Viewer would put them
+    pub fixed: BTreeMap<String, String>,
+    /// Sets summed or averaged over instead of fixed
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub aggregate: BTreeMap<String, Aggregation>,
+    /// Dataset weighting the sets aggregated as `"weighted"`, with the same sets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weights: Option<String>,
+    /// Unit to convert the values to, e.g. `"PJ"`
+    pub units: Option<String>,
+    pub cumulative: bool,
+    /// Show zeros as dashes and whole numbers without decimals, as read by earlier versions
+    pub formatted: bool,
+    /// Each formatting option on its own, taking precedence over `formatted`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub formatting: Option<Formatting>,
+    /// Rows sorted by descending total instead of file order, if set
+    pub sort_by_total: Option<bool>,
+    /// Number of decimals, if not that of the config
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decimals: Option<usize>,
+}
+
+impl Default for ViewDefinition {
+    fn default() -> Self {
+        Self {
+            dataset: String::default(),
+            rows: String::default(),
+            columns: String::default(),
+            fixed: BTreeMap::default(),
+            aggregate: BTreeMap::default(),
+            weights: None,
+            units: None,
+            cumulative: false,
+            formatted: true,
+            formatting: None,
+            sort_by_total: None,
+            decimals: None,
+        }
+    }
+}
+
+/// A [`ViewDefinition`] resolved against the sets of a dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedView {
+    pub axis0: usize,
+    pub axis1: usize,
+    /// Index of each fixed set, `None` where the definition leaves it out
+    pub active_index: Vec<Option<usize>>,
+    pub aggregations: Vec<Aggregation>,
+    pub unit_scale: Option<Conversion>,
+}
+
+impl ViewDefinition {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| eyre!("Unable to read view {}: {e}", path.display()))?;
+        serde_json::from_str(&text).map_err(|e| eyre!("Invalid view {}: {e}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Positions of the sets and labels of the definition in `d`, failing on any unknown name.
+    ///
+    /// `units` is converted to with one of `conversions` or of the registry.
+    pub fn resolve(&self, d: &Data, conversions: &[Conversion]) -> Result<ResolvedView> {
+        let dim = |name: &str| {
+            d.set_names.iter().position(|s| s == name).ok_or_else(|| {
+                eyre!(
+                    "{} has no dimension {name:?}, it has {}",
+                    d.name,
+                    d.set_names.join(", ")
+                )
+            })
+        };
+        let (axis1, axis0) = (dim(&self.rows)?, dim(&self.columns)?);
+        if axis0 == axis1 {
+            return Err(eyre!("Rows and columns are both {:?}", self.rows));
+        }
+        let mut active_index = vec![None; d.ndims];
+        for (name, label) in self.fixed.iter() {
+            let i = dim(name)?;
+            let index = d.set_data[i]
+                .iter()
+                .position(|l| l == label)
+                .ok_or_else(|| eyre!("{name} has no element {label:?}"))?;
+            active_index[i] = Some(index);
+        }
+        let mut aggregations = vec![Aggregation::Fix; d.ndims];
+        for (name, aggregation) in self.aggregate.iter() {
+            aggregations[dim(name)?] = *aggregation;
+        }
+        let unit_scale = match self.units {
+            Some(ref to) => Some(
+                units::conversions_for(&d.units, conversions)
+                    .into_iter()
+                    .find(|c| c.to == *to)
+                    .ok_or_else(|| eyre!("Unable to convert {} to {to}", d.units))?,
+            ),
+            None => None,
+        };
+        Ok(ResolvedView {
+            axis0,
+            axis1,
+            active_index,
+            aggregations,
+            unit_scale,
+        })
+    }
+}